@@ -0,0 +1,38 @@
+use crate::prelude::*;
+
+/// Create a blob from any number of bytes.
+///
+/// Entries are limited to `ENTRY_SIZE_LIMIT` bytes so larger data is split into chunks of
+/// `BLOB_CHUNK_SIZE` bytes. Each chunk is created as a content addressed `BlobChunk` entry and then
+/// a `BlobManifest` entry lists the chunk hashes in order.
+///
+/// Returns the hash of the manifest, which is the address of the blob for `get_blob`.
+///
+/// The zome must include the blob entry defs for this to validate:
+///
+/// ```ignore
+/// entry_defs![BlobManifest::entry_def(), BlobChunk::entry_def()];
+///
+/// let manifest_hash = create_blob(&image_bytes)?;
+/// ```
+///
+/// @see get_blob
+pub fn create_blob(bytes: &[u8]) -> HdkResult<EntryHash> {
+    let chunks = BlobManifest::chunk(bytes);
+    let mut chunk_hashes = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        chunk_hashes.push(hash_entry(&chunk)?);
+        create(
+            BlobChunk::entry_def_id(),
+            Entry::App(SerializedBytes::try_from(&chunk)?.try_into()?),
+        )?;
+    }
+
+    let manifest = BlobManifest::new(bytes.len() as u64, chunk_hashes);
+    let manifest_hash = hash_entry(&manifest)?;
+    create(
+        BlobManifest::entry_def_id(),
+        Entry::App(SerializedBytes::try_from(&manifest)?.try_into()?),
+    )?;
+    Ok(manifest_hash)
+}
@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// List the CapClaims on the local source chain that haven't been deleted.
+///
+/// Pass `CapQuery::all()` for every live claim or `CapQuery::tagged(tag)` to only list the claims
+/// created with a given tag.
+///
+/// As with `create_cap_claim` there is no guarantee that a listed claim is still honoured by
+/// the grantor.
+///
+/// @see create_cap_claim
+pub fn capability_claims(query: CapQuery) -> HdkResult<Vec<LiveCapClaim>> {
+    Ok(host_call::<CapabilityClaimsInput, CapabilityClaimsOutput>(
+        __capability_claims,
+        &CapabilityClaimsInput::new(query),
+    )?
+    .into_inner())
+}
@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// List the CapGrants on the local source chain that haven't been updated or deleted.
+///
+/// Pass `CapQuery::all()` for every live grant or `CapQuery::tagged(tag)` to only list the grants
+/// created with a given tag.
+///
+/// Each `LiveCapGrant` includes the hash of the header that created it, which is the address
+/// to pass to `update_cap_grant` or `delete_cap_grant` to roll or revoke the grant.
+///
+/// @see create_cap_grant
+pub fn capability_grants(query: CapQuery) -> HdkResult<Vec<LiveCapGrant>> {
+    Ok(host_call::<CapabilityGrantsInput, CapabilityGrantsOutput>(
+        __capability_grants,
+        &CapabilityGrantsInput::new(query),
+    )?
+    .into_inner())
+}
@@ -0,0 +1,19 @@
+use crate::prelude::*;
+
+/// Get the CapGrant that authorized the current zome call, along with the calling agent.
+///
+/// Calls made by the chain author are authorized by `CapGrant::ChainAuthor`, everything else
+/// by the committed grant that matched the caller's provenance and secret.
+///
+/// ```ignore
+/// if let CapGrant::ChainAuthor(_) = capability_info()?.cap_grant {
+///     ...
+/// }
+/// ```
+pub fn capability_info() -> HdkResult<CapGrantInfo> {
+    Ok(host_call::<CapabilityInfoInput, CapabilityInfoOutput>(
+        __capability_info,
+        &CapabilityInfoInput::new(()),
+    )?
+    .into_inner())
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// Get the bytes of a blob by the hash of its manifest.
+///
+/// ```ignore
+/// let manifest_hash = create_blob(&image_bytes)?;
+/// let image_bytes = get_blob(manifest_hash)?;
+/// ```
+///
+/// The host fetches the manifest and then all of its chunks in parallel, checking each chunk is
+/// the right size before the blob is reassembled.
+///
+/// Returns None if the manifest can't be found.
+/// Returns an error if the manifest is found but any of its chunks can't be.
+///
+/// @see create_blob
+pub fn get_blob(manifest_hash: EntryHash) -> HdkResult<Option<Vec<u8>>> {
+    host_externs!(__get_blob);
+    Ok(
+        host_call::<GetBlobInput, GetBlobOutput>(__get_blob, &GetBlobInput::new(manifest_hash))?
+            .into_inner()
+            .map(|bytes| bytes.into_vec()),
+    )
+}
@@ -20,9 +20,39 @@ use crate::prelude::*;
 ///
 /// @see get_link_details
 pub fn get_links(base: EntryHash, link_tag: Option<LinkTag>) -> HdkResult<Links> {
+    get_links_filtered(base, link_tag, LinkFilter::default())
+}
+
+/// Same as `get_links` but only returns the links that pass a `LinkFilter`.
+///
+/// The filter is applied by the authorities for the base so only the matching links are sent
+/// back over the network. Links are returned in order of creation and a filter with a `limit`
+/// returns one page at a time.
+///
+/// ```ignore
+/// let page = get_links_filtered(base.clone(), None, LinkFilter::new().limit(100))?.into_inner();
+/// if let Some(last) = page.last() {
+///     let next_page = get_links_filtered(
+///         base,
+///         None,
+///         LinkFilter::new().limit(100).cursor(last.into()),
+///     )?;
+/// }
+/// ```
+///
+/// The limit counts links before any deletes are applied, so a page can hold fewer than `limit`
+/// links when some of them have been deleted. Keep paging from the last link returned until a
+/// page comes back empty.
+///
+/// @see get_links
+pub fn get_links_filtered(
+    base: EntryHash,
+    link_tag: Option<LinkTag>,
+    filter: LinkFilter,
+) -> HdkResult<Links> {
     Ok(host_call::<GetLinksInput, GetLinksOutput>(
         __get_links,
-        &GetLinksInput::new((base, link_tag)),
+        &GetLinksInput::new((base, link_tag, filter)),
     )?
     .into_inner())
 }
@@ -1 +1,32 @@
-//! @todo
+use crate::prelude::*;
+
+/// Get the DNA properties, or the value at a path inside them, as a user type.
+///
+/// Properties are set in the dna file and can be overridden per network at install time, so they
+/// are how a zome reads the configuration it was installed with.
+///
+/// The path is a `PropertyPath` or anything that converts into one, e.g. a dotted string where
+/// each segment is an object key or an array index. An empty path returns all the properties.
+///
+/// Returns `None` if nothing exists at the path.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize, SerializedBytes)]
+/// struct Limits {
+///     max_posts: u32,
+/// }
+///
+/// // with properties `{ "limits": { "max_posts": 10 } }`
+/// let limits: Option<Limits> = property("limits")?;
+/// ```
+pub fn property<T>(path: impl Into<PropertyPath>) -> HdkResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    Ok(
+        host_call::<PropertyInput, PropertyOutput>(__property, &PropertyInput::new(path.into()))?
+            .into_inner()
+            .map(T::try_from)
+            .transpose()?,
+    )
+}
@@ -1 +1,52 @@
-//! @todo
+use crate::prelude::*;
+
+/// Schedule a function in the current zome to be called back by the host.
+///
+/// The function is called with the agent of the current source chain as the provenance, so it
+/// does not need a cap grant, and receives `payload` as its input.
+///
+/// - fn_name: The name of the function in this zome to call.
+/// - payload: The input for the function; it needs to deserialize cleanly on the other side.
+/// - after: How long to wait before the first call.
+/// - every: If set, the function will keep being called at this interval after the first call.
+///
+/// Schedules are persisted by the host so they survive a conductor restart, but they are only
+/// persisted if the zome call that made them completes successfully.
+/// A schedule made from a function that is itself scheduled is treated like any other, so a
+/// function can reschedule itself with a different delay to back off.
+///
+/// The exact time a function is called is not guaranteed; the host calls it as soon as it can
+/// once it is due, which may be much later if the conductor was not running at the time.
+///
+/// ```ignore
+/// #[hdk_extern]
+/// fn expire_offer(offer: OfferId) -> ExternResult<()> {
+///     ...
+/// }
+///
+/// ...
+/// schedule("expire_offer", &offer_id, Duration::from_secs(60 * 60), None)?;
+/// ...
+/// ```
+pub fn schedule<'a, I: 'a>(
+    fn_name: impl Into<FunctionName>,
+    payload: &'a I,
+    after: core::time::Duration,
+    every: Option<core::time::Duration>,
+) -> HdkResult<()>
+where
+    SerializedBytes: TryFrom<&'a I, Error = SerializedBytesError>,
+{
+    let payload = SerializedBytes::try_from(payload)?;
+    #[allow(clippy::unit_arg)]
+    Ok(host_call::<ScheduleInput, ScheduleOutput>(
+        __schedule,
+        &ScheduleInput::new(Schedule {
+            fn_name: fn_name.into(),
+            payload,
+            after,
+            every,
+        }),
+    )?
+    .into_inner())
+}
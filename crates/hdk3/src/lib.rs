@@ -1,3 +1,4 @@
+pub mod blob;
 pub mod capability;
 pub mod entry;
 pub mod error;
@@ -1,3 +1,7 @@
+pub use crate::blob::create_blob;
+pub use crate::capability::capability_claims::capability_claims;
+pub use crate::capability::capability_grants::capability_grants;
+pub use crate::capability::capability_info::capability_info;
 pub use crate::capability::create_cap_claim::create_cap_claim;
 pub use crate::capability::create_cap_grant::create_cap_grant;
 pub use crate::capability::delete_cap_grant::delete_cap_grant;
@@ -28,14 +32,18 @@ pub use crate::host_fn::delete_link::delete_link;
 pub use crate::host_fn::emit_signal::emit_signal;
 pub use crate::host_fn::get::get;
 pub use crate::host_fn::get_agent_activity::get_agent_activity;
+pub use crate::host_fn::get_blob::get_blob;
 pub use crate::host_fn::get_details::get_details;
 pub use crate::host_fn::get_link_details::get_link_details;
 pub use crate::host_fn::get_links::get_links;
+pub use crate::host_fn::get_links::get_links_filtered;
 pub use crate::host_fn::hash_entry::hash_entry;
+pub use crate::host_fn::property::property;
 pub use crate::host_fn::query::query;
 pub use crate::host_fn::random_bytes::random_bytes;
 pub use crate::host_fn::random_bytes::*;
 pub use crate::host_fn::remote_signal::remote_signal;
+pub use crate::host_fn::schedule::schedule;
 pub use crate::host_fn::sign::sign;
 pub use crate::host_fn::sys_time::sys_time;
 pub use crate::host_fn::update::update;
@@ -44,6 +52,8 @@ pub use crate::host_fn::zome_info::zome_info;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
 pub use crate::x_salsa20_poly1305::create_x25519_keypair::create_x25519_keypair;
+pub use crate::x_salsa20_poly1305::create_x_salsa20_poly1305_key::create_x_salsa20_poly1305_key;
+pub use crate::x_salsa20_poly1305::derive_x_salsa20_poly1305_key::derive_x_salsa20_poly1305_key;
 pub use crate::x_salsa20_poly1305::x_25519_x_salsa20_poly1305_decrypt::x_25519_x_salsa20_poly1305_decrypt;
 pub use crate::x_salsa20_poly1305::x_25519_x_salsa20_poly1305_encrypt::x_25519_x_salsa20_poly1305_encrypt;
 pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_decrypt::x_salsa20_poly1305_decrypt;
@@ -1,4 +1,6 @@
 pub mod create_x25519_keypair;
+pub mod create_x_salsa20_poly1305_key;
+pub mod derive_x_salsa20_poly1305_key;
 pub mod x_25519_x_salsa20_poly1305_decrypt;
 pub mod x_25519_x_salsa20_poly1305_encrypt;
 pub mod x_salsa20_poly1305_decrypt;
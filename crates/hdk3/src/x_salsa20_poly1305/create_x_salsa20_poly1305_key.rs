@@ -0,0 +1,14 @@
+use crate::prelude::*;
+
+/// Generate a new secretbox key in the keystore.
+/// Only an opaque ref to the key is returned because the key never leaves the keystore.
+pub fn create_x_salsa20_poly1305_key() -> HdkResult<XSalsa20Poly1305KeyRef> {
+    host_externs!(__create_x_salsa20_poly1305_key);
+    Ok(
+        host_call::<CreateXSalsa20Poly1305KeyInput, CreateXSalsa20Poly1305KeyOutput>(
+            __create_x_salsa20_poly1305_key,
+            &().into(),
+        )?
+        .into_inner(),
+    )
+}
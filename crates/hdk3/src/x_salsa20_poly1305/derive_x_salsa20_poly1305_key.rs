@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// Derive a secretbox key in the keystore from an existing key and a context,
+/// e.g. one key per conversation from a single key.
+///
+/// The same key ref and context always derive the same key ref, and knowing a derived key
+/// says nothing about the key it was derived from.
+pub fn derive_x_salsa20_poly1305_key(
+    key_ref: XSalsa20Poly1305KeyRef,
+    context: Vec<u8>,
+) -> HdkResult<XSalsa20Poly1305KeyRef> {
+    host_externs!(__derive_x_salsa20_poly1305_key);
+    Ok(
+        host_call::<DeriveXSalsa20Poly1305KeyInput, DeriveXSalsa20Poly1305KeyOutput>(
+            __derive_x_salsa20_poly1305_key,
+            &DeriveXSalsa20Poly1305KeyInput::new(
+                holochain_zome_types::x_salsa20_poly1305::XSalsa20Poly1305DeriveKey::new(
+                    key_ref, context,
+                ),
+            ),
+        )?
+        .into_inner(),
+    )
+}
@@ -7,15 +7,14 @@ use crate::prelude::*;
 /// [saltpack](https://saltpack.org/).
 ///
 /// Important information about secretbox:
-///  - Wasm memory is NOT secure, a compromised host can steal the key.
-///  - The key is SECRET, anyone with the key and nonce can read the encrypted message.
+///  - The key is held by the keystore and the zome only ever sees an opaque key ref.
+///  - Use `create_x_salsa20_poly1305_key` or `derive_x_salsa20_poly1305_key` to get a key ref.
+///  - A key ref only resolves to its key in the keystore of the conductor that created it.
+///  - The key ref is SECRET, anyone with the ref on that conductor can read the encrypted message.
 ///  - The nonce is PUBLIC and UNIQUE, it must NEVER be re-used (so we don't allow it to be set).
-///  - It is STRONGLY RECOMMENDED to use `TryFromRandom` for the key for every message.
 ///  - Secretbox is designed for 'small' data, break large data into chunks with unique nonces.
 ///  - Secretbox is NOT quantum resistant.
 ///
-/// @todo shift all the secret handling into lair so that we only work with opaque key references.
-///
 /// If you want to hide data:
 ///  - Consider using capability tokens and/or dedicated DHT networks to control access.
 ///  - Consider how the shared key is being distributed, e.g. maybe use a key exchange protocol.
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use super::error::ConductorApiError;
 use super::error::ConductorApiResult;
 use crate::conductor::entry_def_store::EntryDefBufferKey;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::workflow::call_zome_workflow::call_zome_workspace_lock::CallZomeWorkspaceLock;
@@ -13,7 +14,10 @@ use async_trait::async_trait;
 use holo_hash::DnaHash;
 use holochain_conductor_api::ZomeCall;
 use holochain_keystore::KeystoreSender;
+use holochain_p2p::dht_arc::DhtArc;
+use holochain_p2p::kitsune_p2p::agent_store::AgentInfo;
 use holochain_types::prelude::*;
+use std::convert::TryFrom;
 use tracing::*;
 
 /// The concrete implementation of [CellConductorApiT], which is used to give
@@ -107,6 +111,23 @@ impl CellConductorApiT for CellConductorApi {
         self.conductor_handle.get_entry_def(key).await
     }
 
+    async fn get_agent_arc(&self, agent: AgentPubKey) -> ConductorApiResult<Option<DhtArc>> {
+        let cell_id = CellId::new(self.cell_id.dna_hash().clone(), agent);
+        match self
+            .conductor_handle
+            .get_agent_infos(Some(cell_id))
+            .await?
+            .first()
+        {
+            Some(info) => Ok(Some(
+                AgentInfo::try_from(info)
+                    .map_err(ConductorError::from)?
+                    .dht_arc(),
+            )),
+            None => Ok(None),
+        }
+    }
+
     fn into_call_zome_handle(self) -> CellConductorReadHandle {
         Arc::new(self)
     }
@@ -153,6 +174,10 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
     /// Get a [EntryDef] from the [EntryDefBuf]
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
 
+    /// Get the arc another agent holds in this cell's DNA,
+    /// if we have its agent info
+    async fn get_agent_arc(&self, agent: AgentPubKey) -> ConductorApiResult<Option<DhtArc>>;
+
     /// Turn this into a call zome handle
     fn into_call_zome_handle(self) -> CellConductorReadHandle;
 }
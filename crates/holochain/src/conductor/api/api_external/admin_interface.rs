@@ -192,18 +192,45 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDeactivated)
             }
-            AttachAppInterface { port } => {
+            UninstallApp {
+                installed_app_id,
+                delete_data,
+            } => {
+                self.conductor_handle
+                    .uninstall_app(installed_app_id, delete_data)
+                    .await?;
+                Ok(AdminResponse::AppUninstalled)
+            }
+            MigrateAgent {
+                cell_id,
+                successor_dna_hash,
+            } => {
+                let successor_cell_id = self
+                    .conductor_handle
+                    .migrate_agent(cell_id, successor_dna_hash)
+                    .await?;
+                Ok(AdminResponse::AgentMigrated(successor_cell_id))
+            }
+            AttachAppInterface {
+                port,
+                auth,
+                installed_app_ids,
+            } => {
                 let port = port.unwrap_or(0);
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(port, auth, installed_app_ids)
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
             DumpState { cell_id } => {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
-                Ok(AdminResponse::StateDumped(state))
+                Ok(AdminResponse::StateDumped(Box::new(state)))
+            }
+            CompactEnvironment { cell_id } => {
+                self.conductor_handle.compact_cell_env(&cell_id).await?;
+                Ok(AdminResponse::EnvironmentCompacted)
             }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
@@ -213,6 +240,42 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let r = self.conductor_handle.get_agent_infos(cell_id).await?;
                 Ok(AdminResponse::AgentInfoRequested(r))
             }
+            IssueAppInterfaceToken => {
+                let token = self.conductor_handle.issue_app_interface_token().await?;
+                Ok(AdminResponse::AppInterfaceTokenIssued(token))
+            }
+            RevokeAppInterfaceToken { token } => {
+                self.conductor_handle
+                    .revoke_app_interface_token(token)
+                    .await?;
+                Ok(AdminResponse::AppInterfaceTokenRevoked)
+            }
+            IssueAdminInterfaceToken => {
+                let token = self.conductor_handle.issue_admin_interface_token().await?;
+                Ok(AdminResponse::AdminInterfaceTokenIssued(token))
+            }
+            RevokeAdminInterfaceToken { token } => {
+                self.conductor_handle
+                    .revoke_admin_interface_token(token)
+                    .await?;
+                Ok(AdminResponse::AdminInterfaceTokenRevoked)
+            }
+            ListAppInterfaces => {
+                let ports = self.conductor_handle.list_app_interfaces().await?;
+                Ok(AdminResponse::AppInterfacesListed(ports))
+            }
+            DetachAppInterface { port } => {
+                self.conductor_handle.detach_app_interface(port).await?;
+                Ok(AdminResponse::AppInterfaceDetached)
+            }
+            ListAdminInterfaces => {
+                let ports = self.conductor_handle.list_admin_interfaces().await;
+                Ok(AdminResponse::AdminInterfacesListed(ports))
+            }
+            RemoveAdminInterface { port } => {
+                self.conductor_handle.remove_admin_interface(port).await?;
+                Ok(AdminResponse::AdminInterfaceRemoved)
+            }
         }
     }
 }
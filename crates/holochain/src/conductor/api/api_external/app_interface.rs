@@ -1,15 +1,23 @@
 use super::InterfaceApi;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
 use crate::conductor::state::AppInterfaceId;
 use crate::conductor::ConductorHandle;
 
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::signal_subscription::SignalSubscription;
+use holochain_keystore::KeystoreSenderExt;
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub use holochain_conductor_api::*;
 
@@ -22,6 +30,12 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse>;
 
+    /// The signal subscriptions made over this connection
+    fn signal_subscriptions(&self) -> &SignalSubscriptions;
+
+    /// A copy of this api for a new connection, without any signal subscriptions
+    fn for_connection(&self) -> Self;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_app_request_inner`
@@ -41,6 +55,7 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    signal_subscriptions: SignalSubscriptions,
 }
 
 impl RealAppInterfaceApi {
@@ -49,6 +64,102 @@ impl RealAppInterfaceApi {
         Self {
             conductor_handle,
             interface_id,
+            signal_subscriptions: SignalSubscriptions::default(),
+        }
+    }
+
+    /// Do some crypto on behalf of an agent, checking that the agent
+    /// belongs to an active app listed for this interface first.
+    async fn crypto(&self, request: CryptoRequest) -> ConductorApiResult<CryptoResponse> {
+        let agent = request.agent().clone();
+        let state = self.conductor_handle.get_state_from_handle().await?;
+        let found = state
+            .interface_by_id(&self.interface_id)
+            .map(|interface| {
+                interface
+                    .installed_app_ids
+                    .iter()
+                    .filter_map(|installed_app_id| state.active_apps.get(installed_app_id))
+                    .flatten()
+                    .any(|cell| *cell.as_id().agent_pubkey() == agent)
+            })
+            .unwrap_or(false);
+        if !found {
+            return Err(ConductorApiError::AgentNotInActiveApp(agent));
+        }
+        let owns_key = |key: &X25519PubKey| {
+            state
+                .agent_x25519_keys
+                .get(&agent)
+                .map(|keys| keys.contains(key))
+                .unwrap_or(false)
+        };
+
+        let keystore = self.conductor_handle.keystore();
+        Ok(match request {
+            CryptoRequest::Sign { agent, data } => {
+                let data: Vec<u8> = UnsafeBytes::from(data).into();
+                let data = [CRYPTO_SIGN_PREFIX, &data].concat();
+                CryptoResponse::Signature(agent.sign_raw(keystore, &data).await?)
+            }
+            CryptoRequest::CreateX25519Keypair { agent } => CryptoResponse::X25519PubKey(
+                self.conductor_handle
+                    .create_x25519_keypair_for_agent(agent)
+                    .await?,
+            ),
+            CryptoRequest::Encrypt { input, .. } => {
+                if !owns_key(input.as_sender_ref()) {
+                    return Err(ConductorApiError::X25519KeyNotOwned(agent));
+                }
+                CryptoResponse::Encrypted(keystore.x_25519_x_salsa20_poly1305_encrypt(input).await?)
+            }
+            CryptoRequest::Decrypt { input, .. } => {
+                if !owns_key(input.as_recipient_ref()) {
+                    return Err(ConductorApiError::X25519KeyNotOwned(agent));
+                }
+                CryptoResponse::Decrypted(keystore.x_25519_x_salsa20_poly1305_decrypt(input).await?)
+            }
+        })
+    }
+}
+
+/// The signal subscriptions made over a single app interface connection.
+/// Until the first subscription is made every signal is let through.
+#[derive(Clone, Debug, Default)]
+pub struct SignalSubscriptions(
+    Arc<parking_lot::RwLock<Option<HashMap<InstalledAppId, AppFilter>>>>,
+);
+
+/// The cells of a subscribed app and the filters applied to them
+#[derive(Debug)]
+struct AppFilter {
+    cells: HashSet<CellId>,
+    filters: SignalFilterSet,
+}
+
+impl SignalSubscriptions {
+    /// Replace the subscription for an app.
+    /// Only signals from `cells` can get through for this app.
+    pub fn subscribe(&self, cells: HashSet<CellId>, subscription: SignalSubscription) {
+        self.0.write().get_or_insert_with(HashMap::new).insert(
+            subscription.installed_app_id().clone(),
+            AppFilter {
+                cells,
+                filters: subscription.filters().clone(),
+            },
+        );
+    }
+
+    /// Whether the signal should be sent to this connection
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App(cell_id, _) => match &*self.0.read() {
+                None => true,
+                Some(apps) => apps
+                    .values()
+                    .any(|app| app.cells.contains(cell_id) && app.filters.allows(cell_id)),
+            },
+            Signal::System(_) => true,
         }
     }
 }
@@ -97,8 +208,36 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
-            AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(subscription) => {
+                let app = self
+                    .conductor_handle
+                    .get_app_info(subscription.installed_app_id())
+                    .await?
+                    .ok_or_else(|| {
+                        ConductorError::AppNotInstalled(subscription.installed_app_id().clone())
+                    })?;
+                let cells = app
+                    .cell_data
+                    .into_iter()
+                    .map(|cell| cell.into_id())
+                    .collect();
+                self.signal_subscriptions.subscribe(cells, subscription);
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
+            AppRequest::Crypto(request) => {
+                Ok(AppResponse::Crypto(Box::new(self.crypto(*request).await?)))
+            }
+        }
+    }
+
+    fn signal_subscriptions(&self) -> &SignalSubscriptions {
+        &self.signal_subscriptions
+    }
+
+    fn for_connection(&self) -> Self {
+        Self {
+            signal_subscriptions: SignalSubscriptions::default(),
+            ..self.clone()
         }
     }
 }
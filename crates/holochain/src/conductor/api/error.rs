@@ -6,6 +6,7 @@ use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::CellError;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::workflow::error::WorkflowError;
+use holo_hash::AgentPubKey;
 use holo_hash::DnaHash;
 use holochain_lmdb::error::DatabaseError;
 
@@ -36,6 +37,14 @@ pub enum ConductorApiError {
         call_cell_id: CellId,
     },
 
+    /// The agent is not the agent of any cell in an active app on the interface.
+    #[error("The agent is not part of any active app on this interface. AgentPubKey: {0}")]
+    AgentNotInActiveApp(AgentPubKey),
+
+    /// The X25519 key was not created for the agent.
+    #[error("The X25519 key does not belong to the agent. AgentPubKey: {0}")]
+    X25519KeyNotOwned(AgentPubKey),
+
     /// Conductor threw an error during API call.
     #[error("Conductor returned an error while using a ConductorApi: {0:?}")]
     ConductorError(#[from] ConductorError),
@@ -74,6 +83,10 @@ pub enum ConductorApiError {
     #[error("KeystoreError: {0}")]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
 
+    /// A blocking task failed to complete
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+
     /// Cell error
     #[error(transparent)]
     CellError(#[from] CellError),
@@ -10,6 +10,7 @@ use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
 use holochain_keystore::KeystoreSender;
+use holochain_p2p::dht_arc::DhtArc;
 use holochain_types::prelude::*;
 use mockall::mock;
 
@@ -38,6 +39,7 @@ mock! {
         fn sync_get_this_dna(&self) -> ConductorApiResult<DnaFile>;
         fn sync_get_zome(&self, dna_hash: &DnaHash, zome_name: &ZomeName) -> ConductorApiResult<Zome>;
         fn sync_get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+        fn sync_get_agent_arc(&self, agent: AgentPubKey) -> ConductorApiResult<Option<DhtArc>>;
         fn into_call_zome_handle(self) -> super::CellConductorReadHandle;
     }
 
@@ -92,6 +94,10 @@ impl CellConductorApiT for MockCellConductorApi {
         self.sync_get_entry_def(key)
     }
 
+    async fn get_agent_arc(&self, agent: AgentPubKey) -> ConductorApiResult<Option<DhtArc>> {
+        self.sync_get_agent_arc(agent)
+    }
+
     fn into_call_zome_handle(self) -> super::CellConductorReadHandle {
         self.into_call_zome_handle()
     }
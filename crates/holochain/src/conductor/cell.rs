@@ -13,6 +13,7 @@ use crate::conductor::api::CellConductorApiT;
 use crate::conductor::cell::error::CellResult;
 use crate::conductor::entry_def_store::get_entry_def_from_ids;
 use crate::conductor::handle::ConductorHandle;
+use crate::core::metrics::record_zome_call;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
@@ -22,9 +23,21 @@ use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::error::WorkflowError;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
+use crate::core::workflow::health_check_workflow::authored_ops_with_receipts;
+use crate::core::workflow::health_check_workflow::health_check_workflow;
 use crate::core::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
+use crate::core::workflow::incoming_validation_receipt_workflow::incoming_validation_receipt_workflow;
+use crate::core::workflow::incoming_warrant_workflow::incoming_warrant_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
+use crate::core::workflow::migrate_agent_workflow::commit_migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::prepare_migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
+use crate::core::workflow::migrate_agent_workflow::PreparedMigration;
 use crate::core::workflow::produce_dht_ops_workflow::dht_op_light::light_to_op;
+use crate::core::workflow::publish_dht_ops_workflow::PublishDhtOpsWorkspace;
+use crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE;
+use crate::core::workflow::validation_receipt_workflow::integrated_valid_ops;
+use crate::core::workflow::validation_receipt_workflow::send_validation_receipts;
 use crate::core::workflow::CallZomeWorkflowArgs;
 use crate::core::workflow::CallZomeWorkspace;
 use crate::core::workflow::GenesisWorkflowArgs;
@@ -37,10 +50,18 @@ use fallible_iterator::FallibleIterator;
 use futures::future::FutureExt;
 use hash_type::AnyDht;
 use holo_hash::*;
+use holochain_conductor_api::IntegrationDump;
+use holochain_conductor_api::SourceChainDump;
+use holochain_lmdb::buffer::KvBufFresh;
 use holochain_lmdb::db::GetDb;
+use holochain_lmdb::db::AUTHORED_DHT_OPS;
+use holochain_lmdb::db::INTEGRATED_DHT_OPS;
+use holochain_lmdb::db::INTEGRATION_LIMBO;
 use holochain_lmdb::env::EnvironmentRead;
 use holochain_lmdb::env::EnvironmentWrite;
 use holochain_lmdb::env::ReadManager;
+use holochain_lmdb::error::DatabaseResult;
+use holochain_lmdb::fresh_reader;
 use holochain_p2p::HolochainP2pCellT;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::prelude::*;
@@ -49,6 +70,8 @@ use observability::OpenSpanExt;
 use std::convert::TryInto;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync;
 use tracing::*;
 use tracing_futures::Instrument;
@@ -105,6 +128,10 @@ where
     env: EnvironmentWrite,
     holochain_p2p_cell: P2pCell,
     queue_triggers: QueueTriggers,
+    queue_consumer_stop: sync::broadcast::Sender<()>,
+    /// Closes once every queue consumer has stopped
+    queue_consumers_stopped: sync::Mutex<sync::mpsc::Receiver<()>>,
+    replication_health: Arc<parking_lot::RwLock<Option<ReplicationHealth>>>,
 }
 
 impl Cell {
@@ -129,14 +156,35 @@ impl Cell {
 
         if has_genesis {
             holochain_p2p_cell.join().await?;
-            let (queue_triggers, initial_queue_triggers) = spawn_queue_consumer_tasks(
-                &env,
-                holochain_p2p_cell.clone(),
-                conductor_api.clone(),
-                managed_task_add_sender,
-                managed_task_stop_broadcaster,
-            )
-            .await;
+
+            // The queue consumers stop when either the whole conductor shuts
+            // down or just this cell is cleaned up
+            let (queue_consumer_stop, _) = sync::broadcast::channel(1);
+            tokio::spawn({
+                let mut conductor_stop = managed_task_stop_broadcaster.subscribe();
+                let mut cell_stop = queue_consumer_stop.subscribe();
+                let queue_consumer_stop = queue_consumer_stop.clone();
+                async move {
+                    tokio::select! {
+                        _ = conductor_stop.recv() => {
+                            queue_consumer_stop.send(()).ok();
+                        }
+                        _ = cell_stop.recv() => {}
+                    }
+                }
+            });
+
+            let replication_health = Arc::new(parking_lot::RwLock::new(None));
+            let (queue_triggers, initial_queue_triggers, queue_consumers_stopped) =
+                spawn_queue_consumer_tasks(
+                    &env,
+                    holochain_p2p_cell.clone(),
+                    conductor_api.clone(),
+                    managed_task_add_sender,
+                    queue_consumer_stop.clone(),
+                    replication_health.clone(),
+                )
+                .await;
 
             Ok((
                 Self {
@@ -145,6 +193,9 @@ impl Cell {
                     env,
                     holochain_p2p_cell,
                     queue_triggers,
+                    queue_consumer_stop,
+                    queue_consumers_stopped: sync::Mutex::new(queue_consumers_stopped),
+                    replication_health,
                 },
                 initial_queue_triggers,
             ))
@@ -360,6 +411,22 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_validation_receipt_received"))
                 .await;
             }
+            PutWarrant {
+                span_context: _,
+                respond,
+                warrant,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_put_warrant(warrant)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_put_warrant"))
+                .await;
+            }
             FetchOpHashesForConstraints {
                 span_context: _,
                 respond,
@@ -412,25 +479,42 @@ impl Cell {
         Ok(())
     }
 
-    #[instrument(skip(self, _request_validation_receipt, _dht_hash, ops))]
+    #[instrument(skip(self, request_validation_receipt, _dht_hash, ops))]
     /// we are receiving a "publish" event from the network
     async fn handle_publish(
         &self,
         from_agent: AgentPubKey,
-        _request_validation_receipt: bool,
+        request_validation_receipt: bool,
         _dht_hash: holo_hash::AnyDhtHash,
         ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
     ) -> CellResult<()> {
+        // Ops we already hold are skipped by the incoming workflow,
+        // so their receipts are sent from here
+        let held = if request_validation_receipt {
+            integrated_valid_ops(&self.env.clone().into(), &ops).map_err(Box::new)?
+        } else {
+            Vec::new()
+        };
         incoming_dht_ops_workflow(
             &self.env,
             self.queue_triggers.sys_validation.clone(),
             ops,
             Some(from_agent),
+            request_validation_receipt,
         )
         .await
         .map_err(Box::new)
         .map_err(ConductorApiError::from)
         .map_err(Box::new)?;
+        if !held.is_empty() {
+            send_validation_receipts(
+                &mut self.holochain_p2p_cell.clone(),
+                self.env.keystore(),
+                held,
+            )
+            .await
+            .map_err(Box::new)?;
+        }
         Ok(())
     }
 
@@ -515,14 +599,15 @@ impl Cell {
         authority::handle_get_element(env, hash).map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let env = self.env.clone();
+        authority::handle_get_meta(env.into(), dht_hash, options).map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -553,8 +638,29 @@ impl Cell {
 
     /// a remote agent is sending us a validation receipt.
     #[tracing::instrument(skip(self))]
-    async fn handle_validation_receipt(&self, _receipt: SerializedBytes) -> CellResult<()> {
-        unimplemented!()
+    async fn handle_validation_receipt(&self, receipt: SerializedBytes) -> CellResult<()> {
+        incoming_validation_receipt_workflow(
+            &self.env,
+            receipt.try_into()?,
+            self.conductor_api.clone(),
+        )
+        .await
+        .map_err(Box::new)?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, warrant))]
+    /// an authority is warning us about an agent whose activity we hold
+    async fn handle_put_warrant(&self, warrant: SignedWarrant) -> CellResult<()> {
+        incoming_warrant_workflow(
+            &self.env,
+            self.queue_triggers.sys_validation.clone(),
+            warrant,
+            self.conductor_api.clone(),
+        )
+        .await
+        .map_err(Box::new)?;
+        Ok(())
     }
 
     #[instrument(skip(self, dht_arc, since, until))]
@@ -617,13 +723,74 @@ impl Cell {
     }
 
     /// When the Conductor determines that it's time to execute some [AutonomicProcess],
-    /// whether scheduled or through an [AutonomicCue], this function gets called
+    /// whether scheduled or through an [AutonomicCue], this function gets called.
+    /// Returns what the health check found.
     #[tracing::instrument(skip(self, process))]
-    pub async fn handle_autonomic_process(&self, process: AutonomicProcess) -> CellResult<()> {
-        match process {
-            AutonomicProcess::SlowHeal => unimplemented!(),
-            AutonomicProcess::HealthCheck => unimplemented!(),
-        }
+    pub async fn handle_autonomic_process(
+        &self,
+        process: AutonomicProcess,
+    ) -> CellResult<ReplicationHealth> {
+        let heal = match process {
+            AutonomicProcess::SlowHeal => true,
+            AutonomicProcess::HealthCheck => false,
+        };
+        let workspace = PublishDhtOpsWorkspace::new(self.env.clone().into())?;
+        let health = health_check_workflow(
+            workspace,
+            self.env.clone().into(),
+            &mut self.holochain_p2p_cell.clone(),
+            heal,
+        )
+        .await
+        .map_err(Box::new)?;
+        *self.replication_health.write() = Some(health.clone());
+        Ok(health)
+    }
+
+    /// The replication of this cell's authored ops as of the last health check,
+    /// or None if no check has run yet
+    pub fn replication_health(&self) -> Option<ReplicationHealth> {
+        self.replication_health.read().clone()
+    }
+
+    /// The length and head of this cell's source chain
+    pub fn source_chain_dump(&self) -> CellResult<SourceChainDump> {
+        let source_chain = SourceChainBuf::new(self.env.clone().into())?;
+        Ok(SourceChainDump {
+            len: source_chain.len(),
+            head: source_chain.chain_head().cloned(),
+        })
+    }
+
+    /// Count the ops in each of this cell's integration databases
+    pub fn integration_dump(&self) -> CellResult<IntegrationDump> {
+        let env: EnvironmentRead = self.env.clone().into();
+        let validation_limbo = ValidationLimboStore::new(env.clone())?;
+        let integration_limbo: IntegrationLimboStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*INTEGRATION_LIMBO)?);
+        let integrated: IntegratedDhtOpsStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*INTEGRATED_DHT_OPS)?);
+        let authored: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*AUTHORED_DHT_OPS)?);
+        Ok(fresh_reader!(env, |r| {
+            DatabaseResult::Ok(IntegrationDump {
+                validation_limbo: validation_limbo.0.iter(&r)?.count()?,
+                integration_limbo: integration_limbo.iter(&r)?.count()?,
+                integrated: integrated.iter(&r)?.count()?,
+                authored: authored.iter(&r)?.count()?,
+            })
+        })?)
+    }
+
+    /// The number of published ops authored by this cell which are short
+    /// of their validation receipts right now
+    pub async fn pending_validation_receipts(&self) -> CellResult<usize> {
+        let workspace = PublishDhtOpsWorkspace::new(self.env.clone().into())?;
+        Ok(authored_ops_with_receipts(&workspace)
+            .map_err(Box::new)?
+            .into_iter()
+            .filter(|(_, value, _)| value.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE)
+            .count())
     }
 
     #[instrument(skip(self, from_agent, fn_name, cap, payload))]
@@ -670,6 +837,9 @@ impl Cell {
             None => CallZomeWorkspaceLock::new(CallZomeWorkspace::new(arc.clone().into())?),
         };
 
+        let start = Instant::now();
+        let zome_name = call.zome_name.to_string();
+        let fn_name = call.fn_name.to_string();
         let conductor_api = self.conductor_api.clone();
         let signal_tx = self.signal_broadcaster().await;
         let ribosome = self.get_ribosome().await?;
@@ -682,7 +852,7 @@ impl Cell {
             signal_tx,
             is_root_zome_call,
         };
-        Ok(call_zome_workflow(
+        let result = call_zome_workflow(
             workspace_lock,
             self.holochain_p2p_cell.clone(),
             keystore,
@@ -691,7 +861,15 @@ impl Cell {
             self.queue_triggers.produce_dht_ops.clone(),
         )
         .await
-        .map_err(Box::new)?)
+        .map_err(Box::new)?;
+        record_zome_call(&zome_name, &fn_name, &result, start);
+
+        // The call may have scheduled a function which is due sooner
+        // than anything the scheduler is currently waiting on
+        if is_root_zome_call {
+            self.queue_triggers.call_scheduled_fns.clone().trigger();
+        }
+        Ok(result)
     }
 
     /// Check if each Zome's init callback has been run, and if not, run it.
@@ -742,14 +920,73 @@ impl Cell {
         Ok(())
     }
 
+    /// Run the `migrate_agent` callbacks for this Cell and, if they all pass,
+    /// prepare to close or open its chain. Nothing is written until the
+    /// migration is passed to [Cell::commit_migrate_agent].
+    ///
+    /// `other_dna_hash` is the DNA on the other side of the migration, i.e.
+    /// the successor when closing and the predecessor when opening.
+    #[tracing::instrument(skip(self))]
+    pub async fn prepare_migrate_agent(
+        &self,
+        migrate_agent: MigrateAgent,
+        other_dna_hash: DnaHash,
+    ) -> CellResult<PreparedMigration> {
+        // The zomes must be initialized before the chain can be migrated
+        self.check_or_run_zome_init().await?;
+
+        let env = self.env.clone();
+        let workspace = CallZomeWorkspace::new(env.clone().into())
+            .map_err(WorkflowError::from)
+            .map_err(Box::new)?;
+        let ribosome = self.get_ribosome().await?;
+        let dna_def = ribosome.dna_file.dna_def().clone();
+
+        let args = MigrateAgentWorkflowArgs {
+            dna_def,
+            ribosome,
+            migrate_agent,
+            other_dna_hash,
+        };
+        prepare_migrate_agent_workflow(workspace, args)
+            .await
+            .map_err(Box::new)?
+            .map_err(CellError::MigrateAgentFailed)
+    }
+
+    /// Write a migration prepared by [Cell::prepare_migrate_agent]
+    #[tracing::instrument(skip(self, prepared))]
+    pub async fn commit_migrate_agent(&self, prepared: PreparedMigration) -> CellResult<()> {
+        commit_migrate_agent_workflow(
+            prepared,
+            self.env.clone().into(),
+            self.queue_triggers.produce_dht_ops.clone(),
+        )
+        .await
+        .map_err(Box::new)?;
+        Ok(())
+    }
+
+    /// Stop this Cell's queue consumers, waiting for them to finish, and leave
+    /// the network space for its DNA. The Cell should not be used afterwards.
+    #[tracing::instrument(skip(self))]
+    pub async fn cleanup(&self) -> CellResult<()> {
+        // An error just means the queue consumers have already stopped
+        self.queue_consumer_stop.send(()).ok();
+        self.queue_consumers_stopped.lock().await.recv().await;
+        self.holochain_p2p_cell.clone().leave().await?;
+        Ok(())
+    }
+
     /// Delete all data associated with this Cell by deleting the associated
     /// LMDB environment. Completely reverses Cell creation.
     #[tracing::instrument(skip(self))]
-    pub async fn destroy(self) -> CellResult<()> {
+    pub async fn destroy(&self) -> CellResult<()> {
         let path = self.env.path().clone();
         // Remove db from global map
         // Delete directory
         self.env
+            .clone()
             .remove()
             .await
             .map_err(|e| CellError::Cleanup(e.to_string(), path))?;
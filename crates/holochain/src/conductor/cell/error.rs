@@ -2,6 +2,7 @@ use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::workflow::error::WorkflowError;
 use crate::core::workflow::produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertError;
 use crate::core::SourceChainError;
@@ -48,6 +49,8 @@ pub enum CellError {
     SourceChainError(#[from] SourceChainError),
     #[error("The cell tried to run the initialize zomes callback but failed because {0:?}")]
     InitFailed(InitResult),
+    #[error("The cell tried to run the migrate agent callback but failed because {0:?}")]
+    MigrateAgentFailed(MigrateAgentResult),
     #[error(transparent)]
     HolochainP2pError(#[from] HolochainP2pError),
     #[error(transparent)]
@@ -1,11 +1,19 @@
 use crate::conductor::manager::spawn_task_manager;
 use crate::core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace;
+use crate::fixt::AgentPubKeyFixturator;
+use crate::fixt::CreateLinkFixturator;
 use crate::fixt::DnaFileFixturator;
+use crate::fixt::EntryHashFixturator;
 use crate::fixt::SignatureFixturator;
 use crate::test_utils::test_network;
 use ::fixt::prelude::*;
 use holo_hash::HasHash;
+use holo_hash::HeaderHash;
+use holochain_lmdb::prelude::*;
 use holochain_lmdb::test_utils::test_cell_env;
+use holochain_state::element_buf::ElementBuf;
+use holochain_state::metadata::MetadataBuf;
+use holochain_state::metadata::MetadataBufT;
 use holochain_types::prelude::*;
 use holochain_zome_types::header;
 use holochain_zome_types::HeaderHashed;
@@ -76,3 +84,71 @@ async fn test_cell_handle_publish() {
     stop_tx.send(()).unwrap();
     shutdown.await.unwrap();
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn test_authority_handle_get_links_filters() {
+    let cell_env = test_cell_env();
+    let env = cell_env.env();
+
+    // Links on one base created a second apart, alternating between two authors
+    let base = fixt!(EntryHash);
+    let authors = vec![fixt!(AgentPubKey), fixt!(AgentPubKey)];
+    let mut element_vault = ElementBuf::vault(env.clone().into(), false).unwrap();
+    let mut meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+    for i in 0..6 {
+        let mut create_link = fixt!(CreateLink);
+        create_link.base_address = base.clone();
+        create_link.timestamp = holochain_zome_types::timestamp::Timestamp(i, 0);
+        create_link.author = authors[i as usize % 2].clone();
+        meta_vault.add_link(create_link.clone()).unwrap();
+        let header = HeaderHashed::from_content_sync(header::Header::CreateLink(create_link));
+        element_vault
+            .put(
+                SignedHeaderHashed::with_presigned(header, fixt!(Signature)),
+                None,
+            )
+            .unwrap();
+    }
+    env.guard()
+        .with_commit(|writer| {
+            element_vault.flush_to_txn(writer)?;
+            meta_vault.flush_to_txn(writer)
+        })
+        .unwrap();
+
+    let get_links = |filter: LinkFilter| {
+        super::authority::handle_get_links(
+            env.clone().into(),
+            WireLinkMetaKey::Base(base.clone()),
+            holochain_p2p::event::GetLinksOptions { filter },
+        )
+        .unwrap()
+        .link_adds
+        .into_iter()
+        .map(|(create_link, _)| create_link)
+        .collect::<Vec<_>>()
+    };
+    let timestamps = |links: &[CreateLink]| links.iter().map(|l| l.timestamp.0).collect::<Vec<_>>();
+
+    assert_eq!(
+        timestamps(&get_links(LinkFilter::new())),
+        vec![0, 1, 2, 3, 4, 5]
+    );
+
+    // Only links from the second author, in pages of two
+    let filter = LinkFilter::new().authors(vec![authors[1].clone()]).limit(2);
+    let page = get_links(filter.clone());
+    assert_eq!(timestamps(&page), vec![1, 3]);
+    let last = page.last().unwrap();
+    let cursor = LinkCursor {
+        timestamp: last.timestamp,
+        create_link_hash: HeaderHash::with_data_sync(&header::Header::CreateLink(last.clone())),
+    };
+    assert_eq!(timestamps(&get_links(filter.cursor(cursor))), vec![5]);
+
+    // Time bounds
+    let filter = LinkFilter::new()
+        .after(holochain_zome_types::timestamp::Timestamp(2, 0))
+        .before(holochain_zome_types::timestamp::Timestamp(4, 0));
+    assert_eq!(timestamps(&get_links(filter)), vec![2, 3]);
+}
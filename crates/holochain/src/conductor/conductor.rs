@@ -22,10 +22,13 @@ use super::entry_def_store::EntryDefBufferKey;
 use super::error::ConductorError;
 use super::error::CreateAppError;
 use super::handle::ConductorHandleImpl;
+use super::interface::error::InterfaceError;
 use super::interface::error::InterfaceResult;
+use super::interface::metrics::spawn_metrics_interface_task;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
+use super::interface::websocket::websocket_auth;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::SignalBroadcaster;
 use super::manager::keep_alive_task;
@@ -34,11 +37,16 @@ use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
 use super::manager::TaskManagerRunHandle;
 use super::p2p_store::all_agent_infos;
+use super::p2p_store::count_known_peers;
+use super::p2p_store::get_held_arc;
 use super::p2p_store::get_single_agent_info;
 use super::p2p_store::inject_agent_infos;
+use super::p2p_store::prune_agent_infos_task;
 use super::paths::EnvironmentRootPath;
+use super::state::AdminInterfaceState;
 use super::state::AppInterfaceId;
 use super::state::ConductorState;
+use super::state::InterfaceAuthState;
 use super::CellError;
 use super::{api::CellConductorApi, state::AppInterfaceConfig};
 use crate::conductor::api::error::ConductorApiResult;
@@ -54,6 +62,8 @@ use futures::future;
 use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
+use holochain_conductor_api::config::InterfaceAuthConfig;
+use holochain_conductor_api::CellStateDump;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
 use holochain_keystore::KeystoreSender;
@@ -63,16 +73,19 @@ use holochain_lmdb::buffer::KvStore;
 use holochain_lmdb::buffer::KvStoreT;
 use holochain_lmdb::db;
 use holochain_lmdb::env::EnvironmentKind;
+use holochain_lmdb::env::EnvironmentRead;
 use holochain_lmdb::env::EnvironmentWrite;
 use holochain_lmdb::env::ReadManager;
 use holochain_lmdb::exports::SingleStore;
 use holochain_lmdb::fresh_reader;
 use holochain_lmdb::prelude::*;
-use holochain_state::source_chain::SourceChainBuf;
 use holochain_state::wasm::WasmBuf;
 use holochain_types::prelude::*;
+use holochain_websocket::TokenHash;
+use holochain_websocket::WebsocketTokens;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::sync::RwLock;
@@ -132,10 +145,27 @@ where
     /// the dynamically allocated port later.
     admin_websocket_ports: Vec<u16>,
 
+    /// The port the metrics endpoint is bound to, if it is running.
+    metrics_port: Option<u16>,
+
+    /// Tokens issued through the admin interface, shared by
+    /// every admin interface that requires a token
+    admin_interface_tokens: WebsocketTokens,
+
+    /// Tokens issued through the admin interface, shared by
+    /// every app interface that requires a token
+    app_interface_tokens: WebsocketTokens,
+
     /// Collection of signal broadcasters per app interface, keyed by id
     app_interface_signal_broadcasters:
         HashMap<AppInterfaceId, tokio::sync::broadcast::Sender<Signal>>,
 
+    /// Channels which stop a single app interface, keyed by id
+    app_interface_detach_broadcasters: HashMap<AppInterfaceId, StopBroadcaster>,
+
+    /// Channels which stop a single admin interface, keyed by port
+    admin_interface_remove_broadcasters: HashMap<u16, StopBroadcaster>,
+
     /// Channel on which to send info about tasks we want to manage
     managed_task_add_sender: mpsc::Sender<ManagedTaskAdd>,
 
@@ -178,6 +208,11 @@ where
     pub fn get_arbitrary_admin_websocket_port(&self) -> Option<u16> {
         self.admin_websocket_ports.get(0).copied()
     }
+
+    /// Returns the port the metrics endpoint is bound to, if it is running.
+    pub fn get_metrics_port(&self) -> Option<u16> {
+        self.metrics_port
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -227,34 +262,85 @@ where
         self.task_manager_run_handle.take()
     }
 
-    /// Spawn all admin interface tasks, register them with the TaskManager,
-    /// and modify the conductor accordingly, based on the config passed in
+    /// Spawn admin interface tasks from the configs passed in and persist them,
+    /// so they are started again when the conductor restarts
     pub(super) async fn add_admin_interfaces_via_handle(
         &mut self,
         configs: Vec<AdminInterfaceConfig>,
         handle: ConductorHandle,
-    ) -> ConductorResult<()>
-    where
-        DS: DnaStore + 'static,
-    {
+    ) -> ConductorResult<()> {
+        let configs: Vec<AdminInterfaceState> = configs.into_iter().map(Into::into).collect();
+        let ports = self.spawn_admin_interfaces(configs.clone(), handle).await?;
+        // Persist the bound ports, so interfaces added on port 0 keep their port
+        let configs = configs
+            .into_iter()
+            .zip(ports)
+            .map(|(config, port)| AdminInterfaceState {
+                driver: InterfaceDriver::Websocket { port },
+                ..config
+            });
+        self.update_state(move |mut state| {
+            state.admin_interfaces.extend(configs);
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Start the admin interfaces from the conductor config along with
+    /// the ones persisted in state.
+    /// This should only be run at conductor initialization.
+    pub(super) async fn startup_admin_interfaces_via_handle(
+        &mut self,
+        configs: Vec<AdminInterfaceConfig>,
+        handle: ConductorHandle,
+    ) -> ConductorResult<()> {
+        let mut configs: Vec<AdminInterfaceState> = configs.into_iter().map(Into::into).collect();
+        let state = self.get_state().await?;
+        for hash in state.admin_interface_tokens {
+            self.admin_interface_tokens.insert(hash);
+        }
+        for config in state.admin_interfaces {
+            if !configs.iter().any(|c| c.driver == config.driver) {
+                configs.push(config);
+            }
+        }
+        if !configs.is_empty() {
+            self.spawn_admin_interfaces(configs, handle).await?;
+        }
+        Ok(())
+    }
+
+    /// Spawn all admin interface tasks, register them with the TaskManager,
+    /// and modify the conductor accordingly, based on the config passed in.
+    /// Returns the bound port of each interface, in the order of the configs.
+    async fn spawn_admin_interfaces(
+        &mut self,
+        configs: Vec<AdminInterfaceState>,
+        handle: ConductorHandle,
+    ) -> ConductorResult<Vec<u16>> {
         let admin_api = RealAdminInterfaceApi::new(handle);
         let stop_tx = self.managed_task_stop_broadcaster.clone();
+        let issued_tokens = self.admin_interface_tokens.clone();
 
         // Closure to process each admin config item
-        let spawn_from_config = |AdminInterfaceConfig { driver, .. }| {
+        let spawn_from_config = |AdminInterfaceState { driver, auth }| {
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
+            let auth = websocket_auth(&auth, Some(issued_tokens.clone()));
             async move {
                 match driver {
                     InterfaceDriver::Websocket { port } => {
-                        let listener = spawn_websocket_listener(port).await?;
+                        let listener = spawn_websocket_listener(port, auth).await?;
                         let port = listener.local_addr().port().unwrap_or(port);
+                        let (remove_tx, remove_rx) = tokio::sync::broadcast::channel(1);
                         let handle: ManagedTaskHandle = spawn_admin_interface_task(
                             listener,
                             admin_api.clone(),
                             stop_tx.subscribe(),
+                            remove_rx,
                         )?;
-                        InterfaceResult::Ok((port, handle))
+                        InterfaceResult::Ok((port, handle, remove_tx))
                     }
                 }
             }
@@ -270,9 +356,8 @@ where
         // Exit if the admin interfaces fail to be created
         let handles = handles.map_err(Box::new)?;
 
+        let mut ports = Vec::new();
         {
-            let mut ports = Vec::new();
-
             // First, register the keepalive task, to ensure the conductor doesn't shut down
             // in the absence of other "real" tasks
             self.manage_task(ManagedTaskAdd::dont_handle(tokio::spawn(keep_alive_task(
@@ -281,8 +366,10 @@ where
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
+            for (port, handle, remove_tx) in handles {
                 ports.push(port);
+                self.admin_interface_remove_broadcasters
+                    .insert(port, remove_tx);
                 self.manage_task(ManagedTaskAdd::new(
                     handle,
                     Box::new(|result| {
@@ -294,41 +381,171 @@ where
                 ))
                 .await?
             }
-            for p in ports {
-                self.add_admin_port(p);
+            for p in ports.iter() {
+                self.add_admin_port(*p);
             }
         }
-        Ok(())
+        Ok(ports)
     }
 
+    /// Spawn an app interface and persist it, returning the port it bound to
     pub(super) async fn add_app_interface_via_handle(
         &mut self,
         port: u16,
+        auth: InterfaceAuthConfig,
+        installed_app_ids: Vec<InstalledAppId>,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
-        let interface_id: AppInterfaceId = format!("interface-{}", port).into();
+        let auth = InterfaceAuthState::from(auth);
+        let (interface_id, port) = self.spawn_app_interface(port, &auth, None, handle).await?;
+        let config = AppInterfaceConfig::websocket(port, auth, installed_app_ids);
+        self.update_state(|mut state| {
+            state.app_interfaces.insert(interface_id, config);
+            Ok(state)
+        })
+        .await?;
+        Ok(port)
+    }
+
+    /// Spawn an app interface task and register it with the TaskManager.
+    /// Interfaces without an id are named after the port they bind to.
+    async fn spawn_app_interface(
+        &mut self,
+        port: u16,
+        auth: &InterfaceAuthState,
+        interface_id: Option<AppInterfaceId>,
+        handle: ConductorHandle,
+    ) -> ConductorResult<(AppInterfaceId, u16)> {
+        let websocket_auth = websocket_auth(auth, Some(self.app_interface_tokens.clone()));
+        let listener = spawn_websocket_listener(port, websocket_auth)
+            .await
+            .map_err(Box::new)?;
+        let port = listener
+            .local_addr()
+            .port()
+            .ok_or_else(|| Box::new(InterfaceError::PortError))?;
+        let interface_id = interface_id.unwrap_or_else(|| format!("interface-{}", port).into());
         let app_api = RealAppInterfaceApi::new(handle, interface_id.clone());
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
         let (signal_broadcaster, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
+        let (detach_tx, detach_rx) = tokio::sync::broadcast::channel(1);
         let stop_rx = self.managed_task_stop_broadcaster.subscribe();
-        let (port, task) =
-            spawn_app_interface_task(port, app_api, signal_broadcaster.clone(), stop_rx)
-                .await
-                .map_err(Box::new)?;
+        let task = spawn_app_interface_task(
+            listener,
+            app_api,
+            signal_broadcaster.clone(),
+            stop_rx,
+            detach_rx,
+        )
+        .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::dont_handle(task)).await?;
         self.app_interface_signal_broadcasters
             .insert(interface_id.clone(), signal_broadcaster);
-        let config = AppInterfaceConfig::websocket(port);
-        self.update_state(|mut state| {
-            state.app_interfaces.insert(interface_id, config);
+        self.app_interface_detach_broadcasters
+            .insert(interface_id.clone(), detach_tx);
+        Ok((interface_id, port))
+    }
+
+    /// Stop the app interface on this port, closing its connections,
+    /// and remove it from state so it is not started again
+    pub(super) async fn detach_app_interface(&mut self, port: u16) -> ConductorResult<()> {
+        let interface_id = self
+            .get_state()
+            .await?
+            .app_interfaces
+            .into_iter()
+            .find(|(_, config)| config.driver == InterfaceDriver::Websocket { port })
+            .map(|(id, _)| id)
+            .ok_or(ConductorError::AppInterfaceNotFound(port))?;
+        if let Some(detach_tx) = self.app_interface_detach_broadcasters.remove(&interface_id) {
+            // The task may have already stopped, which is fine
+            detach_tx.send(()).ok();
+        }
+        self.app_interface_signal_broadcasters.remove(&interface_id);
+        self.update_state(move |mut state| {
+            state.app_interfaces.remove(&interface_id);
             Ok(state)
         })
         .await?;
+        Ok(())
+    }
+
+    /// The ports of all attached app interfaces, in ascending order
+    #[allow(irrefutable_let_patterns)]
+    pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+        let mut ports: Vec<u16> = self
+            .get_state()
+            .await?
+            .app_interfaces
+            .values()
+            .filter_map(|config| {
+                if let InterfaceDriver::Websocket { port } = config.driver {
+                    Some(port)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        ports.sort_unstable();
+        Ok(ports)
+    }
+
+    /// The ports of all running admin interfaces
+    pub(super) fn list_admin_interfaces(&self) -> Vec<u16> {
+        self.admin_websocket_ports.clone()
+    }
+
+    /// Stop an admin interface added through the admin interface,
+    /// closing its connections, and remove it from state so it is not
+    /// started again. Interfaces in the conductor config can't be removed.
+    pub(super) async fn remove_admin_interface(&mut self, port: u16) -> ConductorResult<()> {
+        let driver = InterfaceDriver::Websocket { port };
+        if !self
+            .get_state()
+            .await?
+            .admin_interfaces
+            .iter()
+            .any(|config| config.driver == driver)
+        {
+            return Err(ConductorError::AdminInterfaceNotFound(port));
+        }
+        if let Some(remove_tx) = self.admin_interface_remove_broadcasters.remove(&port) {
+            // The task may have already stopped, which is fine
+            remove_tx.send(()).ok();
+        }
+        self.admin_websocket_ports.retain(|p| *p != port);
+        self.update_state(move |mut state| {
+            state
+                .admin_interfaces
+                .retain(|config| config.driver != driver);
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Spawn the metrics endpoint on a port on localhost
+    /// and return the port it bound to.
+    pub(super) async fn add_metrics_interface(&mut self, port: u16) -> ConductorResult<u16> {
+        let stop_rx = self.managed_task_stop_broadcaster.subscribe();
+        let (port, task) = spawn_metrics_interface_task(port, stop_rx)
+            .await
+            .map_err(Box::new)?;
+        self.manage_task(ManagedTaskAdd::dont_handle(task)).await?;
+        self.metrics_port = Some(port);
         Ok(port)
     }
 
+    /// Spawn the task which periodically deletes expired agent infos
+    /// from the peer store.
+    async fn spawn_agent_info_pruning(&mut self) -> ConductorResult<()> {
+        let stop_rx = self.managed_task_stop_broadcaster.subscribe();
+        let task = tokio::task::spawn(prune_agent_infos_task(self.p2p_env.clone(), stop_rx));
+        self.manage_task(ManagedTaskAdd::dont_handle(task)).await
+    }
+
     /// Start all app interfaces currently in state.
     /// This should only be run at conductor initialization.
     #[allow(irrefutable_let_patterns)]
@@ -336,20 +553,118 @@ where
         &mut self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for i in self.get_state().await?.app_interfaces.values() {
+        let state = self.get_state().await?;
+        for hash in state.app_interface_tokens {
+            self.app_interface_tokens.insert(hash);
+        }
+        for (id, i) in state.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", i);
             let port = if let InterfaceDriver::Websocket { port } = i.driver {
                 port
             } else {
                 unreachable!()
             };
-            let _ = self
-                .add_app_interface_via_handle(port, handle.clone())
+            let (id, bound_port) = self
+                .spawn_app_interface(port, &i.auth, Some(id), handle.clone())
+                .await?;
+            // Interfaces persisted before their bound port was recorded
+            // may have been stored with port 0
+            if bound_port != port {
+                let config = AppInterfaceConfig::websocket(bound_port, i.auth, i.installed_app_ids);
+                self.update_state(move |mut state| {
+                    state.app_interfaces.insert(id, config);
+                    Ok(state)
+                })
                 .await?;
+            }
         }
         Ok(())
     }
 
+    /// Issue a token which admin interfaces that require one will accept
+    pub(super) async fn issue_admin_interface_token(&self) -> ConductorResult<String> {
+        self.issue_interface_token(&self.admin_interface_tokens, |state| {
+            &mut state.admin_interface_tokens
+        })
+        .await
+    }
+
+    /// Stop admin interfaces accepting an issued token on new connections
+    pub(super) async fn revoke_admin_interface_token(&self, token: &str) -> ConductorResult<()> {
+        self.revoke_interface_token(&self.admin_interface_tokens, token, |state| {
+            &mut state.admin_interface_tokens
+        })
+        .await
+    }
+
+    /// Issue a token which app interfaces that require one will accept
+    pub(super) async fn issue_app_interface_token(&self) -> ConductorResult<String> {
+        self.issue_interface_token(&self.app_interface_tokens, |state| {
+            &mut state.app_interface_tokens
+        })
+        .await
+    }
+
+    /// Stop app interfaces accepting an issued token on new connections
+    pub(super) async fn revoke_app_interface_token(&self, token: &str) -> ConductorResult<()> {
+        self.revoke_interface_token(&self.app_interface_tokens, token, |state| {
+            &mut state.app_interface_tokens
+        })
+        .await
+    }
+
+    /// Mint a token in the set and persist its hash in the state field
+    async fn issue_interface_token(
+        &self,
+        tokens: &WebsocketTokens,
+        persisted: fn(&mut ConductorState) -> &mut HashSet<TokenHash>,
+    ) -> ConductorResult<String> {
+        let token = tokens.mint();
+        let hash = TokenHash::new(&token);
+        let result = self
+            .update_state(move |mut state| {
+                persisted(&mut state).insert(hash);
+                Ok(state)
+            })
+            .await;
+        if let Err(e) = result {
+            tokens.revoke(&token);
+            return Err(e);
+        }
+        Ok(token)
+    }
+
+    /// Revoke a token in the set and remove its hash from the state field
+    async fn revoke_interface_token(
+        &self,
+        tokens: &WebsocketTokens,
+        token: &str,
+        persisted: fn(&mut ConductorState) -> &mut HashSet<TokenHash>,
+    ) -> ConductorResult<()> {
+        tokens.revoke(token);
+        let hash = TokenHash::new(token);
+        self.update_state(move |mut state| {
+            persisted(&mut state).remove(&hash);
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Create an X25519 keypair in the keystore and record that it belongs to the agent
+    pub(super) async fn create_x25519_keypair_for_agent(
+        &self,
+        agent: AgentPubKey,
+    ) -> ConductorResult<X25519PubKey> {
+        let key = self.keystore.create_x25519_keypair().await?;
+        self.update_state(move |mut state| {
+            state.agent_x25519_keys.entry(agent).or_default().push(key);
+            Ok(state)
+        })
+        .await?;
+        Ok(key)
+    }
+
     pub(super) fn signal_broadcaster(&self) -> SignalBroadcaster {
         SignalBroadcaster::new(
             self.app_interface_signal_broadcasters
@@ -598,6 +913,36 @@ where
             .collect())
     }
 
+    /// Remove an app from the database, whether it is active or inactive,
+    /// returning the ids of its cells
+    pub(super) async fn remove_app_from_db(
+        &mut self,
+        installed_app_id: InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>> {
+        let mut cell_ids = Vec::new();
+        self.update_state({
+            let cell_ids = &mut cell_ids;
+            move |mut state| {
+                let cells = state
+                    .active_apps
+                    .remove(&installed_app_id)
+                    .or_else(|| state.inactive_apps.remove(&installed_app_id))
+                    .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+                *cell_ids = cells.into_iter().map(|c| c.into_id()).collect();
+                Ok(state)
+            }
+        })
+        .await?;
+        Ok(cell_ids)
+    }
+
+    /// Delete the LMDB environment of a cell which is not running
+    pub(super) async fn remove_cell_env(&self, cell_id: CellId) -> ConductorResult<()> {
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        EnvironmentWrite::remove_cell(&root_env_dir, cell_id).await?;
+        Ok(())
+    }
+
     /// Add fully constructed cells to the cell map in the Conductor
     pub(super) fn add_cells(&mut self, cells: Vec<(Cell, InitialQueueTriggers)>) {
         for (cell, trigger) in cells {
@@ -659,11 +1004,14 @@ where
         Ok((dnas, defs))
     }
 
-    /// Remove cells from the cell map in the Conductor
-    pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) {
-        for cell_id in cell_ids {
-            self.cells.remove(&cell_id);
-        }
+    /// Remove cells from the cell map in the Conductor, returning the cells
+    /// which were running
+    pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) -> Vec<Arc<Cell>> {
+        cell_ids
+            .into_iter()
+            .filter_map(|cell_id| self.cells.remove(&cell_id))
+            .map(|item| item.cell)
+            .collect()
     }
 
     pub(super) fn add_agent_infos(
@@ -716,17 +1064,14 @@ where
         if dna_def_buf.get(dna.dna_hash()).await?.is_none() {
             dna_def_buf.put(dna.dna_def().clone()).await?;
         }
-        {
-            let env = environ.guard();
-            // write the wasm db
-            env.with_commit(|writer| wasm_buf.flush_to_txn(writer))?;
+        // write the wasm db
+        environ.with_commit_retry(|writer| wasm_buf.flush_to_txn_ref(writer))?;
 
-            // write the dna_def db
-            env.with_commit(|writer| dna_def_buf.flush_to_txn(writer))?;
+        // write the dna_def db
+        environ.with_commit_retry(|writer| dna_def_buf.flush_to_txn_ref(writer))?;
 
-            // write the entry_def db
-            env.with_commit(|writer| entry_def_buf.flush_to_txn(writer))?;
-        }
+        // write the entry_def db
+        environ.with_commit_retry(|writer| entry_def_buf.flush_to_txn_ref(writer))?;
         Ok(zome_defs)
     }
 
@@ -739,11 +1084,22 @@ where
         Ok(active_apps.keys().cloned().collect())
     }
 
-    pub(super) async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
+    pub(super) async fn dump_cell_state(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<CellStateDump> {
         let cell = self.cell_by_id(cell_id)?;
-        let arc = cell.env();
-        let source_chain = SourceChainBuf::new(arc.clone().into())?;
-        Ok(source_chain.dump_as_json().await?)
+        let p2p_env: EnvironmentRead = self.p2p_env.clone().into();
+        let dna = cell_id.dna_hash().clone();
+        let agent = cell_id.agent_pubkey().clone();
+        Ok(CellStateDump {
+            cell_id: cell_id.clone(),
+            source_chain: cell.source_chain_dump()?,
+            integration: cell.integration_dump()?,
+            held_arc: get_held_arc(p2p_env.clone(), dna.clone(), agent.clone())?,
+            known_peers: count_known_peers(p2p_env, dna, agent)?,
+            pending_validation_receipts: cell.pending_validation_receipts().await?,
+        })
     }
 
     pub(super) fn p2p_env(&self) -> EnvironmentWrite {
@@ -785,10 +1141,15 @@ where
             cells: HashMap::new(),
             shutting_down: false,
             app_interface_signal_broadcasters: HashMap::new(),
+            app_interface_detach_broadcasters: HashMap::new(),
+            admin_interface_remove_broadcasters: HashMap::new(),
             managed_task_add_sender: task_tx,
             managed_task_stop_broadcaster: stop_tx,
             task_manager_run_handle,
             admin_websocket_ports: Vec::new(),
+            metrics_port: None,
+            admin_interface_tokens: WebsocketTokens::default(),
+            app_interface_tokens: WebsocketTokens::default(),
             dna_store,
             keystore,
             root_env_dir,
@@ -965,10 +1326,13 @@ mod builder {
         }
 
         async fn finish(
-            conductor: Conductor<DS>,
+            mut conductor: Conductor<DS>,
             conductor_config: ConductorConfig,
             p2p_evt: holochain_p2p::event::HolochainP2pEventReceiver,
         ) -> ConductorResult<ConductorHandle> {
+            // Keep the peer store free of expired agent infos
+            conductor.spawn_agent_info_pruning().await?;
+
             // Get data before handle
             let keystore = conductor.keystore.clone();
             let holochain_p2p = conductor.holochain_p2p.clone();
@@ -994,9 +1358,15 @@ mod builder {
                 );
             }
 
-            // Create admin interfaces
-            if let Some(configs) = conductor_config.admin_interfaces {
-                handle.clone().add_admin_interfaces(configs).await?;
+            // Create admin interfaces, from config and persisted in state
+            handle
+                .clone()
+                .startup_admin_interfaces(conductor_config.admin_interfaces.unwrap_or_default())
+                .await?;
+
+            // Serve metrics
+            if let Some(port) = conductor_config.metrics_port {
+                handle.add_metrics_interface(port).await?;
             }
 
             // Create app interfaces
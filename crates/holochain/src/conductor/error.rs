@@ -76,6 +76,12 @@ pub enum ConductorError {
     #[error("Tried to activate an app that was not installed: {0}")]
     AppNotInstalled(InstalledAppId),
 
+    #[error("No app interface is attached on port {0}")]
+    AppInterfaceNotFound(u16),
+
+    #[error("No admin interface added through the admin interface is running on port {0}")]
+    AdminInterfaceNotFound(u16),
+
     #[error("Tried to install an app using an already-used InstalledAppId: {0}")]
     AppAlreadyInstalled(InstalledAppId),
 
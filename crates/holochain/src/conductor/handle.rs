@@ -44,8 +44,10 @@
 use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
 use super::config::AdminInterfaceConfig;
+use super::config::InterfaceAuthConfig;
 use super::dna_store::DnaStore;
 use super::entry_def_store::EntryDefBufferKey;
+use super::error::ConductorError;
 use super::error::ConductorResult;
 use super::error::CreateAppError;
 use super::interface::SignalBroadcaster;
@@ -59,6 +61,7 @@ use crate::core::workflow::CallZomeWorkspaceLock;
 use crate::core::workflow::ZomeCallResult;
 use derive_more::From;
 use futures::future::FutureExt;
+use holochain_conductor_api::CellStateDump;
 use holochain_p2p::event::HolochainP2pEvent::*;
 use holochain_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
@@ -96,12 +99,60 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
+    /// Start the admin interfaces from the conductor config,
+    /// along with the ones persisted by `add_admin_interfaces`.
+    /// Should only be run once at Conductor initialization.
+    async fn startup_admin_interfaces(
+        self: Arc<Self>,
+        configs: Vec<AdminInterfaceConfig>,
+    ) -> ConductorResult<()>;
+
+    /// Serve metrics on a port on localhost, returning the bound port
+    async fn add_metrics_interface(&self, port: u16) -> ConductorResult<u16>;
+
     /// Start up persisted app interfaces.
     /// Should only be run once at Conductor initialization.
     async fn startup_app_interfaces(self: Arc<Self>) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface.
+    /// The agents of `installed_app_ids` can make crypto requests over it.
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        auth: InterfaceAuthConfig,
+        installed_app_ids: Vec<InstalledAppId>,
+    ) -> ConductorResult<u16>;
+
+    /// Issue a token which app interfaces that require one will accept
+    async fn issue_app_interface_token(&self) -> ConductorResult<String>;
+
+    /// Stop app interfaces accepting an issued token on new connections
+    async fn revoke_app_interface_token(&self, token: String) -> ConductorResult<()>;
+
+    /// Issue a token which admin interfaces that require one will accept
+    async fn issue_admin_interface_token(&self) -> ConductorResult<String>;
+
+    /// Stop admin interfaces accepting an issued token on new connections
+    async fn revoke_admin_interface_token(&self, token: String) -> ConductorResult<()>;
+
+    /// Create an X25519 keypair in the keystore and record that it belongs to the agent
+    async fn create_x25519_keypair_for_agent(
+        &self,
+        agent: AgentPubKey,
+    ) -> ConductorResult<X25519PubKey>;
+
+    /// List the ports of the attached app interfaces
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
+
+    /// Stop the app interface on this port and remove it from state
+    async fn detach_app_interface(&self, port: u16) -> ConductorResult<()>;
+
+    /// List the ports of the running admin interfaces
+    async fn list_admin_interfaces(&self) -> Vec<u16>;
+
+    /// Stop an admin interface added through the admin interface
+    /// and remove it from state
+    async fn remove_admin_interface(&self, port: u16) -> ConductorResult<()>;
 
     /// Install a [Dna] in this Conductor
     async fn install_dna(&self, dna: DnaFile) -> ConductorResult<()>;
@@ -138,9 +189,29 @@ pub trait ConductorHandleT: Send + Sync {
     /// Cue the autonomic system to perform some action early (experimental)
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()>;
 
+    /// Run a health check of a cell's authored ops now, republishing any which
+    /// are short of validation receipts, and return what it found
+    async fn check_replication_health(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<ReplicationHealth>;
+
+    /// Get the replication of a cell's authored ops as of its last health check.
+    /// Cells check themselves every [HEALTH_CHECK_INTERVAL], so this is None
+    /// for a newly started cell.
+    ///
+    /// [HEALTH_CHECK_INTERVAL]: crate::core::workflow::health_check_workflow::HEALTH_CHECK_INTERVAL
+    async fn get_replication_health(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Option<ReplicationHealth>>;
+
     /// Get a Websocket port which will
     async fn get_arbitrary_admin_websocket_port(&self) -> Option<u16>;
 
+    /// Get the port the metrics endpoint is bound to, if it is running
+    async fn get_metrics_port(&self) -> Option<u16>;
+
     /// Return the JoinHandle for all managed tasks, which when resolved will
     /// signal that the Conductor has completely shut down.
     ///
@@ -178,6 +249,24 @@ pub trait ConductorHandleT: Send + Sync {
     #[allow(clippy::ptr_arg)]
     async fn deactivate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<()>;
 
+    /// Uninstall an app, whether active or inactive, stopping its cells.
+    /// If `delete_data` is set, the cells' environments are deleted too.
+    #[allow(clippy::ptr_arg)]
+    async fn uninstall_app(
+        &self,
+        installed_app_id: InstalledAppId,
+        delete_data: bool,
+    ) -> ConductorResult<()>;
+
+    /// Migrate the agent of a cell to the successor DNA by closing the cell's
+    /// chain and opening the chain of the successor cell for the same agent,
+    /// which must already be running. Returns the successor cell's id.
+    async fn migrate_agent(
+        &self,
+        cell_id: CellId,
+        successor_dna_hash: DnaHash,
+    ) -> ConductorApiResult<CellId>;
+
     /// List Cell Ids
     async fn list_cell_ids(&self) -> ConductorResult<Vec<CellId>>;
 
@@ -186,7 +275,11 @@ pub trait ConductorHandleT: Send + Sync {
 
     /// Dump the cells state
     #[allow(clippy::ptr_arg)]
-    async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
+    async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<CellStateDump>;
+
+    /// Compact the cell's environment, giving back the space left by deleted data
+    #[allow(clippy::ptr_arg)]
+    async fn compact_cell_env(&self, cell_id: &CellId) -> ConductorApiResult<()>;
 
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
@@ -255,6 +348,23 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .await
     }
 
+    async fn startup_admin_interfaces(
+        self: Arc<Self>,
+        configs: Vec<AdminInterfaceConfig>,
+    ) -> ConductorResult<()> {
+        let mut lock = self.conductor.write().await;
+        lock.startup_admin_interfaces_via_handle(configs, self.clone())
+            .await
+    }
+
+    async fn add_metrics_interface(&self, port: u16) -> ConductorResult<u16> {
+        self.conductor
+            .write()
+            .await
+            .add_metrics_interface(port)
+            .await
+    }
+
     async fn startup_app_interfaces(self: Arc<Self>) -> ConductorResult<()> {
         self.conductor
             .write()
@@ -263,9 +373,82 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        auth: InterfaceAuthConfig,
+        installed_app_ids: Vec<InstalledAppId>,
+    ) -> ConductorResult<u16> {
         let mut lock = self.conductor.write().await;
-        lock.add_app_interface_via_handle(port, self.clone()).await
+        lock.add_app_interface_via_handle(port, auth, installed_app_ids, self.clone())
+            .await
+    }
+
+    async fn issue_app_interface_token(&self) -> ConductorResult<String> {
+        self.conductor
+            .read()
+            .await
+            .issue_app_interface_token()
+            .await
+    }
+
+    async fn revoke_app_interface_token(&self, token: String) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .revoke_app_interface_token(&token)
+            .await
+    }
+
+    async fn issue_admin_interface_token(&self) -> ConductorResult<String> {
+        self.conductor
+            .read()
+            .await
+            .issue_admin_interface_token()
+            .await
+    }
+
+    async fn revoke_admin_interface_token(&self, token: String) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .revoke_admin_interface_token(&token)
+            .await
+    }
+
+    async fn create_x25519_keypair_for_agent(
+        &self,
+        agent: AgentPubKey,
+    ) -> ConductorResult<X25519PubKey> {
+        self.conductor
+            .read()
+            .await
+            .create_x25519_keypair_for_agent(agent)
+            .await
+    }
+
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+        self.conductor.read().await.list_app_interfaces().await
+    }
+
+    async fn detach_app_interface(&self, port: u16) -> ConductorResult<()> {
+        self.conductor
+            .write()
+            .await
+            .detach_app_interface(port)
+            .await
+    }
+
+    async fn list_admin_interfaces(&self) -> Vec<u16> {
+        self.conductor.read().await.list_admin_interfaces()
+    }
+
+    async fn remove_admin_interface(&self, port: u16) -> ConductorResult<()> {
+        self.conductor
+            .write()
+            .await
+            .remove_admin_interface(port)
+            .await
     }
 
     async fn install_dna(&self, dna: DnaFile) -> ConductorResult<()> {
@@ -389,6 +572,24 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(())
     }
 
+    async fn check_replication_health(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<ReplicationHealth> {
+        let cell = self.cell_by_id(cell_id).await?;
+        Ok(cell
+            .handle_autonomic_process(AutonomicProcess::HealthCheck)
+            .await?)
+    }
+
+    async fn get_replication_health(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Option<ReplicationHealth>> {
+        let cell = self.cell_by_id(cell_id).await?;
+        Ok(cell.replication_health())
+    }
+
     async fn take_shutdown_handle(&self) -> Option<TaskManagerRunHandle> {
         self.conductor.write().await.take_shutdown_handle()
     }
@@ -400,6 +601,10 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .get_arbitrary_admin_websocket_port()
     }
 
+    async fn get_metrics_port(&self) -> Option<u16> {
+        self.conductor.read().await.get_metrics_port()
+    }
+
     async fn shutdown(&self) {
         self.conductor.write().await.shutdown()
     }
@@ -493,6 +698,68 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(())
     }
 
+    async fn uninstall_app(
+        &self,
+        installed_app_id: InstalledAppId,
+        delete_data: bool,
+    ) -> ConductorResult<()> {
+        let (cell_ids, cells) = {
+            let mut lock = self.conductor.write().await;
+            let cell_ids = lock.remove_app_from_db(installed_app_id).await?;
+            let cells = lock.remove_cells(cell_ids.clone());
+            (cell_ids, cells)
+        };
+        // Inactive apps have no running cells to clean up
+        for cell in &cells {
+            cell.cleanup().await?;
+            if delete_data {
+                cell.destroy().await?;
+            }
+        }
+        if delete_data {
+            let lock = self.conductor.read().await;
+            for cell_id in cell_ids {
+                if !cells.iter().any(|cell| *cell.id() == cell_id) {
+                    lock.remove_cell_env(cell_id).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn migrate_agent(
+        &self,
+        cell_id: CellId,
+        successor_dna_hash: DnaHash,
+    ) -> ConductorApiResult<CellId> {
+        let successor_cell_id = CellId::new(successor_dna_hash, cell_id.agent_pubkey().clone());
+        // Make sure both sides exist before touching either chain
+        let cell = self.cell_by_id(&cell_id).await?;
+        let successor_cell = self.cell_by_id(&successor_cell_id).await?;
+
+        // Run both sides' callbacks before writing either header,
+        // so a failing callback leaves both chains as they were
+        let close = cell
+            .prepare_migrate_agent(MigrateAgent::Close, successor_cell_id.dna_hash().clone())
+            .await
+            .map_err(ConductorError::from)?;
+        let open = successor_cell
+            .prepare_migrate_agent(MigrateAgent::Open, cell_id.dna_hash().clone())
+            .await
+            .map_err(ConductorError::from)?;
+
+        // Open before closing, an agent left with two open chains
+        // can try again but one with none is stranded
+        successor_cell
+            .commit_migrate_agent(open)
+            .await
+            .map_err(ConductorError::from)?;
+        cell.commit_migrate_agent(close)
+            .await
+            .map_err(ConductorError::from)?;
+        Ok(successor_cell_id)
+    }
+
     async fn list_cell_ids(&self) -> ConductorResult<Vec<CellId>> {
         self.conductor.read().await.list_cell_ids().await
     }
@@ -501,10 +768,17 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.read().await.list_active_apps().await
     }
 
-    async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
+    async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<CellStateDump> {
         self.conductor.read().await.dump_cell_state(cell_id).await
     }
 
+    async fn compact_cell_env(&self, cell_id: &CellId) -> ConductorApiResult<()> {
+        let env = self.cell_by_id(cell_id).await?.env().clone();
+        // Compaction waits for open transactions and copies the whole
+        // environment, so keep it off the async executor
+        Ok(tokio::task::spawn_blocking(move || env.compact()).await??)
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.read().await.signal_broadcaster()
     }
@@ -15,6 +15,7 @@ use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
 pub mod error;
+pub mod metrics;
 pub mod websocket;
 
 /// A collection of Senders to be used for emitting Signals from a Cell.
@@ -0,0 +1,147 @@
+//! A minimal HTTP interface which serves the process wide metrics
+//! in the Prometheus text format, configured with `metrics_port`.
+//!
+//! It only listens on localhost and only answers `GET /metrics`.
+
+use super::error::InterfaceResult;
+use crate::conductor::conductor::StopReceiver;
+use crate::conductor::manager::ManagedTaskHandle;
+use crate::conductor::manager::ManagedTaskResult;
+use kitsune_p2p::metrics;
+use std::net::Shutdown;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tracing::*;
+
+/// Requests larger than this are answered without reading the rest.
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// The content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Bind the metrics endpoint to a port on localhost and spawn the task
+/// which serves it. Returns the bound port, which differs from `port` if 0.
+pub async fn spawn_metrics_interface_task(
+    port: u16,
+    mut stop_rx: StopReceiver,
+) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+    let mut listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    let port = listener.local_addr()?.port();
+    trace!("Metrics LISTENING AT: {}", port);
+    // Make sure every holochain metric family is rendered, even before
+    // it has been recorded into
+    crate::core::metrics::register_all();
+    let task = tokio::task::spawn(async move {
+        loop {
+            tokio::select! {
+                // break if we receive on the stop channel
+                _ = stop_rx.recv() => { break; },
+
+                maybe_con = listener.accept() => match maybe_con {
+                    Ok((stream, _)) => {
+                        tokio::task::spawn(respond(stream));
+                    }
+                    Err(err) => {
+                        warn!("Metrics connection failed: {}", err);
+                    }
+                },
+            }
+        }
+        ManagedTaskResult::Ok(())
+    });
+    Ok((port, task))
+}
+
+async fn respond(mut stream: TcpStream) {
+    if let Err(err) = respond_inner(&mut stream).await {
+        debug!("Failed to respond to metrics request: {}", err);
+    }
+}
+
+async fn respond_inner(stream: &mut TcpStream) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.split_whitespace();
+    let (status, body) = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", metrics::render()),
+        _ => ("404 Not Found", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown(Shutdown::Write)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::metrics::record_zome_call;
+    use crate::core::metrics::UNKNOWN_FN;
+    use crate::core::ribosome::error::RibosomeError;
+    use holochain_serialized_bytes::SerializedBytes;
+    use holochain_zome_types::ExternOutput;
+    use holochain_zome_types::ZomeCallResponse;
+    use std::convert::TryFrom;
+    use std::time::Instant;
+
+    async fn get(port: u16, path: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn serves_metrics() {
+        let (stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+        let (port, task) = spawn_metrics_interface_task(0, stop_rx).await.unwrap();
+        let output = ExternOutput::new(SerializedBytes::try_from(()).unwrap());
+        let ok = Ok(ZomeCallResponse::Ok(output));
+        record_zome_call("metrics_zome", "metrics_fn", &ok, Instant::now());
+        let missing = Err(RibosomeError::ZomeFnNotExists(
+            "metrics_zome".into(),
+            "no_such_fn".into(),
+        ));
+        record_zome_call("metrics_zome", "no_such_fn", &missing, Instant::now());
+
+        let response = get(port, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+        assert!(response.contains("# TYPE holochain_workflow_queue_depth gauge\n"));
+        let count = "holochain_zome_call_duration_seconds_count";
+        assert!(response.contains(&format!(
+            "{}{{zome=\"metrics_zome\",fn=\"metrics_fn\"}} 1\n",
+            count
+        )));
+        // Calls to fns which don't exist are recorded without their names
+        assert!(!response.contains("no_such_fn"));
+        assert!(response.contains(&format!(
+            "{}{{zome=\"{}\",fn=\"{}\"}}",
+            count, UNKNOWN_FN, UNKNOWN_FN
+        )));
+
+        let response = get(port, "/").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        stop_tx.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+}
@@ -7,14 +7,17 @@ use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
+use crate::conductor::state::InterfaceAuthState;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_types::signal::Signal;
 use holochain_websocket::websocket_bind;
+use holochain_websocket::WebsocketAuth;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
 use holochain_websocket::WebsocketSender;
+use holochain_websocket::WebsocketTokens;
 use std::convert::TryFrom;
 
 use std::sync::atomic::AtomicUsize;
@@ -33,23 +36,50 @@ pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: usize = 400;
 
 /// Create a WebsocketListener to be used in interfaces
-pub async fn spawn_websocket_listener(port: u16) -> InterfaceResult<WebsocketListener> {
+pub async fn spawn_websocket_listener(
+    port: u16,
+    auth: WebsocketAuth,
+) -> InterfaceResult<WebsocketListener> {
     trace!("Initializing Admin interface");
     let listener = websocket_bind(
         url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        Arc::new(WebsocketConfig::default().auth(auth)),
     )
     .await?;
     trace!("LISTENING AT: {}", listener.local_addr());
     Ok(listener)
 }
 
+/// Build the handshake checks for an interface from its config.
+/// If tokens are required, `issued` tokens are accepted as well
+/// as the ones in the config.
+pub fn websocket_auth(
+    config: &InterfaceAuthState,
+    issued: Option<WebsocketTokens>,
+) -> WebsocketAuth {
+    let mut auth = WebsocketAuth::default();
+    if let Some(origins) = &config.allowed_origins {
+        auth = auth.allowed_origins(origins.clone());
+    }
+    if config.require_token {
+        auth = auth.require_token(WebsocketTokens::from_hashes(config.token_hashes.clone()));
+        if let Some(issued) = issued {
+            auth = auth.require_token(issued);
+        }
+    }
+    auth
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
 /// from the external client
+///
+/// The interface stops, closing its connections, on either the conductor's
+/// stop channel or `remove_rx`.
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
     mut listener: WebsocketListener,
     api: A,
     mut stop_rx: StopReceiver,
+    mut remove_rx: StopReceiver,
 ) -> InterfaceResult<ManagedTaskHandle> {
     Ok(tokio::task::spawn(async move {
         let mut listener_handles = Vec::new();
@@ -60,6 +90,9 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                 // break if we receive on the stop channel
                 _ = stop_rx.recv() => { break; },
 
+                // break if this interface is removed
+                _ = remove_rx.recv() => { break; },
+
                 // establish a new connection to a client
                 maybe_con = listener.next() => if let Some(connection) = maybe_con {
                     match connection {
@@ -112,32 +145,28 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
+/// from Cells via a broadcast channel.
+/// Each connection only receives the signals it has subscribed to.
+///
+/// The interface stops, closing its connections, on either the conductor's
+/// stop channel or `detach_rx`.
+pub fn spawn_app_interface_task<A: InterfaceApi + AppInterfaceApi>(
+    mut listener: WebsocketListener,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
-    trace!("Initializing App interface");
-    let mut listener = websocket_bind(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", listener.local_addr());
-    let port = listener
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
-    let task = tokio::task::spawn(async move {
+    mut detach_rx: StopReceiver,
+) -> InterfaceResult<ManagedTaskHandle> {
+    Ok(tokio::task::spawn(async move {
         let mut listener_handles = Vec::new();
+        let mut send_sockets = Vec::new();
 
         let mut handle_connection =
             |tx_to_iface: WebsocketSender, rx_from_iface: WebsocketReceiver| {
+                send_sockets.push(tx_to_iface.clone());
                 let rx_from_cell = signal_broadcaster.subscribe();
                 listener_handles.push(tokio::task::spawn(recv_incoming_msgs_and_outgoing_signals(
-                    api.clone(),
+                    api.for_connection(),
                     rx_from_iface,
                     rx_from_cell,
                     tx_to_iface,
@@ -149,6 +178,9 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 // break if we receive on the stop channel
                 _ = stop_rx.recv() => { break; },
 
+                // break if this interface is detached
+                _ = detach_rx.recv() => { break; },
+
                 // establish a new connection to a client
                 maybe_con = listener.next() => if let Some(connection) = maybe_con {
                     match connection {
@@ -164,11 +196,18 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 }
             }
         }
+        drop(listener);
+        for mut tx_to_iface in send_sockets {
+            if let Err(e) =
+                WebsocketSender::close(&mut tx_to_iface, 1000, "Shutting down".into()).await
+            {
+                debug!("App socket failed to close: {}", e);
+            }
+        }
 
         handle_shutdown(listener_handles).await;
         ManagedTaskResult::Ok(())
-    });
-    Ok((port, task))
+    }))
 }
 
 async fn handle_shutdown(listener_handles: Vec<JoinHandle<InterfaceResult<()>>>) {
@@ -224,8 +263,8 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
-/// App interface.
-async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+/// App interface. Only the signals this connection subscribed to are sent.
+async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi + AppInterfaceApi>(
     api: A,
     mut rx_from_iface: WebsocketReceiver,
     mut rx_from_cell: broadcast::Receiver<Signal>,
@@ -241,11 +280,12 @@ async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
             // tx and rx together in a new spawned task
             signal = rx_from_cell.next() => {
                 if let Some(signal) = signal {
-                    trace!(msg = "Sending signal!", ?signal);
-                    let bytes = SerializedBytes::try_from(
-                        signal.map_err(InterfaceError::SignalReceive)?,
-                    )?;
-                    tx_to_iface.signal(bytes).await?;
+                    let signal = signal.map_err(InterfaceError::SignalReceive)?;
+                    if api.signal_subscriptions().allows(&signal) {
+                        trace!(msg = "Sending signal!", ?signal);
+                        let bytes = SerializedBytes::try_from(signal)?;
+                        tx_to_iface.signal(bytes).await?;
+                    }
                 } else {
                     debug!("Closing interface: signal stream empty");
                     break;
@@ -342,8 +382,10 @@ pub mod test {
     use crate::conductor::api::AdminRequest;
     use crate::conductor::api::AdminResponse;
     use crate::conductor::api::RealAdminInterfaceApi;
+    use crate::conductor::api::RealAppInterfaceApi;
     use crate::conductor::conductor::ConductorBuilder;
     use crate::conductor::dna_store::MockDnaStore;
+    use crate::conductor::error::ConductorError;
     use crate::conductor::p2p_store::AgentKv;
     use crate::conductor::p2p_store::AgentKvKey;
     use crate::conductor::state::ConductorState;
@@ -351,9 +393,20 @@ pub mod test {
     use crate::conductor::ConductorHandle;
     use crate::fixt::RealRibosomeFixturator;
     use crate::test_utils::conductor_setup::ConductorTestData;
+    use crate::test_utils::wait_for_integration_10s;
+    use crate::test_utils::WaitOps;
     use ::fixt::prelude::*;
     use fallible_iterator::FallibleIterator;
     use futures::future::FutureExt;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_conductor_api::config::InterfaceAuthConfig;
+    use holochain_conductor_api::signal_subscription::SignalFilterSet;
+    use holochain_conductor_api::signal_subscription::SignalSubscription;
+    use holochain_conductor_api::IntegrationDump;
+    use holochain_conductor_api::SourceChainDump;
+    use holochain_conductor_api::CRYPTO_SIGN_PREFIX;
+    use holochain_keystore::AgentPubKeyExt;
+    use holochain_keystore::KeystoreSenderExt;
     use holochain_lmdb::buffer::KvStoreT;
     use holochain_lmdb::fresh_reader_test;
     use holochain_lmdb::test_utils::test_environments;
@@ -364,14 +417,22 @@ pub mod test {
     use holochain_types::app::InstalledCell;
     use holochain_types::dna::DnaDef;
     use holochain_types::dna::DnaFile;
+    use holochain_types::signal::test_signal;
     use holochain_types::test_utils::fake_agent_pubkey_1;
     use holochain_types::test_utils::fake_dna_file;
     use holochain_types::test_utils::fake_dna_zomes;
     use holochain_wasm_test_utils::TestWasm;
+    use holochain_websocket::websocket_connect;
+    use holochain_websocket::TokenHash;
     use holochain_websocket::WebsocketMessage;
     use holochain_zome_types::cell::CellId;
+    use holochain_zome_types::signal::AppSignal;
     use holochain_zome_types::test_utils::fake_agent_pubkey_2;
     use holochain_zome_types::ExternInput;
+    use holochain_zome_types::X25519XSalsa20Poly1305Decrypt;
+    use holochain_zome_types::X25519XSalsa20Poly1305Encrypt;
+    use holochain_zome_types::XSalsa20Poly1305Data;
+    use kitsune_p2p::agent_store::AgentInfo;
     use kitsune_p2p::agent_store::AgentInfoSigned;
     use kitsune_p2p::fixt::AgentInfoSignedFixturator;
     use matches::assert_matches;
@@ -552,6 +613,238 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn websocket_signal_subscription() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let other_cell_id = CellId::from((fixt!(DnaHash), fake_agent_pubkey_2()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let signal =
+            |cell_id: &CellId| Signal::App(cell_id.clone(), AppSignal::new(().try_into().unwrap()));
+        let subscribe = |api: RealAppInterfaceApi, app_id: &str, filters| async move {
+            let msg = AppRequest::SignalSubscription(SignalSubscription::new(
+                app_id.to_string(),
+                filters,
+            ));
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let respond = move |bytes: SerializedBytes| {
+                let response: AppResponse = bytes.try_into().unwrap();
+                tx.send(response).unwrap();
+                async { Ok(()) }.boxed()
+            };
+            let msg = WebsocketMessage::Request(msg.try_into().unwrap(), Box::new(respond));
+            handle_incoming_message(msg, api).await.unwrap();
+            rx.await.unwrap()
+        };
+
+        // Connections get every signal until they subscribe
+        let conn_1 = app_api.for_connection();
+        let conn_2 = app_api.for_connection();
+        assert!(conn_1
+            .signal_subscriptions()
+            .allows(&signal(&other_cell_id)));
+
+        // Subscribing to an app blocks signals from cells outside it
+        let response = subscribe(conn_1.clone(), "test app", SignalFilterSet::allow_all()).await;
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+        let subs = conn_1.signal_subscriptions();
+        assert!(subs.allows(&signal(&cell_id)));
+        assert!(!subs.allows(&signal(&other_cell_id)));
+        assert!(subs.allows(&test_signal("system")));
+
+        // Other connections are unaffected
+        assert!(conn_2
+            .signal_subscriptions()
+            .allows(&signal(&other_cell_id)));
+
+        // A new subscription replaces the old one
+        let response = subscribe(conn_1.clone(), "test app", SignalFilterSet::block_all()).await;
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+        assert!(!conn_1.signal_subscriptions().allows(&signal(&cell_id)));
+
+        // Can't subscribe to an app that isn't installed
+        let response = subscribe(conn_2.clone(), "not an app", SignalFilterSet::allow_all()).await;
+        assert_matches!(response, AppResponse::Error(_));
+        assert!(conn_2
+            .signal_subscriptions()
+            .allows(&signal(&other_cell_id)));
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn websocket_crypto() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let agent = fake_agent_pubkey_1();
+        let cell_id = CellId::from((dna_hash.clone(), agent.clone()));
+        let installed_cell = InstalledCell::new(cell_id, "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, unlisted_app_api, handle) =
+            setup_app(vec![(installed_cell, None)], dna_store).await;
+        let port = handle
+            .clone()
+            .add_app_interface(0, Default::default(), vec!["test app".to_string()])
+            .await
+            .unwrap();
+        let app_api =
+            RealAppInterfaceApi::new(handle.clone(), format!("interface-{}", port).into());
+        let crypto = |request| {
+            let app_api = app_api.clone();
+            async move {
+                app_api
+                    .handle_app_request(AppRequest::Crypto(Box::new(request)))
+                    .await
+            }
+        };
+
+        // Sign with the agent key. The data is prefixed so the signature
+        // can't pass for one over a header.
+        let data = vec![1, 2, 3];
+        let response = crypto(CryptoRequest::Sign {
+            agent: agent.clone(),
+            data: UnsafeBytes::from(data.clone()).into(),
+        })
+        .await;
+        let signature = match response {
+            AppResponse::Crypto(response) => match *response {
+                CryptoResponse::Signature(signature) => signature,
+                other => panic!("unexpected crypto response {:?}", other),
+            },
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert!(!agent.verify_signature_raw(&signature, &data).await.unwrap());
+        assert!(agent
+            .verify_signature_raw(&signature, &[CRYPTO_SIGN_PREFIX, &data].concat())
+            .await
+            .unwrap());
+
+        // Keys the agent doesn't own can't be used
+        let other_key = handle.keystore().create_x25519_keypair().await.unwrap();
+        let response = crypto(CryptoRequest::Encrypt {
+            agent: agent.clone(),
+            input: X25519XSalsa20Poly1305Encrypt::new(
+                other_key,
+                other_key,
+                XSalsa20Poly1305Data::from(data.clone()),
+            ),
+        })
+        .await;
+        assert_matches!(response, AppResponse::Error(_));
+
+        // Encrypt and decrypt between two keys created for the agent
+        let create_key = || {
+            let response = crypto(CryptoRequest::CreateX25519Keypair {
+                agent: agent.clone(),
+            });
+            async move {
+                match response.await {
+                    AppResponse::Crypto(response) => match *response {
+                        CryptoResponse::X25519PubKey(key) => key,
+                        other => panic!("unexpected crypto response {:?}", other),
+                    },
+                    other => panic!("unexpected response {:?}", other),
+                }
+            }
+        };
+        let sender = create_key().await;
+        let recipient = create_key().await;
+        let response = crypto(CryptoRequest::Encrypt {
+            agent: agent.clone(),
+            input: X25519XSalsa20Poly1305Encrypt::new(
+                sender,
+                recipient,
+                XSalsa20Poly1305Data::from(data.clone()),
+            ),
+        })
+        .await;
+        let encrypted = match response {
+            AppResponse::Crypto(response) => match *response {
+                CryptoResponse::Encrypted(encrypted) => encrypted,
+                other => panic!("unexpected crypto response {:?}", other),
+            },
+            other => panic!("unexpected response {:?}", other),
+        };
+        let response = crypto(CryptoRequest::Decrypt {
+            agent: agent.clone(),
+            input: X25519XSalsa20Poly1305Decrypt::new(sender, recipient, encrypted),
+        })
+        .await;
+        assert_matches!(
+            response,
+            AppResponse::Crypto(response)
+            if matches!(
+                *response,
+                CryptoResponse::Decrypted(Some(ref decrypted))
+                if decrypted == &XSalsa20Poly1305Data::from(data.clone())
+            )
+        );
+
+        // Agents outside the active apps can't use the keystore
+        let response = crypto(CryptoRequest::Sign {
+            agent: fake_agent_pubkey_2(),
+            data: UnsafeBytes::from(data.clone()).into(),
+        })
+        .await;
+        assert_matches!(response, AppResponse::Error(_));
+
+        // Nor can agents of apps not listed for the interface
+        let response = unlisted_app_api
+            .handle_app_request(AppRequest::Crypto(Box::new(CryptoRequest::Sign {
+                agent: agent.clone(),
+                data: UnsafeBytes::from(data).into(),
+            })))
+            .await;
+        assert_matches!(response, AppResponse::Error(_));
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn activate_app() {
         observability::test_run().ok();
@@ -583,7 +876,7 @@ pub mod test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(1)
             .return_const(());
-        let (_tmpdir, conductor_handle) =
+        let (tmpdir, conductor_handle) =
             setup_admin_fake_cells(cell_ids_with_proofs, dna_store).await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
 
@@ -664,6 +957,35 @@ pub mod test {
             .collect();
 
         assert_eq!(expected, cell_ids);
+
+        // Now uninstall app
+        let msg = AdminRequest::UninstallApp {
+            installed_app_id: "test app".to_string(),
+            delete_data: true,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppUninstalled);
+            async { Ok(()) }.boxed()
+        };
+        let respond = Box::new(respond);
+        let msg = WebsocketMessage::Request(msg, respond);
+
+        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
+            .await
+            .unwrap();
+
+        // Check it's gone from both active and inactive
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        assert_eq!(state.active_apps.get("test app"), None);
+        assert_eq!(state.inactive_apps.get("test app"), None);
+
+        // Check the cells' data is gone too
+        for cell_id in expected {
+            assert!(!tmpdir.path().join(cell_id.to_string()).exists());
+        }
+
         conductor_handle.shutdown().await;
         shutdown.await.unwrap();
     }
@@ -674,7 +996,11 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            auth: Default::default(),
+            installed_app_ids: Vec::new(),
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -688,6 +1014,249 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn app_interface_requires_issued_token() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let auth = InterfaceAuthConfig {
+            require_token: true,
+            tokens: vec!["config-token".into()],
+            ..Default::default()
+        };
+        let port = conductor_handle
+            .clone()
+            .add_app_interface(0, auth, Vec::new())
+            .await
+            .unwrap();
+        let connect = |token: Option<String>| {
+            let mut config = WebsocketConfig::default();
+            config.bearer_token = token;
+            websocket_connect(url2!("ws://127.0.0.1:{}", port), Arc::new(config))
+        };
+
+        assert!(connect(None).await.is_err());
+        assert!(connect(Some("config-token".into())).await.is_ok());
+
+        // Only the hash of the configured token is persisted
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        let config = state.app_interfaces.values().next().unwrap();
+        assert_eq!(
+            config.auth.token_hashes,
+            vec![TokenHash::new("config-token")]
+        );
+        assert!(!format!("{:?}", state).contains("config-token"));
+
+        let token = conductor_handle.issue_app_interface_token().await.unwrap();
+        assert!(connect(Some(token.clone())).await.is_ok());
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        assert!(state.app_interface_tokens.contains(&TokenHash::new(&token)));
+
+        conductor_handle
+            .revoke_app_interface_token(token.clone())
+            .await
+            .unwrap();
+        assert!(connect(Some(token)).await.is_err());
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        assert!(state.app_interface_tokens.is_empty());
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn admin_interface_requires_issued_token() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let existing = conductor_handle.list_admin_interfaces().await;
+        let config = holochain_conductor_api::config::AdminInterfaceConfig {
+            driver: holochain_conductor_api::config::InterfaceDriver::Websocket { port: 0 },
+            auth: InterfaceAuthConfig {
+                require_token: true,
+                ..Default::default()
+            },
+        };
+        conductor_handle
+            .clone()
+            .add_admin_interfaces(vec![config])
+            .await
+            .unwrap();
+        let port = conductor_handle
+            .list_admin_interfaces()
+            .await
+            .into_iter()
+            .find(|p| !existing.contains(p))
+            .unwrap();
+        let connect = |token: Option<String>| {
+            let mut config = WebsocketConfig::default();
+            config.bearer_token = token;
+            websocket_connect(url2!("ws://127.0.0.1:{}", port), Arc::new(config))
+        };
+
+        assert!(connect(None).await.is_err());
+
+        // App interface tokens are not accepted by admin interfaces
+        let app_token = conductor_handle.issue_app_interface_token().await.unwrap();
+        assert!(connect(Some(app_token)).await.is_err());
+
+        let token = conductor_handle
+            .issue_admin_interface_token()
+            .await
+            .unwrap();
+        assert!(connect(Some(token.clone())).await.is_ok());
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        assert!(state
+            .admin_interface_tokens
+            .contains(&TokenHash::new(&token)));
+
+        conductor_handle
+            .revoke_admin_interface_token(token.clone())
+            .await
+            .unwrap();
+        assert!(connect(Some(token)).await.is_err());
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+        assert!(state.admin_interface_tokens.is_empty());
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn list_and_detach_app_interfaces() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let mut ports = Vec::new();
+        for _ in 0..2 {
+            let port = conductor_handle
+                .clone()
+                .add_app_interface(0, Default::default(), Vec::new())
+                .await
+                .unwrap();
+            ports.push(port);
+        }
+        ports.sort_unstable();
+        assert_ne!(ports[0], ports[1]);
+        assert_eq!(conductor_handle.list_app_interfaces().await.unwrap(), ports);
+        let connect = |port: u16| {
+            websocket_connect(
+                url2!("ws://127.0.0.1:{}", port),
+                Arc::new(WebsocketConfig::default()),
+            )
+        };
+        let (_, mut rx) = connect(ports[0]).await.unwrap();
+
+        conductor_handle
+            .detach_app_interface(ports[0])
+            .await
+            .unwrap();
+        // The open connection is closed and new ones are refused
+        assert!(rx.next().await.is_none());
+        assert!(connect(ports[0]).await.is_err());
+        assert!(connect(ports[1]).await.is_ok());
+        assert_eq!(
+            conductor_handle.list_app_interfaces().await.unwrap(),
+            vec![ports[1]]
+        );
+        assert_matches!(
+            conductor_handle.detach_app_interface(ports[0]).await,
+            Err(ConductorError::AppInterfaceNotFound(_))
+        );
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn interfaces_keep_their_ports_across_restarts() {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let admin_config = holochain_conductor_api::config::AdminInterfaceConfig {
+            driver: holochain_conductor_api::config::InterfaceDriver::Websocket { port: 0 },
+            auth: Default::default(),
+        };
+
+        let conductor_handle = Conductor::builder().test(&envs).await.unwrap();
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        conductor_handle
+            .clone()
+            .add_admin_interfaces(vec![admin_config])
+            .await
+            .unwrap();
+        let admin_ports = conductor_handle.list_admin_interfaces().await;
+        let app_port = conductor_handle
+            .clone()
+            .add_app_interface(0, Default::default(), Vec::new())
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+
+        let conductor_handle = Conductor::builder().test(&envs).await.unwrap();
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        assert_eq!(conductor_handle.list_admin_interfaces().await, admin_ports);
+        assert_eq!(
+            conductor_handle.list_app_interfaces().await.unwrap(),
+            vec![app_port]
+        );
+        for port in admin_ports.into_iter().chain(std::iter::once(app_port)) {
+            websocket_connect(
+                url2!("ws://127.0.0.1:{}", port),
+                Arc::new(WebsocketConfig::default()),
+            )
+            .await
+            .unwrap();
+        }
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn remove_admin_interface() {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let admin_config = holochain_conductor_api::config::AdminInterfaceConfig {
+            driver: holochain_conductor_api::config::InterfaceDriver::Websocket { port: 0 },
+            auth: Default::default(),
+        };
+
+        let conductor_handle = Conductor::builder().test(&envs).await.unwrap();
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        conductor_handle
+            .clone()
+            .add_admin_interfaces(vec![admin_config])
+            .await
+            .unwrap();
+        let port = conductor_handle.list_admin_interfaces().await[0];
+        let connect = || {
+            websocket_connect(
+                url2!("ws://127.0.0.1:{}", port),
+                Arc::new(WebsocketConfig::default()),
+            )
+        };
+        let (_, mut rx) = connect().await.unwrap();
+
+        conductor_handle.remove_admin_interface(port).await.unwrap();
+        // The open connection is closed and new ones are refused
+        assert!(rx.next().await.is_none());
+        assert!(connect().await.is_err());
+        assert!(conductor_handle.list_admin_interfaces().await.is_empty());
+        assert_matches!(
+            conductor_handle.remove_admin_interface(port).await,
+            Err(ConductorError::AdminInterfaceNotFound(_))
+        );
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+
+        // It isn't started again
+        let conductor_handle = Conductor::builder().test(&envs).await.unwrap();
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        assert!(conductor_handle.list_admin_interfaces().await.is_empty());
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn dump_state() {
         observability::test_run().ok();
@@ -716,21 +1285,53 @@ pub mod test {
 
         // Set some state
         let cell_env = conductor_handle.get_cell_env(&cell_id).await.unwrap();
+        // The genesis ops are published to ourselves, as the only authority
+        wait_for_integration_10s(&cell_env, WaitOps::cold_start()).await;
 
         // Get state
         let expected = {
             let source_chain = SourceChainBuf::new(cell_env.clone().into()).unwrap();
-            source_chain.dump_as_json().await.unwrap()
+            SourceChainDump {
+                len: source_chain.len(),
+                head: source_chain.chain_head().cloned(),
+            }
         };
+        let agent_infos = conductor_handle
+            .get_agent_infos(Some(cell_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(agent_infos.len(), 1);
+        let expected_arc = AgentInfo::try_from(&agent_infos[0]).unwrap().dht_arc();
 
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
         let msg = AdminRequest::DumpState {
-            cell_id: Box::new(cell_id),
+            cell_id: Box::new(cell_id.clone()),
         };
         let msg = msg.try_into().unwrap();
         let respond = move |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::StateDumped(s) if s == expected);
+            match response {
+                AdminResponse::StateDumped(dump) => {
+                    assert_eq!(dump.cell_id, cell_id);
+                    assert_eq!(dump.source_chain, expected);
+                    assert_eq!(dump.source_chain.len, 3);
+                    assert_eq!(
+                        dump.integration,
+                        IntegrationDump {
+                            validation_limbo: 0,
+                            integration_limbo: 0,
+                            integrated: WaitOps::cold_start(),
+                            authored: WaitOps::cold_start(),
+                        }
+                    );
+                    assert_eq!(dump.held_arc, Some(expected_arc));
+                    // We are alone in the space
+                    assert_eq!(dump.known_peers, 0);
+                    // We are the only authority so can't send enough receipts
+                    assert_eq!(dump.pending_validation_receipts, WaitOps::cold_start());
+                }
+                other => panic!("unexpected response {:?}", other),
+            }
             async { Ok(()) }.boxed()
         };
         let respond = Box::new(respond);
@@ -740,6 +1341,54 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn uninstall_active_app_deletes_data() {
+        observability::test_run().ok();
+        let dna = fake_dna_file(&Uuid::new_v4().to_string());
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let cell_env_dir = tmpdir.path().join(cell_id.to_string());
+        assert!(cell_env_dir.is_dir());
+
+        // Uninstall the app while its cell is still running
+        let msg = AdminRequest::UninstallApp {
+            installed_app_id: "test app".to_string(),
+            delete_data: true,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppUninstalled);
+            async { Ok(()) }.boxed()
+        };
+        let respond = Box::new(respond);
+        let msg = WebsocketMessage::Request(msg, respond);
+        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
+            .await
+            .unwrap();
+
+        assert!(conductor_handle.list_cell_ids().await.unwrap().is_empty());
+        assert!(!cell_env_dir.exists());
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     async fn make_dna(uuid: &str, zomes: Vec<TestWasm>) -> DnaFile {
         DnaFile::new(
             DnaDef {
@@ -14,13 +14,23 @@ use holochain_lmdb::error::DatabaseResult;
 use holochain_lmdb::fresh_reader;
 use holochain_lmdb::key::BufKey;
 use holochain_lmdb::prelude::Readable;
+use holochain_lmdb::prelude::Writer;
+use holochain_p2p::kitsune_p2p::agent_store::now_ms;
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfo;
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
+use holochain_p2p::kitsune_p2p::dht_arc::DhtArc;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::sync::Arc;
+use tracing::*;
 
+use super::conductor::StopReceiver;
+use super::error::ConductorError;
 use super::error::ConductorResult;
+use super::manager::ManagedTaskResult;
+
+/// How often the conductor deletes expired agent infos from the peer store.
+pub const AGENT_INFO_PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
 
 const AGENT_KEY_LEN: usize = 64;
 const AGENT_KEY_COMPONENT_LEN: usize = 32;
@@ -150,6 +160,27 @@ impl AgentKv {
         self.0.get(reader, &key)
     }
 
+    /// Put an agent info unless we already hold one for the same agent
+    /// in the same space which was signed at the same time or later.
+    /// The agent info must already be verified.
+    fn put_if_newer(
+        &self,
+        writer: &mut Writer,
+        agent_info: &AgentInfo,
+        agent_info_signed: &AgentInfoSigned,
+    ) -> DatabaseResult<()> {
+        let key: AgentKvKey = agent_info.into();
+        if let Some(held) = self.0.get(writer, &key)? {
+            // Anything we can't decode is replaced.
+            if let Ok(held) = AgentInfo::try_from(&held) {
+                if held.signed_at_ms() >= agent_info.signed_at_ms() {
+                    return Ok(());
+                }
+            }
+        }
+        self.0.put(writer, &key, agent_info_signed)
+    }
+
     /// Get an iterator of the agent info stored in this database.
     pub fn iter<'r, R: Readable>(
         &'r self,
@@ -164,20 +195,26 @@ impl AgentKv {
     }
 }
 
-/// Inject multiple agent info entries into the peer store
+/// Inject multiple agent info entries into the peer store.
+///
+/// Every agent info must have a valid signature and be current,
+/// otherwise none of them are stored.
 pub fn inject_agent_infos<I: IntoIterator<Item = AgentInfoSigned> + Send>(
     env: EnvironmentWrite,
     iter: I,
-) -> DatabaseResult<()> {
+) -> ConductorResult<()> {
+    let now = now_ms()?;
+    let agent_infos = iter
+        .into_iter()
+        .map(|agent_info_signed| {
+            let agent_info = agent_info_signed.verify(now)?;
+            Ok((agent_info, agent_info_signed))
+        })
+        .collect::<ConductorResult<Vec<_>>>()?;
     let p2p_store = AgentKv::new(env.clone().into())?;
-    let env_ref = env.guard();
-    Ok(env_ref.with_commit(|writer| {
-        for agent_info_signed in iter {
-            p2p_store.as_store_ref().put(
-                writer,
-                &(&agent_info_signed).try_into()?,
-                &agent_info_signed,
-            )?
+    Ok(env.with_commit_retry(|writer| {
+        for (agent_info, agent_info_signed) in agent_infos.iter() {
+            p2p_store.put_if_newer(writer, agent_info, agent_info_signed)?;
         }
         DatabaseResult::Ok(())
     })?)
@@ -201,6 +238,38 @@ pub fn get_single_agent_info(
     fresh_reader!(env, |r| { p2p_store.get_agent_info(&r, space, agent) })
 }
 
+/// The arc an agent holds, as signed into its agent info
+pub fn get_held_arc(
+    env: EnvironmentRead,
+    space: DnaHash,
+    agent: AgentPubKey,
+) -> ConductorResult<Option<DhtArc>> {
+    match get_single_agent_info(env, space, agent)? {
+        Some(info) => Ok(Some(AgentInfo::try_from(&info)?.dht_arc())),
+        None => Ok(None),
+    }
+}
+
+/// Count the other agents in a space which have unexpired agent info
+pub fn count_known_peers(
+    env: EnvironmentRead,
+    space: DnaHash,
+    agent: AgentPubKey,
+) -> ConductorResult<usize> {
+    let space = holochain_p2p::space_holo_to_kit(space);
+    let agent = holochain_p2p::agent_holo_to_kit(agent);
+    let now = now_ms()?;
+
+    let mut count = 0;
+    for info in all_agent_infos(env)? {
+        let info = AgentInfo::try_from(&info)?;
+        if *info.as_space_ref() == space && *info.as_agent_ref() != agent && !info.is_expired(now) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
 /// Interconnect every provided pair of conductors via their peer store lmdb environments
 #[cfg(any(test, feature = "test_utils"))]
 pub fn exchange_peer_info(envs: Vec<EnvironmentWrite>) {
@@ -303,20 +372,62 @@ pub fn query_agent_info_signed(
     Ok(out)
 }
 
-/// Put single agent info into store
+/// Put single agent info into store, if it is valid, current
+/// and newer than any we already hold for the agent
 pub fn put_agent_info_signed(
     environ: EnvironmentWrite,
     agent_info_signed: kitsune_p2p::agent_store::AgentInfoSigned,
 ) -> ConductorResult<()> {
+    let agent_info = agent_info_signed.verify(now_ms()?)?;
+    let p2p_kv = AgentKv::new(environ.clone().into())?;
+    Ok(environ
+        .with_commit_retry(|writer| p2p_kv.put_if_newer(writer, &agent_info, &agent_info_signed))?)
+}
+
+/// Delete every expired agent info from the store,
+/// returning how many were deleted
+pub fn prune_expired_agent_infos(environ: EnvironmentWrite, now: u64) -> ConductorResult<usize> {
     let p2p_kv = AgentKv::new(environ.clone().into())?;
     let env = environ.guard();
-    Ok(env.with_commit(|writer| {
-        p2p_kv.as_store_ref().put(
-            writer,
-            &(&agent_info_signed).try_into()?,
-            &agent_info_signed,
-        )
-    })?)
+    env.with_commit(|writer| {
+        let expired = p2p_kv
+            .iter(writer)?
+            .filter_map(|(k, v)| {
+                // Anything we can't decode is as useless as an expired agent info.
+                Ok(match AgentInfo::try_from(&v) {
+                    Ok(info) if !info.is_expired(now) => None,
+                    _ => Some(k),
+                })
+            })
+            .collect::<Vec<_>>()?;
+        for key in expired.iter() {
+            p2p_kv.as_store_ref().delete(writer, key)?;
+        }
+        ConductorResult::Ok(expired.len())
+    })
+}
+
+/// Prune expired agent infos from the store every
+/// [`AGENT_INFO_PRUNE_INTERVAL`] until told to stop
+pub(crate) async fn prune_agent_infos_task(
+    environ: EnvironmentWrite,
+    mut stop_rx: StopReceiver,
+) -> ManagedTaskResult {
+    loop {
+        tokio::select! {
+            _ = stop_rx.recv() => break,
+            _ = tokio::time::delay_for(AGENT_INFO_PRUNE_INTERVAL) => {
+                let pruned = now_ms()
+                    .map_err(ConductorError::from)
+                    .and_then(|now| prune_expired_agent_infos(environ.clone(), now));
+                match pruned {
+                    Ok(pruned) => trace!(pruned, "Pruned expired agent infos"),
+                    Err(e) => warn!(error = ?e, "Failed to prune expired agent infos"),
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -328,8 +439,10 @@ mod tests {
     use holochain_lmdb::env::WriteManager;
     use holochain_lmdb::fresh_reader_test;
     use holochain_lmdb::test_utils::test_p2p_env;
+    use holochain_p2p::kitsune_p2p::agent_store::MAX_CLOCK_SKEW_MS;
     use kitsune_p2p::fixt::AgentInfoFixturator;
     use kitsune_p2p::fixt::AgentInfoSignedFixturator;
+    use kitsune_p2p::fixt::*;
     use kitsune_p2p::KitsuneBinType;
     use std::convert::TryInto;
 
@@ -413,4 +526,90 @@ mod tests {
 
         assert_eq!(expect, agents);
     }
+
+    /// Sign an agent info for the agent with `seed` in `space`.
+    fn signed_at(
+        seed: u8,
+        space: &kitsune_p2p::KitsuneSpace,
+        signed_at_ms: u64,
+        expires_after_ms: u64,
+    ) -> AgentInfoSigned {
+        agent_info_signed_with_seed(
+            [seed; 32],
+            space.clone(),
+            fixt!(Urls),
+            signed_at_ms,
+            expires_after_ms,
+            fixt!(DhtArc),
+        )
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn reject_invalid_agent_infos() {
+        observability::test_run().ok();
+        let t_env = test_p2p_env();
+        let env = t_env.env();
+        let space = fixt!(KitsuneSpace);
+        let now = now_ms().unwrap();
+
+        let invalid = vec![
+            forged_agent_info_signed(),
+            signed_at(1, &space, now - 2000, 1000),
+            signed_at(1, &space, now + MAX_CLOCK_SKEW_MS + 60_000, 60_000),
+        ];
+        for agent_info_signed in invalid {
+            assert!(put_agent_info_signed(env.clone(), agent_info_signed.clone()).is_err());
+            // One invalid agent info rejects the whole batch.
+            assert!(inject_agent_infos(
+                env.clone(),
+                vec![fixt!(AgentInfoSigned), agent_info_signed]
+            )
+            .is_err());
+        }
+
+        assert!(all_agent_infos(env.clone().into()).unwrap().is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn keep_newest_agent_info() {
+        observability::test_run().ok();
+        let t_env = test_p2p_env();
+        let env = t_env.env();
+        let space = fixt!(KitsuneSpace);
+        let now = now_ms().unwrap();
+
+        let older = signed_at(2, &space, now - 2000, 60_000);
+        let newer = signed_at(2, &space, now - 1000, 60_000);
+
+        put_agent_info_signed(env.clone(), newer.clone()).unwrap();
+        put_agent_info_signed(env.clone(), older.clone()).unwrap();
+        inject_agent_infos(env.clone(), vec![older]).unwrap();
+        assert_eq!(vec![newer], all_agent_infos(env.clone().into()).unwrap());
+
+        let newest = signed_at(2, &space, now, 60_000);
+        inject_agent_infos(env.clone(), vec![newest.clone()]).unwrap();
+        assert_eq!(vec![newest], all_agent_infos(env.clone().into()).unwrap());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn prune_expired() {
+        observability::test_run().ok();
+        let t_env = test_p2p_env();
+        let env = t_env.env();
+        let space = fixt!(KitsuneSpace);
+        let now = now_ms().unwrap();
+
+        let lasting = signed_at(3, &space, now - 1000, 60_000);
+        let expiring = signed_at(4, &space, now - 1000, 2000);
+        inject_agent_infos(env.clone(), vec![lasting.clone(), expiring]).unwrap();
+
+        assert_eq!(0, prune_expired_agent_infos(env.clone(), now).unwrap());
+        assert_eq!(2, all_agent_infos(env.clone().into()).unwrap().len());
+
+        assert_eq!(
+            1,
+            prune_expired_agent_infos(env.clone(), now + 5000).unwrap()
+        );
+        assert_eq!(vec![lasting], all_agent_infos(env.clone().into()).unwrap());
+    }
 }
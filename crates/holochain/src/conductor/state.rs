@@ -1,12 +1,16 @@
 //! Structs which allow the Conductor's state to be persisted across
 //! startups and shutdowns
 
+use holochain_conductor_api::config::AdminInterfaceConfig;
+use holochain_conductor_api::config::InterfaceAuthConfig;
 use holochain_conductor_api::config::InterfaceDriver;
 use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_types::prelude::*;
+use holochain_websocket::TokenHash;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Mutable conductor state, stored in a DB and writeable only via Admin interface.
 ///
@@ -25,6 +29,22 @@ pub struct ConductorState {
     /// List of interfaces any UI can use to access zome functions.
     #[serde(default)]
     pub app_interfaces: HashMap<AppInterfaceId, AppInterfaceConfig>,
+    /// Hashes of the bearer tokens issued through the admin interface
+    /// which admin interfaces that require a token accept.
+    #[serde(default)]
+    pub admin_interface_tokens: HashSet<TokenHash>,
+    /// Hashes of the bearer tokens issued through the admin interface
+    /// which app interfaces that require a token accept.
+    #[serde(default)]
+    pub app_interface_tokens: HashSet<TokenHash>,
+    /// Admin interfaces added through the admin interface.
+    /// Those in the conductor config are not persisted here.
+    #[serde(default)]
+    pub admin_interfaces: Vec<AdminInterfaceState>,
+    /// X25519 public keys created in the keystore for each agent
+    /// through app interface crypto requests
+    #[serde(default)]
+    pub agent_x25519_keys: HashMap<AgentPubKey, Vec<X25519PubKey>>,
 }
 
 /// A unique identifier used to refer to an App Interface internally.
@@ -73,14 +93,73 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// Who may connect to the interface
+    #[serde(default)]
+    pub auth: InterfaceAuthState,
+
+    /// The apps whose agents can make crypto requests over the interface
+    #[serde(default)]
+    pub installed_app_ids: Vec<InstalledAppId>,
 }
 
 impl AppInterfaceConfig {
     /// Create config for a websocket interface
-    pub fn websocket(port: u16) -> Self {
+    pub fn websocket(
+        port: u16,
+        auth: InterfaceAuthState,
+        installed_app_ids: Vec<InstalledAppId>,
+    ) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
             driver: InterfaceDriver::Websocket { port },
+            auth,
+            installed_app_ids,
+        }
+    }
+}
+
+/// An admin interface added through the admin interface, as it is persisted.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct AdminInterfaceState {
+    /// The driver for the interface, e.g. Websocket
+    pub driver: InterfaceDriver,
+    /// Who may connect to the interface
+    #[serde(default)]
+    pub auth: InterfaceAuthState,
+}
+
+impl From<AdminInterfaceConfig> for AdminInterfaceState {
+    fn from(config: AdminInterfaceConfig) -> Self {
+        Self {
+            driver: config.driver,
+            auth: config.auth.into(),
+        }
+    }
+}
+
+/// An [InterfaceAuthConfig] as it is persisted.
+/// The tokens are stored as their hashes, so they can't be read back out
+/// of the state.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct InterfaceAuthState {
+    /// Browser origins which may connect. Any origin may connect if None.
+    #[serde(default)]
+    pub allowed_origins: Option<Vec<String>>,
+    /// Require connections to present a bearer token
+    #[serde(default)]
+    pub require_token: bool,
+    /// Hashes of the tokens from the config which the interface always accepts
+    #[serde(default)]
+    pub token_hashes: Vec<TokenHash>,
+}
+
+impl From<InterfaceAuthConfig> for InterfaceAuthState {
+    fn from(config: InterfaceAuthConfig) -> Self {
+        Self {
+            allowed_origins: config.allowed_origins,
+            require_token: config.require_token,
+            token_hashes: config.tokens.iter().map(|t| TokenHash::new(t)).collect(),
         }
     }
 }
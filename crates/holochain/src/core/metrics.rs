@@ -0,0 +1,101 @@
+//! Metric families recorded by the conductor and its workflows.
+//!
+//! These are rendered alongside the kitsune metrics by the
+//! metrics endpoint, see [`crate::conductor::interface::metrics`].
+
+use crate::core::ribosome::error::RibosomeError;
+use crate::core::workflow::ZomeCallResult;
+use holochain_lmdb::env::EnvironmentKind;
+use holochain_zome_types::ZomeCallResponse;
+use kitsune_p2p::metrics::*;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// How long each zome call took, by zome and function.
+pub static ZOME_CALL_DURATION: Lazy<Arc<HistogramVec>> = Lazy::new(|| {
+    HistogramVec::register(
+        "holochain_zome_call_duration_seconds",
+        "Time taken to run a zome call",
+        &["zome", "fn"],
+        SECONDS_BUCKETS,
+    )
+});
+
+/// How many ops each workflow run processed.
+pub static WORKFLOW_OPS: Lazy<Arc<HistogramVec>> = Lazy::new(|| {
+    HistogramVec::register(
+        "holochain_workflow_ops_processed",
+        "Ops processed by a single workflow run",
+        &["workflow"],
+        COUNT_BUCKETS,
+    )
+});
+
+/// How long each workflow run took.
+pub static WORKFLOW_DURATION: Lazy<Arc<HistogramVec>> = Lazy::new(|| {
+    HistogramVec::register(
+        "holochain_workflow_duration_seconds",
+        "Time taken by a single workflow run",
+        &["workflow"],
+        SECONDS_BUCKETS,
+    )
+});
+
+/// How many ops were waiting for a workflow when it last ran, per cell.
+pub static QUEUE_DEPTH: Lazy<Arc<GaugeVec>> = Lazy::new(|| {
+    GaugeVec::register(
+        "holochain_workflow_queue_depth",
+        "Ops waiting for a workflow at the start of its last run",
+        &["workflow", "cell"],
+    )
+});
+
+/// Register every metric family above, so they are rendered
+/// before anything has been recorded into them.
+pub fn register_all() {
+    Lazy::force(&ZOME_CALL_DURATION);
+    Lazy::force(&WORKFLOW_OPS);
+    Lazy::force(&WORKFLOW_DURATION);
+    Lazy::force(&QUEUE_DEPTH);
+}
+
+/// The zome and fn label of calls which didn't reach a zome function.
+/// The names in these calls come from the caller, so they are collapsed
+/// into one series instead of letting anyone add series to the registry.
+pub const UNKNOWN_FN: &str = "unknown";
+
+/// Record the zome call latency for a call started at `start`.
+pub fn record_zome_call(zome: &str, fn_name: &str, result: &ZomeCallResult, start: Instant) {
+    let ran = match result {
+        Ok(ZomeCallResponse::Ok(_)) => true,
+        Ok(_) => false,
+        Err(RibosomeError::ZomeNotExists(_)) | Err(RibosomeError::ZomeFnNotExists(_, _)) => false,
+        // The fn ran and returned an error
+        Err(_) => true,
+    };
+    if ran {
+        ZOME_CALL_DURATION.observe_since(&[zome, fn_name], start);
+    } else {
+        ZOME_CALL_DURATION.observe_since(&[UNKNOWN_FN, UNKNOWN_FN], start);
+    }
+}
+
+/// Record one run of a workflow against a cell environment.
+/// `queued` is how many ops were waiting when the run started
+/// and `processed` is how many of them it got through.
+pub fn record_workflow_run(
+    workflow: &str,
+    kind: &EnvironmentKind,
+    queued: usize,
+    processed: usize,
+    start: Instant,
+) {
+    let cell = match kind {
+        EnvironmentKind::Cell(cell_id) => cell_id.to_string(),
+        kind => format!("{:?}", kind),
+    };
+    QUEUE_DEPTH.set(&[workflow, &cell], queued as i64);
+    WORKFLOW_OPS.observe(&[workflow], processed as f64);
+    WORKFLOW_DURATION.observe_since(&[workflow], start);
+}
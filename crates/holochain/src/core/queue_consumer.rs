@@ -19,9 +19,19 @@
 //! |                 **integration, common to both paths**                 |
 //! | DhtOpIntegr.   | IntegrationLimbo | IntegratedDhtOps | Publish        |
 //! | Publish        | AuthoredDhtOps   | *n/a*            | *n/a*          |
+//! |                        **scheduled path**                             |
+//! | CallScheduled  | ScheduledFns     | ScheduledFns ‡   | *n/a*          |
+//! |                        **autonomic path**                             |
+//! | HealthCheck    | AuthoredDhtOps   | AuthoredDhtOps § | *n/a*          |
 //!
 //! († Auth'd + IntQ is short for: AuthoredDhtOps + IntegrationLimbo)
 //!
+//! (‡ CallScheduled also wakes itself up when the next scheduled function is
+//! due, and calls each due function as a regular zome call)
+//!
+//! (§ HealthCheck is not triggered, it wakes itself up periodically and
+//! republishes the authored ops which are short of validation receipts)
+//!
 //! Implicitly, every workflow also writes to its own source queue, i.e. to
 //! remove the item it has just processed.
 
@@ -30,7 +40,6 @@ use derive_more::Display;
 use derive_more::From;
 use futures::future::Either;
 use holochain_lmdb::env::EnvironmentWrite;
-use holochain_lmdb::env::WriteManager;
 use holochain_lmdb::prelude::Writer;
 use tokio::sync;
 use tokio::sync::mpsc;
@@ -44,40 +53,68 @@ mod app_validation_consumer;
 use app_validation_consumer::*;
 mod produce_dht_ops_consumer;
 use produce_dht_ops_consumer::*;
+mod call_scheduled_fns_consumer;
+mod health_check_consumer;
 mod publish_dht_ops_consumer;
 use crate::conductor::api::CellConductorApiT;
 use crate::conductor::manager::ManagedTaskAdd;
+use crate::conductor::manager::ManagedTaskHandle;
+use call_scheduled_fns_consumer::*;
+use health_check_consumer::*;
 use holochain_p2p::HolochainP2pCell;
 use holochain_state::workspace::WorkspaceError;
+use holochain_types::autonomic::ReplicationHealth;
 use publish_dht_ops_consumer::*;
+use std::sync::Arc;
 
 /// Spawns several long-running tasks which are responsible for processing work
 /// which shows up on various databases.
 ///
 /// Waits for the initial loop to complete before returning, to prevent causing
 /// a race condition by trying to run a workflow too soon after cell creation.
+///
+/// The returned receiver yields `None` once every consumer has stopped.
 pub async fn spawn_queue_consumer_tasks(
     env: &EnvironmentWrite,
     cell_network: HolochainP2pCell,
     conductor_api: impl CellConductorApiT + 'static,
     mut task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
-) -> (QueueTriggers, InitialQueueTriggers) {
+    replication_health: Arc<parking_lot::RwLock<Option<ReplicationHealth>>>,
+) -> (QueueTriggers, InitialQueueTriggers, mpsc::Receiver<()>) {
+    let (running, stopped) = mpsc::channel(1);
+
     // Publish
     let (tx_publish, handle) =
         spawn_publish_dht_ops_consumer(env.clone(), stop.subscribe(), cell_network.clone());
     task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
+        .send(track_running(handle, running.clone()))
+        .await
+        .expect("Failed to manage workflow handle");
+
+    // Health check
+    let handle = spawn_health_check_consumer(
+        env.clone(),
+        stop.subscribe(),
+        cell_network.clone(),
+        replication_health,
+    );
+    task_sender
+        .send(track_running(handle, running.clone()))
         .await
         .expect("Failed to manage workflow handle");
 
     let (create_tx_sys, get_tx_sys) = tokio::sync::oneshot::channel();
 
     // Integration
-    let (tx_integration, handle) =
-        spawn_integrate_dht_ops_consumer(env.clone(), stop.subscribe(), get_tx_sys);
+    let (tx_integration, handle) = spawn_integrate_dht_ops_consumer(
+        env.clone(),
+        stop.subscribe(),
+        get_tx_sys,
+        cell_network.clone(),
+    );
     task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
+        .send(track_running(handle, running.clone()))
         .await
         .expect("Failed to manage workflow handle");
 
@@ -90,7 +127,15 @@ pub async fn spawn_queue_consumer_tasks(
         cell_network.clone(),
     );
     task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
+        .send(track_running(handle, running.clone()))
+        .await
+        .expect("Failed to manage workflow handle");
+
+    // Call scheduled fns
+    let (tx_scheduled_fns, handle) =
+        spawn_call_scheduled_fns_consumer(env.clone(), stop.subscribe(), conductor_api.clone());
+    task_sender
+        .send(track_running(handle, running.clone()))
         .await
         .expect("Failed to manage workflow handle");
 
@@ -103,7 +148,7 @@ pub async fn spawn_queue_consumer_tasks(
         conductor_api,
     );
     task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
+        .send(track_running(handle, running.clone()))
         .await
         .expect("Failed to manage workflow handle");
     if create_tx_sys.send(tx_sys.clone()).is_err() {
@@ -114,16 +159,33 @@ pub async fn spawn_queue_consumer_tasks(
     let (tx_produce, handle) =
         spawn_produce_dht_ops_consumer(env.clone(), stop.subscribe(), tx_publish.clone());
     task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
+        .send(track_running(handle, running.clone()))
         .await
         .expect("Failed to manage workflow handle");
 
     (
-        QueueTriggers::new(tx_sys.clone(), tx_produce.clone()),
-        InitialQueueTriggers::new(tx_sys, tx_produce, tx_publish, tx_app, tx_integration),
+        QueueTriggers::new(tx_sys.clone(), tx_produce.clone(), tx_scheduled_fns.clone()),
+        InitialQueueTriggers::new(
+            tx_sys,
+            tx_produce,
+            tx_publish,
+            tx_app,
+            tx_integration,
+            tx_scheduled_fns,
+        ),
+        stopped,
     )
 }
 
+/// Hold a clone of `running` until the consumer behind `handle` has ended
+fn track_running(handle: ManagedTaskHandle, running: mpsc::Sender<()>) -> ManagedTaskAdd {
+    ManagedTaskAdd::dont_handle(tokio::spawn(async move {
+        let result = handle.await?;
+        drop(running);
+        result
+    }))
+}
+
 #[derive(Clone)]
 /// The entry points for kicking off a chain reaction of queue activity
 pub struct QueueTriggers {
@@ -131,6 +193,9 @@ pub struct QueueTriggers {
     pub sys_validation: TriggerSender,
     /// Notify the ProduceDhtOps workflow to run, i.e. after InvokeCallZome
     pub produce_dht_ops: TriggerSender,
+    /// Notify the CallScheduledFns workflow to run, i.e. after a zome call
+    /// which may have scheduled a function
+    pub call_scheduled_fns: TriggerSender,
 }
 
 /// The triggers to run once at the start of a cell
@@ -142,14 +207,20 @@ pub struct InitialQueueTriggers {
     publish_dht_ops: TriggerSender,
     app_validation: TriggerSender,
     integrate_dht_ops: TriggerSender,
+    call_scheduled_fns: TriggerSender,
 }
 
 impl QueueTriggers {
     /// Create a new queue trigger
-    pub fn new(sys_validation: TriggerSender, produce_dht_ops: TriggerSender) -> Self {
+    pub fn new(
+        sys_validation: TriggerSender,
+        produce_dht_ops: TriggerSender,
+        call_scheduled_fns: TriggerSender,
+    ) -> Self {
         Self {
             sys_validation,
             produce_dht_ops,
+            call_scheduled_fns,
         }
     }
 }
@@ -161,6 +232,7 @@ impl InitialQueueTriggers {
         publish_dht_ops: TriggerSender,
         app_validation: TriggerSender,
         integrate_dht_ops: TriggerSender,
+        call_scheduled_fns: TriggerSender,
     ) -> Self {
         Self {
             sys_validation,
@@ -168,6 +240,7 @@ impl InitialQueueTriggers {
             publish_dht_ops,
             app_validation,
             integrate_dht_ops,
+            call_scheduled_fns,
         }
     }
 
@@ -178,6 +251,7 @@ impl InitialQueueTriggers {
         self.publish_dht_ops.trigger();
         self.integrate_dht_ops.trigger();
         self.produce_dht_ops.trigger();
+        self.call_scheduled_fns.trigger();
     }
 }
 /// The means of nudging a queue consumer to tell it to look for more work
@@ -245,14 +319,9 @@ impl OneshotWriter {
     /// Create the writer and pass it into a closure.
     pub fn with_writer<F>(self, f: F) -> Result<(), WorkspaceError>
     where
-        F: FnOnce(&mut Writer) -> Result<(), WorkspaceError> + Send,
+        F: FnMut(&mut Writer) -> Result<(), WorkspaceError> + Send,
     {
-        let env_ref = self.0.guard();
-        env_ref.with_commit::<WorkspaceError, (), _>(|w| {
-            f(w)?;
-            Ok(())
-        })?;
-        Ok(())
+        self.0.with_commit_retry(f)
     }
 }
 
@@ -0,0 +1,57 @@
+//! The workflow and queue consumer for calling scheduled zome functions
+
+use super::*;
+
+use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::call_scheduled_fns_workflow::call_scheduled_fns_workflow;
+use crate::core::workflow::call_scheduled_fns_workflow::CallScheduledFnsWorkspace;
+use holochain_lmdb::env::EnvironmentWrite;
+use holochain_types::prelude::*;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// Spawn the QueueConsumer for CallScheduledFns workflow
+///
+/// Besides being triggered, this consumer wakes itself up whenever the next
+/// scheduled function is due.
+#[instrument(skip(env, stop, conductor_api))]
+pub fn spawn_call_scheduled_fns_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    conductor_api: impl CellConductorApiT + 'static,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let (tx, mut rx) = TriggerSender::new();
+    let handle = tokio::spawn(async move {
+        let mut next_due: Option<Timestamp> = None;
+        loop {
+            // Wait for next job, or until the next function is due
+            let job = match next_due {
+                Some(due) => {
+                    let now: chrono::DateTime<chrono::Utc> = Timestamp::now().into();
+                    let due: chrono::DateTime<chrono::Utc> = due.into();
+                    let delay = due.signed_duration_since(now).to_std().unwrap_or_default();
+                    tokio::time::timeout(delay, next_job_or_exit(&mut rx, &mut stop))
+                        .await
+                        .unwrap_or(Job::Run)
+                }
+                None => next_job_or_exit(&mut rx, &mut stop).await,
+            };
+            if let Job::Shutdown = job {
+                tracing::warn!(
+                    "Cell is shutting down: stopping call_scheduled_fns_workflow queue consumer."
+                );
+                break;
+            }
+
+            // Run the workflow
+            let workspace = CallScheduledFnsWorkspace::new(env.clone().into())
+                .expect("Could not create Workspace");
+            next_due = call_scheduled_fns_workflow(workspace, env.clone().into(), &conductor_api)
+                .await
+                .expect("Error running Workflow");
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
@@ -0,0 +1,59 @@
+//! The queue consumer for the periodic health check of authored ops
+
+use super::*;
+
+use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::health_check_workflow::health_check_workflow;
+use crate::core::workflow::health_check_workflow::HEALTH_CHECK_INTERVAL;
+use crate::core::workflow::health_check_workflow::SLOW_HEAL_INTERVAL;
+use crate::core::workflow::publish_dht_ops_workflow::PublishDhtOpsWorkspace;
+use holochain_lmdb::env::EnvironmentWrite;
+use holochain_types::prelude::*;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// Spawn the consumer for the HealthCheck workflow
+///
+/// This is not triggered by other workflows; it wakes itself up every
+/// [HEALTH_CHECK_INTERVAL] and heals every [SLOW_HEAL_INTERVAL].
+/// The latest report is written to `replication_health`.
+#[instrument(skip(env, stop, cell_network, replication_health))]
+pub fn spawn_health_check_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    mut cell_network: HolochainP2pCell,
+    replication_health: Arc<parking_lot::RwLock<Option<ReplicationHealth>>>,
+) -> JoinHandle<ManagedTaskResult> {
+    tokio::spawn(async move {
+        let mut last_heal = Instant::now();
+        loop {
+            // Wait until the next check is due
+            if tokio::time::timeout(HEALTH_CHECK_INTERVAL, stop.recv())
+                .await
+                .is_ok()
+            {
+                tracing::warn!(
+                    "Cell is shutting down: stopping health_check_workflow queue consumer."
+                );
+                break;
+            }
+
+            // Run the workflow
+            let heal = last_heal.elapsed() >= SLOW_HEAL_INTERVAL;
+            let workspace = PublishDhtOpsWorkspace::new(env.clone().into())
+                .expect("Could not create Workspace");
+            let health =
+                health_check_workflow(workspace, env.clone().into(), &mut cell_network, heal)
+                    .await
+                    .expect("Error running Workflow");
+            if heal {
+                last_heal = Instant::now();
+            }
+            *replication_health.write() = Some(health);
+        }
+        Ok(())
+    })
+}
@@ -32,7 +32,6 @@ use crate::core::ribosome::guest_callback::validation_package::ValidationPackage
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::workflow::CallZomeWorkspaceLock;
-use derive_more::Constructor;
 use error::RibosomeResult;
 use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
@@ -164,6 +163,15 @@ impl HostAccess {
         }
     }
 
+    /// Get the grant that authorized the current zome call,
+    /// or `None` outside an authorized zome call
+    pub fn cap_grant_info(&self) -> Option<&CapGrantInfo> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { cap_grant_info, .. }) => cap_grant_info.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Get the call zome handle, panics if none was provided
     pub fn call_zome_handle(&self) -> &CellConductorReadHandle {
         match self {
@@ -254,8 +262,13 @@ impl ZomeCallInvocation {
     /// - we need to find a live (committed and not deleted) cap grant that matches the secret
     /// - if the live cap grant is for the current author the call is ALWAYS authorized ELSE
     /// - the live cap grant needs to include the invocation's provenance AND zome/function name
+    ///
+    /// returns the grant that authorizes the call, if any
     #[allow(clippy::extra_unused_lifetimes)]
-    pub fn is_authorized<'a>(&self, host_access: &ZomeCallHostAccess) -> RibosomeResult<bool> {
+    pub fn authorized_grant<'a>(
+        &self,
+        host_access: &ZomeCallHostAccess,
+    ) -> RibosomeResult<Option<CapGrantInfo>> {
         let check_function = (self.zome.zome_name().clone(), self.fn_name.clone());
         let check_agent = self.provenance.clone();
         let check_secret = self.cap;
@@ -268,7 +281,10 @@ impl ZomeCallInvocation {
                 .source_chain
                 .valid_cap_grant(&check_function, &check_agent, check_secret.as_ref())?;
 
-            Ok(maybe_grant.is_some())
+            Ok(maybe_grant.map(|cap_grant| CapGrantInfo {
+                cap_grant,
+                provenance: check_agent,
+            }))
         })
     }
 }
@@ -366,7 +382,7 @@ impl From<ZomeCallInvocation> for ZomeCall {
     }
 }
 
-#[derive(Clone, Constructor)]
+#[derive(Clone)]
 pub struct ZomeCallHostAccess {
     pub workspace: CallZomeWorkspaceLock,
     pub keystore: KeystoreSender,
@@ -377,6 +393,30 @@ pub struct ZomeCallHostAccess {
     // "resource" to give access to, but rather it's a bit of data that makes sense in
     // the context of zome calls, but not every CallContext
     pub cell_id: CellId,
+    /// The grant that authorized this call.
+    /// This is filled in by the ribosome once the call has been authorized.
+    pub cap_grant_info: Option<CapGrantInfo>,
+}
+
+impl ZomeCallHostAccess {
+    pub fn new(
+        workspace: CallZomeWorkspaceLock,
+        keystore: KeystoreSender,
+        network: HolochainP2pCell,
+        signal_tx: SignalBroadcaster,
+        call_zome_handle: CellConductorReadHandle,
+        cell_id: CellId,
+    ) -> Self {
+        Self {
+            workspace,
+            keystore,
+            network,
+            signal_tx,
+            call_zome_handle,
+            cell_id,
+            cap_grant_info: None,
+        }
+    }
 }
 
 impl From<ZomeCallHostAccess> for HostAccess {
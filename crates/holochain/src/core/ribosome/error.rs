@@ -5,6 +5,7 @@ use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::core::workflow::produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertError;
 use holo_hash::AnyDhtHash;
+use holo_hash::EntryHash;
 use holochain_cascade::error::CascadeError;
 use holochain_serialized_bytes::prelude::SerializedBytesError;
 use holochain_state::source_chain::SourceChainError;
@@ -103,6 +104,22 @@ pub enum RibosomeError {
     /// ident
     #[error(transparent)]
     SecurePrimitive(#[from] holochain_zome_types::SecurePrimitiveError),
+
+    /// A blob manifest disagreed with itself or its chunks
+    #[error(transparent)]
+    BlobError(#[from] holochain_zome_types::blob::BlobError),
+
+    /// An entry was found but it was not the kind of entry expected
+    #[error("The entry {0} is not a {1}")]
+    UnexpectedEntry(EntryHash, String),
+
+    /// Capability info was asked for outside an authorized zome call
+    #[error("There is no capability grant outside an authorized zome call")]
+    NoCapGrantInfo,
+
+    /// A function was scheduled to recur more often than is allowed
+    #[error("Scheduled functions can't recur more often than every {0:?}, got {1:?}")]
+    ScheduleIntervalTooShort(std::time::Duration, std::time::Duration),
 }
 
 impl From<xsalsa20poly1305::aead::Error> for RibosomeError {
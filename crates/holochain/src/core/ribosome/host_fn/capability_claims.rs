@@ -7,8 +7,17 @@ use std::sync::Arc;
 /// lists all the local claims filtered by tag
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: CapabilityClaimsInput,
+    call_context: Arc<CallContext>,
+    input: CapabilityClaimsInput,
 ) -> RibosomeResult<CapabilityClaimsOutput> {
-    unimplemented!();
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let claims = call_context
+            .host_access
+            .workspace()
+            .read()
+            .await
+            .source_chain
+            .live_cap_claims(input.inner_ref())?;
+        Ok(CapabilityClaimsOutput::new(claims))
+    })
 }
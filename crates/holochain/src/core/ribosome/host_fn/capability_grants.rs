@@ -8,10 +8,19 @@ use std::sync::Arc;
 /// this is only the current grants as per local CRUD
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: CapabilityGrantsInput,
+    call_context: Arc<CallContext>,
+    input: CapabilityGrantsInput,
 ) -> RibosomeResult<CapabilityGrantsOutput> {
-    unimplemented!();
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let grants = call_context
+            .host_access
+            .workspace()
+            .read()
+            .await
+            .source_chain
+            .live_cap_grants(input.inner_ref())?;
+        Ok(CapabilityGrantsOutput::new(grants))
+    })
 }
 
 #[cfg(test)]
@@ -25,7 +34,9 @@ pub mod wasm_test {
     };
     use ::fixt::prelude::*;
     use hdk3::prelude::*;
+    use holochain_lmdb::env::WriteManager;
     use holochain_lmdb::test_utils::test_environments;
+    use holochain_state::workspace::Workspace;
     use holochain_types::fixt::CapSecretFixturator;
     use holochain_types::test_utils::fake_agent_pubkey_1;
     use holochain_types::test_utils::fake_agent_pubkey_2;
@@ -91,6 +102,66 @@ pub mod wasm_test {
         assert_eq!(entry_secret, secret,);
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_capability_grants_and_info<'a>() {
+        observability::test_run().ok();
+        // test workspace boilerplate
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // the author calling their own chain is authorized by authorship
+        let info: CapGrantInfo =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_info", ());
+        assert_eq!(
+            info.cap_grant,
+            CapGrant::ChainAuthor(info.provenance.clone())
+        );
+
+        let secret: CapSecret =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "cap_secret", ());
+        let header_hash: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "transferable_cap_grant",
+            secret
+        );
+
+        // grants are only listed once they are persisted
+        {
+            let mut workspace = workspace_lock.write().await;
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+                .unwrap();
+        }
+
+        let grants: CapabilityGrantsOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "list_cap_grants",
+            CapQuery::all()
+        );
+        let grants = grants.into_inner();
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].header_hash, header_hash);
+        assert_eq!(grants[0].grant.access, CapAccess::from(secret));
+
+        let grants: CapabilityGrantsOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "list_cap_grants",
+            CapQuery::tagged("nope".into())
+        );
+        assert!(grants.into_inner().is_empty());
+    }
+
     // TODO: [ B-03669 ] can move this to an integration test (may need to switch to using a RealDnaStore)
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_authorized_call() {
@@ -1,3 +1,4 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
@@ -6,10 +7,45 @@ use std::sync::Arc;
 
 /// return the access info used for this call
 /// also return who is originated the call (pubkey)
+/// errors in callbacks such as init which no grant authorized
 pub fn capability_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: CapabilityInfoInput,
 ) -> RibosomeResult<CapabilityInfoOutput> {
-    unimplemented!();
+    Ok(CapabilityInfoOutput::new(
+        call_context
+            .host_access
+            .cap_grant_info()
+            .ok_or(RibosomeError::NoCapGrantInfo)?
+            .clone(),
+    ))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::InitHostAccessFixturator;
+    use crate::fixt::RealRibosomeFixturator;
+    use ::fixt::prelude::*;
+    use matches::assert_matches;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn capability_info_outside_zome_call_test() {
+        let ribosome = RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let mut call_context = CallContextFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        call_context.host_access = fixt!(InitHostAccess).into();
+
+        let result = capability_info(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            CapabilityInfoInput::new(()),
+        );
+        assert_matches!(result, Err(RibosomeError::NoCapGrantInfo));
+    }
 }
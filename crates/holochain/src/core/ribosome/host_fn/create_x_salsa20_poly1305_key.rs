@@ -0,0 +1,25 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holochain_keystore::keystore_actor::KeystoreSenderExt;
+use holochain_zome_types::CreateXSalsa20Poly1305KeyInput;
+use holochain_zome_types::CreateXSalsa20Poly1305KeyOutput;
+use std::sync::Arc;
+
+pub fn create_x_salsa20_poly1305_key(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    _input: CreateXSalsa20Poly1305KeyInput,
+) -> RibosomeResult<CreateXSalsa20Poly1305KeyOutput> {
+    Ok(CreateXSalsa20Poly1305KeyOutput::new(
+        tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            call_context
+                .host_access
+                .keystore()
+                .create_x_salsa20_poly1305_key()
+                .await
+        })?,
+    ))
+}
+
+// @see x_salsa20_poly1305_encrypt for testing encryption using created keys.
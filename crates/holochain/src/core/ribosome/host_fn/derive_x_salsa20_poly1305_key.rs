@@ -0,0 +1,25 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holochain_keystore::keystore_actor::KeystoreSenderExt;
+use holochain_zome_types::DeriveXSalsa20Poly1305KeyInput;
+use holochain_zome_types::DeriveXSalsa20Poly1305KeyOutput;
+use std::sync::Arc;
+
+pub fn derive_x_salsa20_poly1305_key(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: DeriveXSalsa20Poly1305KeyInput,
+) -> RibosomeResult<DeriveXSalsa20Poly1305KeyOutput> {
+    Ok(DeriveXSalsa20Poly1305KeyOutput::new(
+        tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            call_context
+                .host_access
+                .keystore()
+                .derive_x_salsa20_poly1305_key(input.into_inner())
+                .await
+        })?,
+    ))
+}
+
+// @see x_salsa20_poly1305_encrypt for testing encryption using derived keys.
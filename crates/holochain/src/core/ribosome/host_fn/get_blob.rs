@@ -0,0 +1,144 @@
+use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use std::sync::Arc;
+
+pub fn get_blob(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetBlobInput,
+) -> RibosomeResult<GetBlobOutput> {
+    let manifest_hash = input.into_inner();
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+
+    // timeouts must be handled by the network
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let mut cascade = workspace.cascade(network);
+
+        let manifest = match cascade
+            .retrieve_entry(manifest_hash.clone(), Default::default())
+            .await?
+        {
+            Some(entry) => decode_blob_entry::<BlobManifest>(entry, "blob manifest")?,
+            None => return Ok(GetBlobOutput::new(None)),
+        };
+        manifest.check()?;
+
+        let chunks = cascade
+            .retrieve_entries_parallel(manifest.chunks.clone(), Default::default())
+            .await?;
+
+        let mut blob = Vec::with_capacity(manifest.size as usize);
+        for (index, (hash, chunk)) in manifest.chunks.iter().zip(chunks).enumerate() {
+            let chunk = chunk.ok_or_else(|| RibosomeError::ElementDeps(hash.clone().into()))?;
+            let chunk = decode_blob_entry::<BlobChunk>(chunk, "blob chunk")?;
+            manifest.check_chunk(index, &chunk)?;
+            blob.extend(Vec::<u8>::from(chunk));
+        }
+
+        Ok(GetBlobOutput::new(Some(Bytes::from(blob))))
+    })
+}
+
+/// Deserialize an app entry that is part of a blob.
+fn decode_blob_entry<T>(entry: EntryHashed, kind: &str) -> RibosomeResult<T>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let (entry, hash) = entry.into_inner();
+    match entry {
+        Entry::App(bytes) => Ok(T::try_from(SerializedBytes::from(bytes))?),
+        _ => Err(RibosomeError::UnexpectedEntry(hash, kind.to_string())),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::decode_blob_entry;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::host_fn::get_blob::get_blob;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
+    use holochain_state::source_chain::SourceChainResult;
+    use holochain_types::prelude::*;
+    use std::sync::Arc;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn get_blob_reassembles_chunks() {
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace =
+            crate::core::workflow::CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let bytes: Vec<u8> = (0..(2 * BLOB_CHUNK_SIZE + 3))
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut hashes = Vec::new();
+        for chunk in BlobManifest::chunk(&bytes) {
+            let entry = Entry::app(chunk.try_into().unwrap()).unwrap();
+            hashes.push(put_entry(&mut workspace, entry).await.unwrap());
+        }
+        let manifest = BlobManifest::new(bytes.len() as u64, hashes);
+        let manifest_hash = put_entry(
+            &mut workspace,
+            Entry::app(manifest.try_into().unwrap()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+        let ribosome = RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.host_access = host_access.into();
+
+        let output = get_blob(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            GetBlobInput::new(manifest_hash),
+        )
+        .unwrap();
+        assert_eq!(output.into_inner().map(|b| b.into_vec()), Some(bytes));
+    }
+
+    #[test]
+    fn blob_entries_must_be_app_entries() {
+        let entry = EntryHashed::from_content_sync(Entry::Agent(fixt!(AgentPubKey)));
+        let hash = entry.as_hash().clone();
+        match decode_blob_entry::<BlobChunk>(entry, "blob chunk") {
+            Err(RibosomeError::UnexpectedEntry(h, kind)) => {
+                assert_eq!(h, hash);
+                assert_eq!(kind, "blob chunk");
+            }
+            other => panic!("expected UnexpectedEntry, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    async fn put_entry(
+        workspace: &mut crate::core::workflow::CallZomeWorkspace,
+        entry: Entry,
+    ) -> SourceChainResult<EntryHash> {
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        let header_builder = builder::Create {
+            entry_type: EntryType::App(AppEntryTypeFixturator::new(Unpredictable).next().unwrap()),
+            entry_hash: entry_hash.clone(),
+        };
+        workspace
+            .source_chain
+            .put(header_builder, Some(entry))
+            .await?;
+        Ok(entry_hash)
+    }
+}
@@ -12,7 +12,7 @@ pub fn get_links<'a>(
     call_context: Arc<CallContext>,
     input: GetLinksInput,
 ) -> RibosomeResult<GetLinksOutput> {
-    let (base_address, tag) = input.into_inner();
+    let (base_address, tag, filter) = input.into_inner();
 
     // Get zome id
     let zome_id = ribosome.zome_to_id(&call_context.zome)?;
@@ -34,7 +34,13 @@ pub fn get_links<'a>(
             .write()
             .await
             .cascade(network)
-            .dht_get_links(&key, GetLinksOptions::default())
+            .dht_get_links(
+                &key,
+                GetLinksOptions {
+                    filter,
+                    ..Default::default()
+                },
+            )
             .await?;
 
         Ok(GetLinksOutput::new(links.into()))
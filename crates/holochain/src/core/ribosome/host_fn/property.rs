@@ -4,10 +4,100 @@ use crate::core::ribosome::RibosomeT;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 
+/// return the dna properties, or the value at the input path inside them
+/// properties are set in the dna file and can be overridden at install time
 pub fn property(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     _call_context: Arc<CallContext>,
-    _input: PropertyInput,
+    input: PropertyInput,
 ) -> RibosomeResult<PropertyOutput> {
-    unimplemented!();
+    let properties = ribosome.dna_def().properties.clone();
+    let path = input.into_inner();
+    if path.is_root() {
+        return Ok(PropertyOutput::new(Some(properties)));
+    }
+
+    let value: serde_json::Value = holochain_serialized_bytes::decode(properties.bytes())?;
+    let selected = path
+        .0
+        .iter()
+        .try_fold(&value, |value, segment| match value {
+            serde_json::Value::Object(map) => map.get(segment),
+            serde_json::Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index)),
+            _ => None,
+        });
+
+    Ok(PropertyOutput::new(
+        selected
+            .map(|value| SerializedBytes::try_from(JsonProperties::new(value.clone())))
+            .transpose()?,
+    ))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod test {
+    use super::*;
+    use crate::core::ribosome::real_ribosome::RealRibosome;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::RealRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    async fn ribosome_with_properties(properties: serde_json::Value) -> Arc<RealRibosome> {
+        let ribosome =
+            RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Foo.into()]))
+                .next()
+                .unwrap();
+        let dna_file = ribosome
+            .dna_file
+            .with_properties(SerializedBytes::try_from(JsonProperties::new(properties)).unwrap())
+            .await
+            .unwrap();
+        Arc::new(RealRibosome::new(dna_file))
+    }
+
+    fn call_property(ribosome: Arc<impl RibosomeT>, path: &str) -> Option<serde_json::Value> {
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.host_access = fixt!(ZomeCallHostAccess).into();
+        property(
+            ribosome,
+            Arc::new(call_context),
+            PropertyPath::from(path).into(),
+        )
+        .unwrap()
+        .into_inner()
+        .map(|sb| holochain_serialized_bytes::decode(sb.bytes()).unwrap())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn property_selects_by_path() {
+        let ribosome = ribosome_with_properties(serde_json::json!({
+            "limits": { "max_posts": 10 },
+            "admins": ["alice", "bob"],
+        }))
+        .await;
+
+        assert_eq!(
+            call_property(ribosome.clone(), ""),
+            Some(serde_json::json!({
+                "limits": { "max_posts": 10 },
+                "admins": ["alice", "bob"],
+            }))
+        );
+        assert_eq!(
+            call_property(ribosome.clone(), "limits.max_posts"),
+            Some(serde_json::json!(10))
+        );
+        assert_eq!(
+            call_property(ribosome.clone(), "admins.1"),
+            Some(serde_json::json!("bob"))
+        );
+        assert_eq!(call_property(ribosome.clone(), "admins.2"), None);
+        assert_eq!(call_property(ribosome, "limits.missing"), None);
+    }
 }
@@ -1,13 +1,140 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::CallZomeWorkspace;
+use holochain_state::scheduled_fn_db::ScheduledFn;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 
+/// Schedule a function in the calling zome to be called back by the conductor.
+/// The schedule is written to the workspace, so it is only persisted if the
+/// zome call that made it succeeds.
 pub fn schedule(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: ScheduleInput,
+    call_context: Arc<CallContext>,
+    input: ScheduleInput,
 ) -> RibosomeResult<ScheduleOutput> {
-    unimplemented!()
+    let schedule = input.into_inner();
+    if let Some(every) = schedule.every {
+        if every < MIN_SCHEDULE_INTERVAL {
+            return Err(RibosomeError::ScheduleIntervalTooShort(
+                MIN_SCHEDULE_INTERVAL,
+                every,
+            ));
+        }
+    }
+    let due = Timestamp::now().saturating_add(&schedule.after);
+    let scheduled_fn = ScheduledFn::new(call_context.zome.zome_name().clone(), schedule);
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let mut guard = call_context.host_access.workspace().write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        workspace.scheduled_fns.schedule(due, scheduled_fn)?;
+        Ok(ScheduleOutput::new(()))
+    })
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_lmdb::env::ReadManager;
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn recurring_faster_than_the_minimum_is_rejected() {
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let call_context = Arc::new(call_context);
+
+        let schedule_every = |every: Duration| {
+            schedule(
+                Arc::new(MockRibosomeT::new()),
+                call_context.clone(),
+                ScheduleInput::new(Schedule::recurring(
+                    "tick".into(),
+                    SerializedBytes::try_from(()).unwrap(),
+                    Duration::from_secs(0),
+                    every,
+                )),
+            )
+        };
+        for every in vec![Duration::from_secs(0), Duration::from_millis(999)] {
+            assert!(matches!(
+                schedule_every(every),
+                Err(RibosomeError::ScheduleIntervalTooShort(_, _))
+            ));
+        }
+        assert!(schedule_every(MIN_SCHEDULE_INTERVAL).is_ok());
+
+        let guard = workspace_lock.read().await;
+        let env_ref = env.guard();
+        let reader = env_ref.reader().unwrap();
+        assert_eq!(
+            guard
+                .scheduled_fns
+                .due(
+                    &reader,
+                    Timestamp::now().saturating_add(&MIN_SCHEDULE_INTERVAL)
+                )
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::workflow::CallZomeWorkspace;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_lmdb::env::ReadManager;
+    use holochain_test_wasm_common::TestInt;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn schedule_test() {
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        let before = Timestamp::now();
+        let _: () = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Schedule,
+            "schedule_tick",
+            TestInt(60_000)
+        );
+
+        // scheduled in the workspace, to be persisted with the zome call
+        let guard = workspace_lock.read().await;
+        let env_ref = env.guard();
+        let reader = env_ref.reader().unwrap();
+        let next_due = guard.scheduled_fns.next_due(&reader).unwrap().unwrap();
+        assert!(next_due >= before.saturating_add(&std::time::Duration::from_secs(60)));
+        let due = guard.scheduled_fns.due(&reader, next_due).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.zome_name, TestWasm::Schedule.into());
+        assert_eq!(due[0].1.schedule.fn_name, "tick".into());
+    }
 }
@@ -35,11 +35,14 @@ use crate::core::ribosome::host_fn::capability_info::capability_info;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
+use crate::core::ribosome::host_fn::create_x_salsa20_poly1305_key::create_x_salsa20_poly1305_key;
 use crate::core::ribosome::host_fn::debug::debug;
 use crate::core::ribosome::host_fn::delete::delete;
 use crate::core::ribosome::host_fn::delete_link::delete_link;
+use crate::core::ribosome::host_fn::derive_x_salsa20_poly1305_key::derive_x_salsa20_poly1305_key;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
 use crate::core::ribosome::host_fn::get::get;
+use crate::core::ribosome::host_fn::get_blob::get_blob;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
@@ -188,6 +191,14 @@ impl RealRibosome {
                 "__create_x25519_keypair",
                 func!(invoke_host_function!(create_x25519_keypair)),
             );
+            ns.insert(
+                "__create_x_salsa20_poly1305_key",
+                func!(invoke_host_function!(create_x_salsa20_poly1305_key)),
+            );
+            ns.insert(
+                "__derive_x_salsa20_poly1305_key",
+                func!(invoke_host_function!(derive_x_salsa20_poly1305_key)),
+            );
             ns.insert(
                 "__x_salsa20_poly1305_encrypt",
                 func!(invoke_host_function!(x_salsa20_poly1305_encrypt)),
@@ -214,6 +225,14 @@ impl RealRibosome {
                 "__create_x25519_keypair",
                 func!(invoke_host_function!(unreachable)),
             );
+            ns.insert(
+                "__create_x_salsa20_poly1305_key",
+                func!(invoke_host_function!(unreachable)),
+            );
+            ns.insert(
+                "__derive_x_salsa20_poly1305_key",
+                func!(invoke_host_function!(unreachable)),
+            );
             ns.insert(
                 "__x_salsa20_poly1305_encrypt",
                 func!(invoke_host_function!(unreachable)),
@@ -308,6 +327,7 @@ impl RealRibosome {
                 "__get_agent_activity",
                 func!(invoke_host_function!(get_agent_activity)),
             );
+            ns.insert("__get_blob", func!(invoke_host_function!(get_blob)));
             ns.insert("__query", func!(invoke_host_function!(query)));
         } else {
             ns.insert("__get", func!(invoke_host_function!(unreachable)));
@@ -321,6 +341,7 @@ impl RealRibosome {
                 "__get_agent_activity",
                 func!(invoke_host_function!(unreachable)),
             );
+            ns.insert("__get_blob", func!(invoke_host_function!(unreachable)));
             ns.insert("__query", func!(invoke_host_function!(unreachable)));
         }
 
@@ -457,10 +478,11 @@ impl RibosomeT for RealRibosome {
     /// so that it can be passed on to source chain manager for transactional writes
     fn call_zome_function(
         &self,
-        host_access: ZomeCallHostAccess,
+        mut host_access: ZomeCallHostAccess,
         invocation: ZomeCallInvocation,
     ) -> RibosomeResult<ZomeCallResponse> {
-        Ok(if invocation.is_authorized(&host_access)? {
+        host_access.cap_grant_info = invocation.authorized_grant(&host_access)?;
+        Ok(if host_access.cap_grant_info.is_some() {
             // make a copy of these for the error handling below
             let zome_name = invocation.zome.zome_name().clone();
             let fn_name = invocation.fn_name.clone();
@@ -173,6 +173,17 @@ pub fn check_prev_seq(header: &Header, prev_header: &Header) -> SysValidationRes
     }
 }
 
+/// Check the previous header didn't close the chain.
+/// Nothing can be added to a chain after a `CloseChain`.
+pub fn check_prev_not_closed(prev_header: &Header) -> SysValidationResult<()> {
+    match prev_header {
+        Header::CloseChain(_) => {
+            Err(PrevHeaderError::ChainClosed).map_err(|e| ValidationOutcome::from(e).into())
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Check the entry variant matches the variant in the headers entry type
 pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationResult<()> {
     match (entry_type, entry) {
@@ -261,6 +272,46 @@ pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
     }
 }
 
+/// Check a blob manifest entry decodes and lists
+/// the right number of chunks for the size of the blob.
+pub fn check_blob_manifest(
+    entry_hash: &EntryHash,
+    entry: &Entry,
+) -> SysValidationResult<BlobManifest> {
+    let manifest = match entry {
+        Entry::App(bytes) => BlobManifest::try_from(SerializedBytes::from(bytes.clone())).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| ValidationOutcome::NotBlobEntry(entry_hash.clone()))?;
+    manifest.check().map_err(ValidationOutcome::from)?;
+    Ok(manifest)
+}
+
+/// Check every chunk in a blob manifest can be found
+/// and has the right length for its position in the blob.
+pub async fn check_blob_chunks(
+    manifest: &BlobManifest,
+    workspace: &mut SysValidationWorkspace,
+    network: HolochainP2pCell,
+) -> SysValidationResult<()> {
+    let mut cascade = workspace.full_cascade(network);
+    let chunks = cascade
+        .retrieve_entries_parallel(manifest.chunks.clone(), Default::default())
+        .await?;
+    for (index, (hash, chunk)) in manifest.chunks.iter().zip(chunks).enumerate() {
+        let chunk = chunk.ok_or_else(|| ValidationOutcome::not_found(hash))?;
+        let chunk = match chunk.into_content() {
+            Entry::App(bytes) => BlobChunk::try_from(SerializedBytes::from(bytes)).ok(),
+            _ => None,
+        }
+        .ok_or_else(|| ValidationOutcome::NotBlobEntry(hash.clone()))?;
+        manifest
+            .check_chunk(index, &chunk)
+            .map_err(ValidationOutcome::from)?;
+    }
+    Ok(())
+}
+
 /// Check the link tag size is under the MAX_TAG_SIZE
 pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
     let size = std::mem::size_of_val(&tag.0[..]);
@@ -456,7 +507,7 @@ impl IncomingDhtOpSender {
     ) -> SysValidationResult<()> {
         if let Some(op) = make_op(element) {
             let ops = vec![op];
-            incoming_dht_ops_workflow(&self.env, self.sys_validation_trigger, ops, None)
+            incoming_dht_ops_workflow(&self.env, self.sys_validation_trigger, ops, None, false)
                 .await
                 .map_err(Box::new)?;
         }
@@ -86,6 +86,8 @@ impl<E> TryFrom<OutcomeOrError<ValidationOutcome, E>> for ValidationOutcome {
 /// failed validation.
 #[derive(Error, Debug)]
 pub enum ValidationOutcome {
+    #[error(transparent)]
+    BlobManifest(#[from] BlobError),
     #[error("The element with signature {0:?} and header {1:?} was found to be counterfeit")]
     Counterfeit(Signature, Header),
     #[error("The dependency {0:?} was not found on the DHT")]
@@ -106,6 +108,8 @@ pub enum ValidationOutcome {
     NotCreateLink(HeaderHash),
     #[error("The header was expected to be a new entry header but was a {0:?}")]
     NotNewEntry(Header),
+    #[error("The entry {0:?} is not a valid part of a blob")]
+    NotBlobEntry(EntryHash),
     #[error("The dependency {0:?} is not held")]
     NotHoldingDep(AnyDhtHash),
     #[error(transparent)]
@@ -137,6 +141,8 @@ impl ValidationOutcome {
 
 #[derive(Error, Debug)]
 pub enum PrevHeaderError {
+    #[error("The previous header closed the chain so nothing can follow it")]
+    ChainClosed,
     #[error("Root of source chain must be Dna")]
     InvalidRoot,
     #[error("Previous header sequence number {1} is not {0} - 1")]
@@ -154,6 +154,18 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn check_previous_not_closed() {
+    assert_matches!(check_prev_not_closed(&fixt!(CreateLink).into()), Ok(()));
+    assert_matches!(check_prev_not_closed(&fixt!(OpenChain).into()), Ok(()));
+    assert_matches!(
+        check_prev_not_closed(&fixt!(CloseChain).into()),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevHeaderError(PrevHeaderError::ChainClosed)
+        ))
+    );
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -361,3 +373,29 @@ async fn check_entry_not_private_test() {
         ))
     );
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn check_blob_manifest_test() {
+    let chunks = vec![fixt!(EntryHash), fixt!(EntryHash)];
+    let manifest = BlobManifest::new(BLOB_CHUNK_SIZE as u64 + 1, chunks.clone());
+    let entry = Entry::app(manifest.clone().try_into().unwrap()).unwrap();
+    let eh = EntryHash::with_data_sync(&entry);
+    assert_matches!(check_blob_manifest(&eh, &entry), Ok(m) if m == manifest);
+
+    let short = BlobManifest::new(3 * BLOB_CHUNK_SIZE as u64, chunks);
+    let entry = Entry::app(short.try_into().unwrap()).unwrap();
+    assert_matches!(
+        check_blob_manifest(&eh, &entry),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::BlobManifest(BlobError::ChunkCount { .. })
+        ))
+    );
+
+    let entry = Entry::app(BlobChunk::new(vec![0; 8]).try_into().unwrap()).unwrap();
+    assert_matches!(
+        check_blob_manifest(&eh, &entry),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotBlobEntry(_)
+        ))
+    );
+}
@@ -3,12 +3,17 @@ use std::convert::TryFrom;
 
 use derivative::Derivative;
 use holo_hash::DhtOpHash;
+use holochain_p2p::HolochainP2pCellT;
+use holochain_state::metadata::MetadataBufT;
 use holochain_types::dht_op::DhtOp;
+use holochain_types::prelude::*;
+use tracing::*;
 
 use super::workflow::error::WorkflowResult;
 use super::SourceChainError;
 use super::SysValidationError;
 use super::ValidationOutcome;
+use crate::conductor::api::CellConductorApiT;
 
 /// Exit early with either an outcome or an error
 pub enum OutcomeOrError<T, E> {
@@ -83,3 +88,29 @@ impl OutcomeOrError<ValidationOutcome, SysValidationError> {
         Err(SourceChainError::InvalidCommit(ValidationOutcome::try_from(self)?.to_string()).into())
     }
 }
+
+/// Sign a warrant against another agent as this cell's agent,
+/// hold it in the metadata vault and send it on to the
+/// warranted agent's activity authorities.
+/// Failing to send is only logged as other authorities
+/// will come across the same proof when they validate.
+pub async fn issue_warrant<M: MetadataBufT, N: HolochainP2pCellT>(
+    proof: WarrantProof,
+    meta_vault: &mut M,
+    network: &mut N,
+    conductor_api: &impl CellConductorApiT,
+) -> WorkflowResult<()> {
+    let warrant = Warrant {
+        proof,
+        author: conductor_api.cell_id().agent_pubkey().clone(),
+        timestamp: Timestamp::now().into(),
+    };
+    let warrant = SignedWarrant::new(conductor_api.keystore(), warrant)
+        .await
+        .map_err(SourceChainError::from)?;
+    meta_vault.register_warrant(warrant.clone())?;
+    if let Err(e) = network.put_warrant(warrant, None).await {
+        warn!(msg = "Failed to send warrant", ?e);
+    }
+    Ok(())
+}
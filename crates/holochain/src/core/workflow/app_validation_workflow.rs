@@ -3,6 +3,7 @@
 use std::collections::BinaryHeap;
 use std::convert::TryInto;
 use std::sync::Arc;
+use std::time::Instant;
 
 use self::validation_package::get_as_author_custom;
 use self::validation_package::get_as_author_full;
@@ -15,6 +16,7 @@ use super::CallZomeWorkspace;
 use super::CallZomeWorkspaceLock;
 use crate::conductor::api::CellConductorApiT;
 use crate::conductor::entry_def_store::get_entry_def;
+use crate::core::metrics::record_workflow_run;
 use crate::core::queue_consumer::OneshotWriter;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
@@ -33,6 +35,7 @@ use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::validation::issue_warrant;
 use crate::core::validation::DhtOpOrder;
 use crate::core::validation::OrderedOp;
 use error::AppValidationResult;
@@ -81,7 +84,7 @@ pub async fn app_validation_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_integration.trigger();
@@ -93,6 +96,7 @@ async fn app_validation_workflow_inner(
     conductor_api: impl CellConductorApiT,
     network: &HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
+    let start = Instant::now();
     let env = workspace.validation_limbo.env().clone();
 
     // Drain the ops into a sorted binary heap
@@ -132,6 +136,9 @@ async fn app_validation_workflow_inner(
         sorted_ops
     })?;
 
+    let queued = sorted_ops.len();
+    let mut processed = 0;
+
     // Validate all the ops
     for so in sorted_ops.into_sorted_vec() {
         let OrderedOp {
@@ -157,9 +164,11 @@ async fn app_validation_workflow_inner(
 
                 match outcome {
                     Outcome::Accepted => {
+                        processed += 1;
                         let iv = IntegrationLimboValue {
                             validation_status: ValidationStatus::Valid,
                             op: vlv.op,
+                            send_receipt: vlv.send_receipt,
                         };
                         workspace.put_int_limbo(hash, iv, op)?;
                     }
@@ -167,10 +176,27 @@ async fn app_validation_workflow_inner(
                         vlv.status = ValidationLimboStatus::AwaitingAppDeps(deps);
                         workspace.put_val_limbo(hash, vlv)?;
                     }
-                    Outcome::Rejected(_) => {
+                    Outcome::Rejected(reason) => {
+                        processed += 1;
+                        let (signature, header, entry) = op.clone().into_inner();
+                        let proof = WarrantProof::InvalidChainOp {
+                            header: SignedHeader(header, signature),
+                            entry,
+                            op_type: op.op_type(),
+                            validation_type: ValidationType::App,
+                            reason,
+                        };
+                        issue_warrant(
+                            proof,
+                            &mut workspace.meta_vault,
+                            &mut network.clone(),
+                            &conductor_api,
+                        )
+                        .await?;
                         let iv = IntegrationLimboValue {
                             op: vlv.op,
                             validation_status: ValidationStatus::Rejected,
+                            send_receipt: vlv.send_receipt,
                         };
                         workspace.put_int_limbo(hash, iv, op)?;
                     }
@@ -179,6 +205,7 @@ async fn app_validation_workflow_inner(
             _ => unreachable!("Should not contain any other status"),
         }
     }
+    record_workflow_run("app_validation", env.kind(), queued, processed, start);
     Ok(WorkComplete::Complete)
 }
 
@@ -1034,6 +1061,8 @@ impl Workspace for AppValidationWorkspace {
         self.integration_limbo.flush_to_txn_ref(writer)?;
         self.element_pending.flush_to_txn_ref(writer)?;
         self.meta_pending.flush_to_txn_ref(writer)?;
+        // Only holds warrants issued during validation
+        self.meta_vault.flush_to_txn_ref(writer)?;
 
         // Flush for cascade
         self.element_cache.flush_to_txn_ref(writer)?;
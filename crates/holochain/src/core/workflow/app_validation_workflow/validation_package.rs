@@ -22,7 +22,8 @@ pub fn get_as_author_sub_chain(
         &ChainQueryFilter::default()
             .include_entries(true)
             .entry_type(EntryType::App(app_entry_type))
-            .sequence_range(0..header_seq),
+            .sequence_range(0..header_seq)
+            .descending(true),
     )?;
     Ok(ValidationPackage::new(elements))
 }
@@ -34,7 +35,8 @@ pub fn get_as_author_full(
     let elements = source_chain.query(
         &ChainQueryFilter::default()
             .include_entries(true)
-            .sequence_range(0..header_seq),
+            .sequence_range(0..header_seq)
+            .descending(true),
     )?;
     Ok(ValidationPackage::new(elements))
 }
@@ -0,0 +1,170 @@
+//! # Call Scheduled Fns Workflow
+//!
+//! Calls back every zome function scheduled via the `schedule` host function
+//! which is now due. Due functions are taken off the schedule (and recurring
+//! ones put back on it at their next time) before any of them are called, so
+//! a callback which fails or panics is not retried in a hot loop.
+
+use super::error::WorkflowResult;
+use crate::conductor::api::CellConductorApiT;
+use crate::core::queue_consumer::OneshotWriter;
+use holochain_conductor_api::ZomeCall;
+use holochain_lmdb::fresh_reader;
+use holochain_lmdb::prelude::*;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use tracing::*;
+
+/// Database buffers required for calling scheduled functions
+pub struct CallScheduledFnsWorkspace {
+    pub scheduled_fns: ScheduledFnStore,
+}
+
+impl CallScheduledFnsWorkspace {
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        Ok(Self {
+            scheduled_fns: ScheduledFnStore::new(env)?,
+        })
+    }
+}
+
+impl Workspace for CallScheduledFnsWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.scheduled_fns.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+/// Call all the due functions and return the time the next one is due, if any.
+#[instrument(skip(workspace, writer, conductor_api))]
+pub async fn call_scheduled_fns_workflow<C: CellConductorApiT>(
+    mut workspace: CallScheduledFnsWorkspace,
+    writer: OneshotWriter,
+    conductor_api: &C,
+) -> WorkflowResult<Option<Timestamp>> {
+    let now = Timestamp::now();
+    let to_call = call_scheduled_fns_workflow_inner(&mut workspace, now)?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace before calling anything so the schedule is
+    // already up to date if a callback schedules something new
+    let env = workspace.scheduled_fns.env().clone();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    let cell_id = conductor_api.cell_id().clone();
+    for ScheduledFn {
+        zome_name,
+        schedule,
+    } in to_call
+    {
+        let call = ZomeCall {
+            cell_id: cell_id.clone(),
+            zome_name,
+            fn_name: schedule.fn_name,
+            cap: None,
+            payload: ExternInput::new(schedule.payload),
+            provenance: cell_id.agent_pubkey().clone(),
+        };
+        match conductor_api.call_zome(&cell_id, call.clone()).await {
+            Ok(Ok(ZomeCallResponse::Ok(_))) => {}
+            result => warn!(
+                msg = "A scheduled zome function did not complete",
+                zome = ?call.zome_name,
+                function = ?call.fn_name,
+                ?result
+            ),
+        }
+    }
+
+    let scheduled_fns = ScheduledFnStore::new(env.clone())?;
+    Ok(fresh_reader!(env, |r| scheduled_fns.next_due(&r))?)
+}
+
+/// Take every function due at `now` off the schedule, rescheduling the
+/// recurring ones, and return them in the order they became due.
+fn call_scheduled_fns_workflow_inner(
+    workspace: &mut CallScheduledFnsWorkspace,
+    now: Timestamp,
+) -> WorkflowResult<Vec<ScheduledFn>> {
+    let env = workspace.scheduled_fns.env().clone();
+    let due = fresh_reader!(env, |r| workspace.scheduled_fns.due(&r, now))?;
+    let mut to_call = Vec::with_capacity(due.len());
+    for (key, scheduled_fn) in due {
+        workspace.scheduled_fns.unschedule(key)?;
+        if let Some(every) = scheduled_fn.schedule.every {
+            workspace
+                .scheduled_fns
+                .schedule(now.saturating_add(&every), scheduled_fn.clone())?;
+        }
+        to_call.push(scheduled_fn);
+    }
+    Ok(to_call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_lmdb::env::ReadManager;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use std::time::Duration;
+
+    fn scheduled_fn(fn_name: &str, every: Option<Duration>) -> ScheduledFn {
+        let mut schedule = Schedule::once(
+            fn_name.into(),
+            SerializedBytes::try_from(()).unwrap(),
+            Duration::from_secs(0),
+        );
+        schedule.every = every;
+        ScheduledFn::new("foo".into(), schedule)
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn due_fns_are_taken_and_recurring_fns_rescheduled() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let now = Timestamp(100, 0);
+
+        {
+            let mut workspace = CallScheduledFnsWorkspace::new(env.clone().into()).unwrap();
+            let scheduled_fns = &mut workspace.scheduled_fns;
+            scheduled_fns
+                .schedule(Timestamp(50, 0), scheduled_fn("once", None))
+                .unwrap();
+            scheduled_fns
+                .schedule(
+                    Timestamp(60, 0),
+                    scheduled_fn("recurring", Some(Duration::from_secs(10))),
+                )
+                .unwrap();
+            scheduled_fns
+                .schedule(Timestamp(200, 0), scheduled_fn("later", None))
+                .unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let mut workspace = CallScheduledFnsWorkspace::new(env.clone().into()).unwrap();
+        let to_call = call_scheduled_fns_workflow_inner(&mut workspace, now).unwrap();
+        assert_eq!(
+            to_call
+                .into_iter()
+                .map(|f| f.schedule.fn_name.0)
+                .collect::<Vec<_>>(),
+            vec!["once".to_string(), "recurring".to_string()]
+        );
+        env.guard()
+            .with_commit(|writer| workspace.flush_to_txn(writer))
+            .unwrap();
+
+        let scheduled_fns = ScheduledFnStore::new(env.clone().into()).unwrap();
+        let env_ref = env.guard();
+        let reader = env_ref.reader().unwrap();
+        assert_eq!(
+            scheduled_fns.next_due(&reader).unwrap(),
+            Some(Timestamp(110, 0))
+        );
+        assert!(scheduled_fns.due(&reader, now).unwrap().is_empty());
+    }
+}
@@ -21,6 +21,7 @@ use holochain_p2p::HolochainP2pCell;
 use holochain_state::element_buf::ElementBuf;
 use holochain_state::metadata::MetadataBuf;
 use holochain_state::metadata::MetadataBufT;
+use holochain_state::scheduled_fn_db::ScheduledFnStore;
 use holochain_state::source_chain::SourceChain;
 use holochain_state::source_chain::SourceChainError;
 use holochain_state::workspace::Workspace;
@@ -269,6 +270,7 @@ pub struct CallZomeWorkspace {
     pub meta_rejected: MetadataBuf<RejectedPrefix>,
     pub element_cache: ElementBuf,
     pub meta_cache: MetadataBuf,
+    pub scheduled_fns: ScheduledFnStore,
 }
 
 impl<'a> CallZomeWorkspace {
@@ -280,7 +282,8 @@ impl<'a> CallZomeWorkspace {
         let element_rejected = ElementBuf::rejected(env.clone())?;
         let meta_rejected = MetadataBuf::rejected(env.clone())?;
         let element_cache = ElementBuf::cache(env.clone())?;
-        let meta_cache = MetadataBuf::cache(env)?;
+        let meta_cache = MetadataBuf::cache(env.clone())?;
+        let scheduled_fns = ScheduledFnStore::new(env)?;
 
         Ok(CallZomeWorkspace {
             source_chain,
@@ -291,6 +294,7 @@ impl<'a> CallZomeWorkspace {
             meta_rejected,
             element_cache,
             meta_cache,
+            scheduled_fns,
         })
     }
 
@@ -331,6 +335,7 @@ impl Workspace for CallZomeWorkspace {
         self.meta_authored.flush_to_txn_ref(writer)?;
         self.element_cache.flush_to_txn_ref(writer)?;
         self.meta_cache.flush_to_txn_ref(writer)?;
+        self.scheduled_fns.flush_to_txn_ref(writer)?;
         Ok(())
     }
 }
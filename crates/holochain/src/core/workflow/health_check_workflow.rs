@@ -0,0 +1,505 @@
+//! # Health Check Workflow
+//!
+//! Counts the valid receipts held for every op this cell has authored.
+//! Receipts can be lost or never sent, so the authorities for the basis of
+//! each op short of receipts are also asked whether they hold it, and the
+//! higher of the two counts is taken.
+//! Ops still short of [DEFAULT_RECEIPT_BUNDLE_SIZE] are republished straight away,
+//! asking the authorities for receipts, instead of waiting for the publish
+//! workflow to come around to them. Ops published within the last
+//! [MIN_PUBLISH_INTERVAL] are left alone.
+//!
+//! A slow heal also republishes the ops which are already replicated without
+//! asking for receipts, so authorities that have taken over part of the DHT
+//! since the op was published come to hold it too.
+
+use super::error::WorkflowResult;
+use super::produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertError;
+use super::produce_dht_ops_workflow::dht_op_light::light_to_op;
+use super::publish_dht_ops_workflow::PublishDhtOpsWorkspace;
+use super::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE;
+use super::publish_dht_ops_workflow::MIN_PUBLISH_INTERVAL;
+use crate::core::queue_consumer::OneshotWriter;
+use fallible_iterator::FallibleIterator;
+use holo_hash::*;
+use holochain_lmdb::buffer::BufferedStore;
+use holochain_lmdb::fresh_reader;
+use holochain_lmdb::prelude::*;
+use holochain_p2p::actor::GetMetaOptions;
+use holochain_p2p::actor::GetOptions;
+use holochain_p2p::HolochainP2pCellT;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use tracing::*;
+
+/// How often each cell checks the replication of its authored ops
+pub const HEALTH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often the health check also republishes ops which are already replicated
+pub const SLOW_HEAL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Ops to publish by basis
+type OpsByBasis = HashMap<AnyDhtHash, Vec<(DhtOpHash, DhtOp)>>;
+
+/// What a health check found and what it needs to republish
+pub struct HealthCheckOutcome {
+    /// The report on the authored ops
+    pub health: ReplicationHealth,
+    /// Ops below the receipt target that are due to be published again
+    pub under_replicated: OpsByBasis,
+    /// Ops at the receipt target, only collected when healing
+    pub replicated: OpsByBasis,
+}
+
+#[instrument(skip(workspace, writer, network))]
+pub async fn health_check_workflow<N: HolochainP2pCellT>(
+    mut workspace: PublishDhtOpsWorkspace,
+    writer: OneshotWriter,
+    network: &mut N,
+    heal: bool,
+) -> WorkflowResult<ReplicationHealth> {
+    let HealthCheckOutcome {
+        health,
+        under_replicated,
+        replicated,
+    } = health_check_workflow_inner(&mut workspace, network, heal).await?;
+
+    for (basis, ops) in under_replicated {
+        network.publish(true, basis, ops, None).await?;
+    }
+    for (basis, ops) in replicated {
+        network.publish(false, basis, ops, None).await?;
+    }
+    if !health.is_healthy() {
+        info!(
+            under_replicated = health.under_replicated.len(),
+            authored = health.authored_ops,
+            "Found under replicated ops"
+        );
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    Ok(health)
+}
+
+/// Every authored op which can be published, along with the number
+/// of valid receipts held for it
+pub fn authored_ops_with_receipts(
+    workspace: &PublishDhtOpsWorkspace,
+) -> WorkflowResult<Vec<(DhtOpHash, AuthoredDhtOpsValue, DhtOp)>> {
+    let env = workspace.elements().headers().env().clone();
+    let values = fresh_reader!(env, |r| {
+        let authored: Vec<(DhtOpHash, AuthoredDhtOpsValue)> = workspace
+            .authored()
+            .iter(&r)?
+            .map(|(k, value)| Ok((DhtOpHash::from_raw_39_panicky(k.to_vec()), value)))
+            .collect()?;
+        // Receipts may have arrived since the value was last written,
+        // so the receipts db has the final say on the count
+        let validation_receipts = workspace.validation_receipts();
+        authored
+            .into_iter()
+            .map(|(op_hash, mut value)| {
+                value.receipt_count = std::cmp::max(
+                    value.receipt_count,
+                    validation_receipts.count_valid(&r, &op_hash)? as u32,
+                );
+                Ok((op_hash, value))
+            })
+            .collect::<DatabaseResult<Vec<_>>>()
+    })?;
+
+    let mut ops = Vec::with_capacity(values.len());
+    for (op_hash, value) in values {
+        match light_to_op(value.op.clone(), workspace.elements()) {
+            // Private entries are never published so can't be replicated
+            Err(DhtOpConvertError::StoreEntryOnPrivate) => continue,
+            r => ops.push((op_hash, value, r?)),
+        }
+    }
+    Ok(ops)
+}
+
+/// Ask the authorities for the basis of each op whether they hold it,
+/// returning how many of them do for each op they were asked about.
+///
+/// An authority can only be asked about the ops it reports on
+/// in its metadata or element responses, so links and agent activity
+/// are only counted by their receipts.
+/// Authorities which can't be reached are counted as not holding the op.
+async fn count_holders<N: HolochainP2pCellT>(
+    network: &mut N,
+    ops: &OpsByBasis,
+) -> HashMap<DhtOpHash, u32> {
+    let mut holders = HashMap::new();
+    for (basis, ops) in ops {
+        let ops: Vec<_> = ops
+            .iter()
+            .filter(|(_, op)| reported_by_authorities(op.op_type()))
+            .map(|(op_hash, op)| {
+                (
+                    op_hash,
+                    op.op_type(),
+                    HeaderHash::with_data_sync(&op.header()),
+                )
+            })
+            .collect();
+        if ops.is_empty() {
+            continue;
+        }
+
+        let mut metadata = Vec::new();
+        if ops
+            .iter()
+            .any(|(_, op_type, _)| *op_type != DhtOpType::StoreElement)
+        {
+            let options = GetMetaOptions {
+                remote_agent_count: Some(DEFAULT_RECEIPT_BUNDLE_SIZE as u8),
+                as_race: false,
+                ..Default::default()
+            };
+            match network.get_meta(basis.clone(), options).await {
+                Ok(r) => metadata = r,
+                Err(e) => warn!(msg = "Failed to ask authorities for metadata", ?basis, ?e),
+            }
+        }
+        let mut elements = Vec::new();
+        if ops
+            .iter()
+            .any(|(_, op_type, _)| *op_type == DhtOpType::StoreElement)
+        {
+            let options = GetOptions {
+                remote_agent_count: Some(DEFAULT_RECEIPT_BUNDLE_SIZE as u8),
+                as_race: false,
+                ..Default::default()
+            };
+            match network.get(basis.clone(), options).await {
+                Ok(r) => elements = r,
+                Err(e) => warn!(msg = "Failed to ask authorities for an element", ?basis, ?e),
+            }
+        }
+
+        for (op_hash, op_type, header_hash) in ops {
+            let count = match op_type {
+                // The basis of a store element op is its header,
+                // so any element returned is this op's
+                DhtOpType::StoreElement => elements
+                    .iter()
+                    .filter(|response| matches!(response, GetElementResponse::GetHeader(Some(_))))
+                    .count(),
+                _ => metadata
+                    .iter()
+                    .filter(|meta| meta_holds(meta, op_type, &header_hash))
+                    .count(),
+            };
+            holders.insert(op_hash.clone(), count as u32);
+        }
+    }
+    holders
+}
+
+/// Whether authorities can be asked if they hold this kind of op
+fn reported_by_authorities(op_type: DhtOpType) -> bool {
+    !matches!(
+        op_type,
+        DhtOpType::RegisterAgentActivity
+            | DhtOpType::RegisterAddLink
+            | DhtOpType::RegisterRemoveLink
+    )
+}
+
+/// Whether an authority's metadata for the op's basis shows it holds the op
+fn meta_holds(meta: &MetadataSet, op_type: DhtOpType, header_hash: &HeaderHash) -> bool {
+    let headers = match op_type {
+        DhtOpType::StoreEntry => &meta.headers,
+        DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedElement => &meta.updates,
+        DhtOpType::RegisterDeletedBy | DhtOpType::RegisterDeletedEntryHeader => &meta.deletes,
+        _ => return false,
+    };
+    headers.iter().any(|h| &h.header_hash == header_hash)
+}
+
+/// Count the receipts for every authored op, topping the count up with the
+/// authorities that report holding the op, and sort them by whether they
+/// have reached the receipt target
+pub async fn health_check_workflow_inner<N: HolochainP2pCellT>(
+    workspace: &mut PublishDhtOpsWorkspace,
+    network: &mut N,
+    heal: bool,
+) -> WorkflowResult<HealthCheckOutcome> {
+    let now = Timestamp::now();
+    let now_dt: chrono::DateTime<chrono::Utc> = now.into();
+    // chrono cannot create const durations
+    let interval =
+        chrono::Duration::from_std(MIN_PUBLISH_INTERVAL).expect("const interval must be positive");
+
+    let ops = authored_ops_with_receipts(workspace)?;
+
+    // Only the ops short of receipts need the authorities' word
+    let mut short: OpsByBasis = HashMap::new();
+    for (op_hash, value, op) in &ops {
+        if value.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
+            short
+                .entry(op.dht_basis())
+                .or_insert_with(Vec::new)
+                .push((op_hash.clone(), op.clone()));
+        }
+    }
+    let holders = count_holders(network, &short).await;
+
+    let mut outcome = HealthCheckOutcome {
+        health: ReplicationHealth {
+            checked_at: now,
+            target: DEFAULT_RECEIPT_BUNDLE_SIZE,
+            authored_ops: 0,
+            replicated_ops: 0,
+            under_replicated: Vec::new(),
+        },
+        under_replicated: HashMap::new(),
+        replicated: HashMap::new(),
+    };
+
+    for (op_hash, mut value, op) in ops {
+        outcome.health.authored_ops += 1;
+        let count = std::cmp::max(
+            value.receipt_count,
+            holders.get(&op_hash).cloned().unwrap_or(0),
+        );
+        if count < DEFAULT_RECEIPT_BUNDLE_SIZE {
+            outcome
+                .health
+                .under_replicated
+                .push((op_hash.clone(), count));
+            let needs_publish = value
+                .last_publish_time
+                .map(|last| now_dt.signed_duration_since(last.into()) > interval)
+                .unwrap_or(true);
+            if !needs_publish {
+                continue;
+            }
+            value.last_publish_time = Some(now);
+            workspace.authored().put(op_hash.clone(), value)?;
+            outcome
+                .under_replicated
+                .entry(op.dht_basis())
+                .or_insert_with(Vec::new)
+                .push((op_hash, op));
+        } else {
+            outcome.health.replicated_ops += 1;
+            if heal {
+                outcome
+                    .replicated
+                    .entry(op.dht_basis())
+                    .or_insert_with(Vec::new)
+                    .push((op_hash, op));
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::CreateLinkFixturator;
+    use crate::fixt::DeleteFixturator;
+    use ::fixt::prelude::*;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use holochain_p2p::MockHolochainP2pCellT;
+
+    /// Author `num_ops` link ops, giving the first `num_replicated`
+    /// of them enough receipts
+    fn setup(env: &EnvironmentWrite, num_ops: usize, num_replicated: usize) -> Vec<DhtOpHash> {
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let mut sig_fixt = SignatureFixturator::new(Unpredictable);
+        let mut link_add_fixt = CreateLinkFixturator::new(Unpredictable);
+        let mut elements = ElementBuf::authored(env.clone().into(), false).unwrap();
+        let mut hashes = Vec::new();
+        for i in 0..num_ops {
+            let sig = sig_fixt.next().unwrap();
+            let link_add = link_add_fixt.next().unwrap();
+            let op = DhtOp::RegisterAddLink(sig.clone(), link_add.clone());
+            let op_hash = DhtOpHash::with_data_sync(&op);
+            let header_hash = HeaderHashed::from_content_sync(link_add.clone().into());
+            let op_light = DhtOpLight::RegisterAddLink(
+                header_hash.as_hash().clone(),
+                link_add.base_address.into(),
+            );
+            let mut value = AuthoredDhtOpsValue::from_light(op_light);
+            if i < num_replicated {
+                value.receipt_count = DEFAULT_RECEIPT_BUNDLE_SIZE;
+            }
+            workspace.authored().put(op_hash.clone(), value).unwrap();
+            elements
+                .put(SignedHeaderHashed::with_presigned(header_hash, sig), None)
+                .unwrap();
+            hashes.push(op_hash);
+        }
+        env.guard()
+            .with_commit::<WorkspaceError, _, _>(|writer| {
+                workspace.flush_to_txn(writer)?;
+                elements.flush_to_txn(writer)?;
+                Ok(())
+            })
+            .unwrap();
+        hashes
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn health_check_finds_under_replicated_ops() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let hashes = setup(&env, 10, 4);
+
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let outcome =
+            health_check_workflow_inner(&mut workspace, &mut MockHolochainP2pCellT::new(), false)
+                .await
+                .unwrap();
+        let health = outcome.health;
+        assert_eq!(health.authored_ops, 10);
+        assert_eq!(health.replicated_ops, 4);
+        assert!(!health.is_healthy());
+        let mut under: Vec<_> = health
+            .under_replicated
+            .into_iter()
+            .map(|(h, _)| h)
+            .collect();
+        let mut expected = hashes[4..].to_vec();
+        under.sort();
+        expected.sort();
+        assert_eq!(under, expected);
+        assert_eq!(
+            outcome
+                .under_replicated
+                .values()
+                .map(Vec::len)
+                .sum::<usize>(),
+            6
+        );
+        assert!(outcome.replicated.is_empty());
+
+        // Healing also collects the replicated ops
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let outcome =
+            health_check_workflow_inner(&mut workspace, &mut MockHolochainP2pCellT::new(), true)
+                .await
+                .unwrap();
+        assert_eq!(outcome.replicated.values().map(Vec::len).sum::<usize>(), 4);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn health_check_respects_publish_interval() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        setup(&env, 3, 0);
+
+        // The first check republishes everything and records the publish time
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let outcome =
+            health_check_workflow_inner(&mut workspace, &mut MockHolochainP2pCellT::new(), false)
+                .await
+                .unwrap();
+        assert_eq!(
+            outcome
+                .under_replicated
+                .values()
+                .map(Vec::len)
+                .sum::<usize>(),
+            3
+        );
+        env.guard()
+            .with_commit(|writer| workspace.flush_to_txn(writer))
+            .unwrap();
+
+        // A check straight after still reports the ops but doesn't republish them
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let outcome =
+            health_check_workflow_inner(&mut workspace, &mut MockHolochainP2pCellT::new(), false)
+                .await
+                .unwrap();
+        assert_eq!(outcome.health.under_replicated.len(), 3);
+        assert!(outcome.under_replicated.is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn health_check_counts_authorities_holding_the_op() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let mut elements = ElementBuf::authored(env.clone().into(), false).unwrap();
+        let mut delete_fixt = DeleteFixturator::new(Unpredictable);
+        let mut ops = Vec::new();
+        for _ in 0..2 {
+            let sig = fixt!(Signature);
+            let delete = delete_fixt.next().unwrap();
+            let basis: AnyDhtHash = delete.deletes_address.clone().into();
+            let op = DhtOp::RegisterDeletedBy(sig.clone(), delete.clone());
+            let op_hash = DhtOpHash::with_data_sync(&op);
+            let header_hash = HeaderHashed::from_content_sync(delete.into());
+            let op_light =
+                DhtOpLight::RegisterDeletedBy(header_hash.as_hash().clone(), basis.clone());
+            workspace
+                .authored()
+                .put(op_hash.clone(), AuthoredDhtOpsValue::from_light(op_light))
+                .unwrap();
+            elements
+                .put(
+                    SignedHeaderHashed::with_presigned(header_hash.clone(), sig),
+                    None,
+                )
+                .unwrap();
+            ops.push((op_hash, basis, header_hash));
+        }
+        env.guard()
+            .with_commit::<WorkspaceError, _, _>(|writer| {
+                workspace.flush_to_txn(writer)?;
+                elements.flush_to_txn(writer)?;
+                Ok(())
+            })
+            .unwrap();
+
+        // Enough authorities hold the first delete, without having sent receipts,
+        // and none hold the second
+        let held_basis = ops[0].1.clone();
+        let meta = MetadataSet {
+            headers: Default::default(),
+            invalid_headers: Default::default(),
+            deletes: vec![ops[0].2.clone().into()].into_iter().collect(),
+            updates: Default::default(),
+            entry_dht_status: None,
+        };
+        let mut network = MockHolochainP2pCellT::new();
+        network
+            .expect_get_meta()
+            .times(2)
+            .returning(move |basis, _| {
+                if basis == held_basis {
+                    Ok(vec![meta.clone(); DEFAULT_RECEIPT_BUNDLE_SIZE as usize])
+                } else {
+                    Ok(vec![])
+                }
+            });
+
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let outcome = health_check_workflow_inner(&mut workspace, &mut network, false)
+            .await
+            .unwrap();
+        assert_eq!(outcome.health.authored_ops, 2);
+        assert_eq!(outcome.health.replicated_ops, 1);
+        assert_eq!(outcome.health.under_replicated, vec![(ops[1].0.clone(), 0)]);
+        assert_eq!(
+            outcome.under_replicated.keys().collect::<Vec<_>>(),
+            vec![&ops[1].1]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! # Incoming Validation Receipt Workflow
+//!
+//! Authorities send us a signed receipt once they have validated an op we
+//! published. Receipts are checked and stored, and the receipt count on the
+//! authored op is updated so that we stop publishing it once enough
+//! authorities hold it. Only receipts from agents whose arc covers the op's
+//! basis count, so made up validators can't mark an op as held.
+
+use super::error::WorkflowResult;
+use crate::conductor::api::CellConductorApiT;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_lmdb::buffer::KvStore;
+use holochain_lmdb::buffer::KvStoreT;
+use holochain_lmdb::db::AUTHORED_DHT_OPS;
+use holochain_lmdb::env::EnvironmentWrite;
+use holochain_lmdb::fresh_reader;
+use holochain_lmdb::prelude::*;
+use holochain_state::prelude::*;
+use tracing::*;
+
+#[instrument(skip(env, receipt, conductor_api))]
+pub async fn incoming_validation_receipt_workflow(
+    env: &EnvironmentWrite,
+    receipt: SignedValidationReceipt,
+    conductor_api: impl CellConductorApiT,
+) -> WorkflowResult<()> {
+    // Drop receipts which weren't signed by the validator they claim to be from
+    if !receipt
+        .receipt
+        .validator
+        .verify_signature(&receipt.validator_signature, receipt.receipt.clone())
+        .await
+        .map_err(DatabaseError::from)?
+    {
+        warn!(
+            msg = "Dropping validation receipt with an invalid signature",
+            ?receipt
+        );
+        return Ok(());
+    }
+
+    let dht_op_hash = receipt.receipt.dht_op_hash.clone();
+    let authored_dht_ops: KvStore<AuthoredDhtOpsKey, AuthoredDhtOpsValue> =
+        KvStore::new(env.get_db(&*AUTHORED_DHT_OPS)?);
+
+    // We only keep receipts for ops we authored
+    let basis = match fresh_reader!(env, |r| authored_dht_ops.get(&r, &dht_op_hash))? {
+        Some(value) => value.op.dht_basis().clone(),
+        None => {
+            warn!(
+                msg = "Dropping validation receipt for an op we didn't author",
+                ?dht_op_hash
+            );
+            return Ok(());
+        }
+    };
+
+    // The validator must be an authority for the op's basis
+    let validator_arc = conductor_api
+        .get_agent_arc(receipt.receipt.validator.clone())
+        .await
+        .map_err(Box::new)?;
+    if !validator_arc
+        .map(|arc| arc.contains(basis.get_loc()))
+        .unwrap_or(false)
+    {
+        warn!(
+            msg = "Dropping validation receipt from an agent which isn't an authority",
+            ?receipt
+        );
+        return Ok(());
+    }
+
+    let mut validation_receipts = ValidationReceiptsBuf::new(env)?;
+    validation_receipts.add_if_unique(receipt)?;
+    env.with_commit_retry(|writer| {
+        let mut value = match authored_dht_ops.get(&*writer, &dht_op_hash)? {
+            Some(value) => value,
+            None => return WorkflowResult::Ok(()),
+        };
+
+        validation_receipts.flush_to_txn_ref(writer)?;
+
+        value.receipt_count = validation_receipts.count_valid(&*writer, &dht_op_hash)? as u32;
+        authored_dht_ops.put(writer, &dht_op_hash, &value)?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::api::MockCellConductorApi;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::*;
+    use holo_hash::DhtOpHash;
+    use holochain_keystore::KeystoreSender;
+    use holochain_keystore::KeystoreSenderExt;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use holochain_lmdb::test_utils::test_keystore;
+    use holochain_p2p::dht_arc::DhtArc;
+    use holochain_types::dht_op::DhtOpLight;
+    use holochain_types::test_utils::fake_dht_op_hash;
+
+    async fn fake_receipt(
+        dht_op_hash: &DhtOpHash,
+        keystore: &KeystoreSender,
+    ) -> SignedValidationReceipt {
+        let validator = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+        ValidationReceipt {
+            dht_op_hash: dht_op_hash.clone(),
+            validation_result: ValidationResult::Valid,
+            validator,
+        }
+        .sign(keystore)
+        .await
+        .unwrap()
+    }
+
+    /// Every validator holds `arc`
+    fn conductor_api(arc: Option<DhtArc>) -> MockCellConductorApi {
+        let mut conductor_api = MockCellConductorApi::new();
+        conductor_api
+            .expect_sync_get_agent_arc()
+            .returning(move |_| Ok(arc));
+        conductor_api
+    }
+
+    fn put_authored_op(env: &EnvironmentWrite, dht_op_hash: &DhtOpHash) {
+        let op = DhtOpLight::RegisterAgentActivity(fixt!(HeaderHash), fixt!(AnyDhtHash));
+        let authored_dht_ops: KvStore<AuthoredDhtOpsKey, AuthoredDhtOpsValue> =
+            KvStore::new(env.get_db(&*AUTHORED_DHT_OPS).unwrap());
+        env.guard()
+            .with_commit::<DatabaseError, _, _>(|writer| {
+                authored_dht_ops.put(writer, dht_op_hash, &AuthoredDhtOpsValue::from_light(op))
+            })
+            .unwrap();
+    }
+
+    fn receipt_count(env: &EnvironmentWrite, dht_op_hash: &DhtOpHash) -> u32 {
+        let authored_dht_ops: KvStore<AuthoredDhtOpsKey, AuthoredDhtOpsValue> =
+            KvStore::new(env.get_db(&*AUTHORED_DHT_OPS).unwrap());
+        let env_ref = env.guard();
+        let reader = env_ref.reader().unwrap();
+        authored_dht_ops
+            .get(&reader, dht_op_hash)
+            .unwrap()
+            .unwrap()
+            .receipt_count
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn receipts_are_verified_and_counted() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let keystore = test_keystore();
+
+        let dht_op_hash = fake_dht_op_hash(1);
+        put_authored_op(&env, &dht_op_hash);
+        let full_arc = Some(DhtArc::new(0, u32::MAX));
+
+        // Receiving the same receipt twice only counts once
+        let receipt = fake_receipt(&dht_op_hash, &keystore).await;
+        incoming_validation_receipt_workflow(&env, receipt.clone(), conductor_api(full_arc))
+            .await
+            .unwrap();
+        incoming_validation_receipt_workflow(&env, receipt, conductor_api(full_arc))
+            .await
+            .unwrap();
+        assert_eq!(receipt_count(&env, &dht_op_hash), 1);
+
+        // A receipt signed by someone other than the validator is dropped
+        let mut forged = fake_receipt(&dht_op_hash, &keystore).await;
+        forged.receipt.validator = fake_receipt(&dht_op_hash, &keystore)
+            .await
+            .receipt
+            .validator;
+        incoming_validation_receipt_workflow(&env, forged, conductor_api(full_arc))
+            .await
+            .unwrap();
+        assert_eq!(receipt_count(&env, &dht_op_hash), 1);
+
+        let receipt = fake_receipt(&dht_op_hash, &keystore).await;
+        incoming_validation_receipt_workflow(&env, receipt, conductor_api(full_arc))
+            .await
+            .unwrap();
+        assert_eq!(receipt_count(&env, &dht_op_hash), 2);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn receipts_from_non_authorities_are_dropped() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let keystore = test_keystore();
+
+        let dht_op_hash = fake_dht_op_hash(1);
+        put_authored_op(&env, &dht_op_hash);
+
+        // We don't know the validator
+        let receipt = fake_receipt(&dht_op_hash, &keystore).await;
+        incoming_validation_receipt_workflow(&env, receipt, conductor_api(None))
+            .await
+            .unwrap();
+        assert_eq!(receipt_count(&env, &dht_op_hash), 0);
+
+        // The validator doesn't hold the op's basis
+        let receipt = fake_receipt(&dht_op_hash, &keystore).await;
+        let empty_arc = Some(DhtArc::new(0, 0));
+        incoming_validation_receipt_workflow(&env, receipt, conductor_api(empty_arc))
+            .await
+            .unwrap();
+        assert_eq!(receipt_count(&env, &dht_op_hash), 0);
+    }
+}
@@ -0,0 +1,289 @@
+//! # Incoming Warrant Workflow
+//!
+//! Authorities that find an agent's chain forked or invalid send a signed
+//! warrant to the agent's activity authorities. Warrants are checked against
+//! the proof they carry and stored alongside the agent's activity so they
+//! can be returned from `get_agent_activity`.
+//!
+//! Only warrants issued by an authority for the op they are about are
+//! accepted, as no one else would have validated it.
+//! An invalid op warrant is only stored if we rejected the header ourselves.
+//! Otherwise the op it is about is sent to validation, which issues our own
+//! warrant if it turns out to be invalid.
+
+use super::error::WorkflowResult;
+use super::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
+use crate::conductor::api::CellConductorApiT;
+use crate::core::queue_consumer::TriggerSender;
+use holochain_lmdb::env::EnvironmentWrite;
+use holochain_lmdb::prelude::*;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use tracing::*;
+
+#[instrument(skip(env, sys_validation_trigger, warrant, conductor_api))]
+pub async fn incoming_warrant_workflow(
+    env: &EnvironmentWrite,
+    sys_validation_trigger: TriggerSender,
+    warrant: SignedWarrant,
+    conductor_api: impl CellConductorApiT,
+) -> WorkflowResult<()> {
+    // Drop warrants that don't hold up
+    if let Err(e) = warrant.verify_signatures().await {
+        warn!(msg = "Dropping invalid warrant", ?warrant, error = %e);
+        return Ok(());
+    }
+
+    // The op an invalid op warrant is about and the basis of the
+    // authorities which validate it. Forks are found by activity authorities.
+    let (op, basis): (Option<DhtOp>, AnyDhtHash) = match &warrant.warrant().proof {
+        WarrantProof::ChainFork(header, _) => (None, header.header().author().clone().into()),
+        WarrantProof::InvalidChainOp {
+            header,
+            entry,
+            op_type,
+            ..
+        } => {
+            let SignedHeader(header, signature) = header.clone();
+            match DhtOp::from_type(*op_type, signature, header, entry.clone()) {
+                Ok(op) => {
+                    let basis = op.dht_basis();
+                    (Some(op), basis)
+                }
+                Err(e) => {
+                    warn!(
+                        msg = "Dropping warrant for an op that can't exist",
+                        ?warrant,
+                        error = %e
+                    );
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let author_arc = conductor_api
+        .get_agent_arc(warrant.warrant().author.clone())
+        .await
+        .map_err(Box::new)?;
+    if !author_arc
+        .map(|arc| arc.contains(basis.get_loc()))
+        .unwrap_or(false)
+    {
+        warn!(
+            msg = "Dropping warrant from an agent which isn't an authority for the op",
+            ?warrant
+        );
+        return Ok(());
+    }
+
+    if let Some(op) = op {
+        let hash = HeaderHash::with_data_sync(&op.header());
+        if !ElementBuf::rejected(env.clone().into())?.contains_header(&hash)? {
+            debug!(msg = "Validating the op of an invalid op warrant", ?warrant);
+            let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+            return incoming_dht_ops_workflow(
+                env,
+                sys_validation_trigger,
+                vec![(op_hash, op)],
+                None,
+                false,
+            )
+            .await;
+        }
+    }
+
+    let mut meta_vault = MetadataBuf::vault(env.clone().into())?;
+    meta_vault.register_warrant(warrant)?;
+    env.with_commit_retry(|writer| {
+        meta_vault.flush_to_txn_ref(writer)?;
+        WorkflowResult::Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::api::MockCellConductorApi;
+    use crate::core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace;
+    use ::fixt::prelude::*;
+    use holochain_keystore::AgentPubKeyExt;
+    use holochain_keystore::KeystoreSender;
+    use holochain_keystore::KeystoreSenderExt;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use holochain_lmdb::test_utils::test_keystore;
+    use holochain_p2p::dht_arc::DhtArc;
+
+    /// Every warrant author holds `arc`
+    fn conductor_api(arc: Option<DhtArc>) -> MockCellConductorApi {
+        let mut conductor_api = MockCellConductorApi::new();
+        conductor_api
+            .expect_sync_get_agent_arc()
+            .returning(move |_| Ok(arc));
+        conductor_api
+    }
+
+    async fn signed_header(
+        keystore: &KeystoreSender,
+        author: &AgentPubKey,
+        header_seq: u32,
+    ) -> SignedHeader {
+        let mut create = fixt!(Create);
+        create.author = author.clone();
+        create.header_seq = header_seq;
+        let header = Header::Create(create);
+        let signature = author.sign(keystore, &header).await.unwrap();
+        SignedHeader(header, signature)
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn only_verified_warrants_are_stored() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let keystore = test_keystore();
+        let bob = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+        let authority = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+        let warrant = |proof| Warrant {
+            proof,
+            author: authority.clone(),
+            timestamp: Timestamp::now().into(),
+        };
+
+        let first = signed_header(&keystore, &bob, 3).await;
+        let second = signed_header(&keystore, &bob, 3).await;
+        let later = signed_header(&keystore, &bob, 4).await;
+
+        // Headers at different positions are not a fork
+        let not_a_fork = warrant(WarrantProof::ChainFork(first.clone(), later));
+        let not_a_fork = SignedWarrant::new(&keystore, not_a_fork).await.unwrap();
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            not_a_fork,
+            conductor_api(Some(DhtArc::new(0, u32::MAX))),
+        )
+        .await
+        .unwrap();
+
+        // The warrant must be signed by its author
+        let fork = warrant(WarrantProof::ChainFork(first.clone(), second.clone()));
+        let mut forged = SignedWarrant::new(&keystore, fork.clone()).await.unwrap();
+        forged.warrant.timestamp = Timestamp::now().into();
+        forged.signature = bob.sign(&keystore, &forged.warrant).await.unwrap();
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            forged,
+            conductor_api(Some(DhtArc::new(0, u32::MAX))),
+        )
+        .await
+        .unwrap();
+
+        let meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+        assert!(meta_vault.get_warrants(&bob).unwrap().is_empty());
+
+        let fork = SignedWarrant::new(&keystore, fork).await.unwrap();
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            fork.clone(),
+            conductor_api(Some(DhtArc::new(0, u32::MAX))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(meta_vault.get_warrants(&bob).unwrap(), vec![fork]);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn invalid_op_warrants_are_only_stored_if_we_rejected_the_header() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let keystore = test_keystore();
+        let bob = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+        let authority = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+
+        let header = signed_header(&keystore, &bob, 3).await;
+        let warrant = Warrant {
+            proof: WarrantProof::InvalidChainOp {
+                header: header.clone(),
+                entry: None,
+                op_type: DhtOpType::StoreElement,
+                validation_type: ValidationType::Sys,
+                reason: "Header is out of order".into(),
+            },
+            author: authority.clone(),
+            timestamp: Timestamp::now().into(),
+        };
+        let warrant = SignedWarrant::new(&keystore, warrant).await.unwrap();
+        let SignedHeader(h, signature) = header.clone();
+        let op_hash =
+            DhtOpHashed::from_content_sync(DhtOp::StoreElement(signature.clone(), h.clone(), None))
+                .into_hash();
+
+        // Warrants from agents which aren't authorities for the op are dropped
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            warrant.clone(),
+            conductor_api(None),
+        )
+        .await
+        .unwrap();
+        let meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+        assert!(meta_vault.get_warrants(&bob).unwrap().is_empty());
+        let workspace = IncomingDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        assert!(!workspace.validation_limbo.contains(&op_hash).unwrap());
+
+        // We haven't rejected the header so the op is sent to validation instead
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            warrant.clone(),
+            conductor_api(Some(DhtArc::new(0, u32::MAX))),
+        )
+        .await
+        .unwrap();
+        assert!(meta_vault.get_warrants(&bob).unwrap().is_empty());
+        let workspace = IncomingDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        assert!(workspace.validation_limbo.contains(&op_hash).unwrap());
+
+        // Once we have rejected the header the warrant is stored
+        let mut rejected = ElementBuf::rejected(env.clone().into()).unwrap();
+        rejected
+            .put(
+                SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(h), signature),
+                None,
+            )
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| rejected.flush_to_txn(writer))
+            .unwrap();
+        incoming_warrant_workflow(
+            &env,
+            TriggerSender::new().0,
+            warrant.clone(),
+            conductor_api(Some(DhtArc::new(0, u32::MAX))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(meta_vault.get_warrants(&bob).unwrap(), vec![warrant]);
+    }
+}
@@ -1,6 +1,7 @@
 //! The workflow and queue consumer for DhtOp integration
 
 use super::*;
+use crate::core::metrics::record_workflow_run;
 use crate::core::queue_consumer::OneshotWriter;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
@@ -8,6 +9,7 @@ use crate::core::validation::DhtOpOrder;
 use crate::core::validation::OrderedOp;
 use error::WorkflowResult;
 use fallible_iterator::FallibleIterator;
+use holo_hash::AgentPubKey;
 use holo_hash::DhtOpHash;
 use holo_hash::EntryHash;
 use holo_hash::HeaderHash;
@@ -22,6 +24,7 @@ use holochain_lmdb::db::INTEGRATION_LIMBO;
 use holochain_lmdb::error::DatabaseResult;
 use holochain_lmdb::fresh_reader;
 use holochain_lmdb::prelude::*;
+use holochain_p2p::HolochainP2pCell;
 use holochain_state::prelude::*;
 use holochain_types::prelude::*;
 
@@ -32,7 +35,9 @@ use produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertResult;
 use produce_dht_ops_workflow::dht_op_light::light_to_op;
 use std::collections::BinaryHeap;
 use std::convert::TryInto;
+use std::time::Instant;
 use tracing::*;
+use validation_receipt_workflow::send_validation_receipts;
 
 pub use disintegrate::*;
 
@@ -41,12 +46,14 @@ mod disintegrate;
 #[cfg(feature = "test_utils")]
 mod tests;
 
-#[instrument(skip(workspace, writer, trigger_sys))]
+#[instrument(skip(workspace, writer, trigger_sys, network))]
 pub async fn integrate_dht_ops_workflow(
     mut workspace: IntegrateDhtOpsWorkspace,
     writer: OneshotWriter,
     trigger_sys: &mut TriggerSender,
+    network: &mut HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
+    let start = Instant::now();
     // one of many possible ways to access the env
     let env = workspace.elements.headers().env().clone();
     // Pull ops out of queue
@@ -57,6 +64,8 @@ pub async fn integrate_dht_ops_workflow(
         .drain_iter(&r)?
         .collect())?;
 
+    let queued = ops.len();
+
     // Sort the ops
     let mut sorted_ops = BinaryHeap::new();
     for iv in ops {
@@ -73,6 +82,8 @@ pub async fn integrate_dht_ops_workflow(
     }
 
     let mut total_integrated: usize = 0;
+    // Valid ops whose authors asked us for a receipt
+    let mut receipts: Vec<(DhtOpHash, AgentPubKey)> = Vec::new();
 
     // Try to process the queue over and over again, until we either exhaust
     // the queue, or we can no longer integrate anything in the queue.
@@ -89,6 +100,7 @@ pub async fn integrate_dht_ops_workflow(
                 value,
                 order,
             } = so;
+            let author = op.header().author().clone();
             // Check validation status and put in correct dbs
             let outcome = integrate_single_dht_op(value.clone(), op, &mut workspace).await?;
             match outcome {
@@ -97,6 +109,10 @@ pub async fn integrate_dht_ops_workflow(
                     // and separate rejected ops from valid ops.
                     // Currently you need to check the IntegratedDhtOpsValue for
                     // the status
+                    if value.send_receipt && integrated.validation_status == ValidationStatus::Valid
+                    {
+                        receipts.push((hash.clone(), author));
+                    }
                     workspace.integrate(hash, integrated)?;
                     num_integrated += 1;
                     total_integrated += 1;
@@ -134,7 +150,18 @@ pub async fn integrate_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    // Only send receipts for ops which are now committed as held
+    send_validation_receipts(network, &env.keystore(), receipts).await?;
+
+    record_workflow_run(
+        "integrate_dht_ops",
+        env.kind(),
+        queued,
+        total_integrated,
+        start,
+    );
 
     // trigger other workflows
 
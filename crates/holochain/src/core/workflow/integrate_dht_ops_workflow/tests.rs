@@ -20,6 +20,7 @@ use holochain_lmdb::env::ReadManager;
 use holochain_lmdb::env::WriteManager;
 use holochain_lmdb::error::DatabaseError;
 use holochain_lmdb::test_utils::test_cell_env;
+use holochain_p2p::HolochainP2pCellFixturator;
 use holochain_state::metadata::ChainItemKey;
 use holochain_state::metadata::LinkMetaKey;
 use holochain_state::workspace::WorkspaceError;
@@ -209,6 +210,7 @@ impl Db {
                     let value = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light(),
+                        send_receipt: false,
                     };
                     let res = workspace
                         .integration_limbo
@@ -465,6 +467,7 @@ impl Db {
                     let val = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light(),
+                        send_receipt: false,
                     };
                     workspace
                         .integration_limbo
@@ -530,7 +533,8 @@ impl Db {
 async fn call_workflow<'env>(env: EnvironmentWrite) {
     let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
     let (mut qt, _rx) = TriggerSender::new();
-    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt)
+    let mut network = fixt!(HolochainP2pCell);
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &mut network)
         .await
         .unwrap();
 }
@@ -1043,7 +1047,7 @@ async fn get_links(
     call_context.zome = zome.clone();
 
     // Call get links
-    let input = GetLinksInput::new((base_address.into(), Some(link_tag)));
+    let input = GetLinksInput::new((base_address.into(), Some(link_tag), LinkFilter::default()));
 
     let output = {
         let mut host_access = fixt!(ZomeCallHostAccess);
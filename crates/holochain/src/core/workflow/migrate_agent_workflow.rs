@@ -0,0 +1,246 @@
+//! # Migrate Agent Workflow
+//!
+//! Moves an agent from one DNA to a successor by closing their chain in the
+//! old DNA and opening a chain in the new one. Each side runs the
+//! `migrate_agent` callbacks first and the header is only written if they
+//! all pass. Preparing and committing are separate steps so that both sides'
+//! callbacks can run before either header is written.
+
+use super::error::WorkflowResult;
+use super::CallZomeWorkspace;
+use super::CallZomeWorkspaceLock;
+use crate::core::queue_consumer::OneshotWriter;
+use crate::core::queue_consumer::TriggerSender;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use derive_more::Constructor;
+use holo_hash::DnaHash;
+use holochain_state::workspace::Workspace;
+use holochain_types::dna::DnaDef;
+use holochain_zome_types::header::builder;
+use holochain_zome_types::migrate_agent::MigrateAgent;
+use tracing::*;
+
+#[derive(Constructor, Debug)]
+pub struct MigrateAgentWorkflowArgs<Ribosome: RibosomeT> {
+    pub dna_def: DnaDef,
+    pub ribosome: Ribosome,
+    /// Whether this chain is being closed or opened
+    pub migrate_agent: MigrateAgent,
+    /// The DNA on the other side of the migration.
+    /// When closing this is the successor, when opening it is the predecessor.
+    pub other_dna_hash: DnaHash,
+}
+
+pub type MigrateAgentWorkspace = CallZomeWorkspace;
+
+/// A migration whose callbacks have passed, with its header put in the
+/// workspace but not yet written
+pub struct PreparedMigration {
+    workspace_lock: CallZomeWorkspaceLock,
+}
+
+/// Run the callbacks and put the header without writing anything.
+/// If a callback fails its result is returned as the error.
+#[instrument(skip(workspace))]
+pub async fn prepare_migrate_agent_workflow<Ribosome: RibosomeT>(
+    workspace: MigrateAgentWorkspace,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<Result<PreparedMigration, MigrateAgentResult>> {
+    let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+    let result = migrate_agent_workflow_inner(workspace_lock.clone(), args).await?;
+    Ok(match result {
+        MigrateAgentResult::Pass => Ok(PreparedMigration { workspace_lock }),
+        result => Err(result),
+    })
+}
+
+/// Write a prepared migration
+#[instrument(skip(prepared, writer, trigger_produce_dht_ops))]
+pub async fn commit_migrate_agent_workflow(
+    prepared: PreparedMigration,
+    writer: OneshotWriter,
+    mut trigger_produce_dht_ops: TriggerSender,
+) -> WorkflowResult<()> {
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+    {
+        let mut guard = prepared.workspace_lock.write().await;
+        let workspace: &mut CallZomeWorkspace = &mut guard;
+        // commit the workspace
+        writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+    }
+    trigger_produce_dht_ops.trigger();
+
+    Ok(())
+}
+
+async fn migrate_agent_workflow_inner<Ribosome: RibosomeT>(
+    workspace: CallZomeWorkspaceLock,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult> {
+    let MigrateAgentWorkflowArgs {
+        dna_def,
+        ribosome,
+        migrate_agent,
+        other_dna_hash,
+    } = args;
+
+    // Call the migrate_agent callbacks
+    let result = {
+        let host_access = MigrateAgentHostAccess::new(workspace.clone());
+        let invocation = MigrateAgentInvocation::new(dna_def, migrate_agent.clone());
+        ribosome.run_migrate_agent(host_access, invocation)?
+    };
+    if let MigrateAgentResult::Fail(_, _) = result {
+        return Ok(result);
+    }
+
+    // Write the header declaring the migration path
+    let mut guard = workspace.write().await;
+    let source_chain = &mut guard.source_chain;
+    match migrate_agent {
+        MigrateAgent::Close => {
+            source_chain
+                .put(
+                    builder::CloseChain {
+                        new_dna_hash: other_dna_hash,
+                    },
+                    None,
+                )
+                .await?
+        }
+        MigrateAgent::Open => {
+            source_chain
+                .put(
+                    builder::OpenChain {
+                        prev_dna_hash: other_dna_hash,
+                    },
+                    None,
+                )
+                .await?
+        }
+    };
+
+    Ok(result)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::core::workflow::fake_genesis;
+    use crate::fixt::DnaDefFixturator;
+    use ::fixt::prelude::*;
+    use fixt::Unpredictable;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use holochain_state::source_chain::SourceChainError;
+    use holochain_zome_types::Header;
+    use holochain_zome_types::ZomeName;
+    use matches::assert_matches;
+
+    fn args(
+        result: MigrateAgentResult,
+        migrate_agent: MigrateAgent,
+        other_dna_hash: DnaHash,
+    ) -> MigrateAgentWorkflowArgs<MockRibosomeT> {
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_migrate_agent()
+            .return_once(move |_, _| Ok(result));
+        MigrateAgentWorkflowArgs {
+            dna_def: DnaDefFixturator::new(Unpredictable).next().unwrap(),
+            ribosome,
+            migrate_agent,
+            other_dna_hash,
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn close_chain_after_callbacks_pass() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+        let new_dna_hash = fixt!(DnaHash);
+
+        // A failing callback writes nothing
+        let result = migrate_agent_workflow_inner(
+            workspace_lock.clone(),
+            args(
+                MigrateAgentResult::Fail(ZomeName::from("foo"), "no".into()),
+                MigrateAgent::Close,
+                new_dna_hash.clone(),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_matches!(result, MigrateAgentResult::Fail(_, _));
+        assert_eq!(workspace_lock.read().await.source_chain.len(), 3);
+
+        migrate_agent_workflow_inner(
+            workspace_lock.clone(),
+            args(
+                MigrateAgentResult::Pass,
+                MigrateAgent::Close,
+                new_dna_hash.clone(),
+            ),
+        )
+        .await
+        .unwrap();
+        {
+            let workspace = workspace_lock.read().await;
+            let element = workspace.source_chain.get_at_index(3).unwrap().unwrap();
+            assert_matches!(
+                element.header(),
+                Header::CloseChain(close) if close.new_dna_hash == new_dna_hash
+            );
+            assert!(workspace.source_chain.is_closed().unwrap());
+        }
+
+        // Nothing can follow the close
+        let err = migrate_agent_workflow_inner(
+            workspace_lock.clone(),
+            args(MigrateAgentResult::Pass, MigrateAgent::Close, new_dna_hash),
+        )
+        .await
+        .unwrap_err();
+        assert_matches!(
+            err,
+            crate::core::workflow::error::WorkflowError::SourceChainError(
+                SourceChainError::ChainClosed
+            )
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn open_chain_after_callbacks_pass() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+        let prev_dna_hash = fixt!(DnaHash);
+
+        migrate_agent_workflow_inner(
+            workspace_lock.clone(),
+            args(
+                MigrateAgentResult::Pass,
+                MigrateAgent::Open,
+                prev_dna_hash.clone(),
+            ),
+        )
+        .await
+        .unwrap();
+        let workspace = workspace_lock.read().await;
+        let element = workspace.source_chain.get_at_index(3).unwrap().unwrap();
+        assert_matches!(
+            element.header(),
+            Header::OpenChain(open) if open.prev_dna_hash == prev_dna_hash
+        );
+        assert!(!workspace.source_chain.is_closed().unwrap());
+    }
+}
@@ -1,4 +1,5 @@
 use super::error::WorkflowResult;
+use crate::core::metrics::record_workflow_run;
 use crate::core::queue_consumer::OneshotWriter;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
@@ -10,6 +11,7 @@ use holochain_lmdb::prelude::GetDb;
 use holochain_lmdb::prelude::Writer;
 use holochain_state::prelude::*;
 use holochain_types::dht_op::DhtOpHashed;
+use std::time::Instant;
 use tracing::*;
 
 pub mod dht_op_light;
@@ -25,7 +27,7 @@ pub async fn produce_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_publish.trigger();
@@ -37,9 +39,13 @@ async fn produce_dht_ops_workflow_inner(
     workspace: &mut ProduceDhtOpsWorkspace,
 ) -> WorkflowResult<WorkComplete> {
     debug!("Starting dht op workflow");
+    let start = Instant::now();
     let all_ops = workspace.source_chain.get_incomplete_dht_ops().await?;
+    let queued = all_ops.len();
+    let mut produced = 0;
 
     for (index, ops) in all_ops {
+        produced += ops.len();
         for op in ops {
             let (op, hash) = DhtOpHashed::from_content_sync(op).into_inner();
             debug!(?hash, ?op);
@@ -54,6 +60,9 @@ async fn produce_dht_ops_workflow_inner(
         workspace.source_chain.complete_dht_op(index)?;
     }
 
+    let kind = workspace.source_chain.env().kind();
+    record_workflow_run("produce_dht_ops", kind, queued, produced, start);
+
     Ok(WorkComplete::Complete)
 }
 
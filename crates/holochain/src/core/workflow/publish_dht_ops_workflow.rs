@@ -13,6 +13,7 @@
 use super::error::WorkflowResult;
 use super::produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertError;
 use super::produce_dht_ops_workflow::dht_op_light::light_to_op;
+use crate::core::metrics::record_workflow_run;
 use crate::core::queue_consumer::OneshotWriter;
 use crate::core::queue_consumer::WorkComplete;
 use fallible_iterator::FallibleIterator;
@@ -48,6 +49,8 @@ pub struct PublishDhtOpsWorkspace {
     authored_dht_ops: AuthoredDhtOpsStore,
     /// Element store for looking up data to construct ops
     elements: ElementBuf<AuthoredPrefix>,
+    /// Validation receipts received for the authored DhtOps
+    validation_receipts: ValidationReceiptsBuf,
 }
 
 #[instrument(skip(workspace, writer, network))]
@@ -56,16 +59,23 @@ pub async fn publish_dht_ops_workflow(
     writer: OneshotWriter,
     network: &mut HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
+    let start = time::Instant::now();
     let to_publish = publish_dht_ops_workflow_inner(&mut workspace).await?;
+    let queued = to_publish.values().map(Vec::len).sum();
 
     // Commit to the network
+    let mut published = 0;
     for (basis, ops) in to_publish {
+        let count = ops.len();
         network.publish(true, basis, ops, None).await?;
+        published += count;
     }
+    let kind = workspace.elements.headers().env().kind();
+    record_workflow_run("publish_dht_ops", kind, queued, published, start);
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     Ok(WorkComplete::Complete)
 }
@@ -86,29 +96,39 @@ pub async fn publish_dht_ops_workflow_inner(
     // one of many ways to access the env
     let env = workspace.elements.headers().env().clone();
 
-    let values = fresh_reader!(env, |r| workspace
-        .authored()
-        .iter(&r)?
-        .filter_map(|(k, mut r)| {
-            Ok(if r.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
-                let needs_publish = r
-                    .last_publish_time
-                    .map(|last| {
-                        let duration = now.signed_duration_since(last.into());
-                        duration > interval
-                    })
-                    .unwrap_or(true);
-                if needs_publish {
-                    r.last_publish_time = Some(now_ts);
-                    Some((DhtOpHash::from_raw_39_panicky(k.to_vec()), r))
+    let values = fresh_reader!(env, |r| {
+        let validation_receipts = &workspace.validation_receipts;
+        workspace
+            .authored_dht_ops
+            .iter(&r)?
+            .filter_map(|(k, mut value)| {
+                let op_hash = DhtOpHash::from_raw_39_panicky(k.to_vec());
+                // Receipts may have arrived since this value was last written,
+                // so the receipts db has the final say on the count
+                value.receipt_count = std::cmp::max(
+                    value.receipt_count,
+                    validation_receipts.count_valid(&r, &op_hash)? as u32,
+                );
+                Ok(if value.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
+                    let needs_publish = value
+                        .last_publish_time
+                        .map(|last| {
+                            let duration = now.signed_duration_since(last.into());
+                            duration > interval
+                        })
+                        .unwrap_or(true);
+                    if needs_publish {
+                        value.last_publish_time = Some(now_ts);
+                        Some((op_hash, value))
+                    } else {
+                        None
+                    }
                 } else {
                     None
-                }
-            } else {
-                None
+                })
             })
-        })
-        .collect::<Vec<_>>())?;
+            .collect::<Vec<_>>()
+    })?;
 
     // Ops to publish by basis
     let mut to_publish = HashMap::new();
@@ -146,20 +166,26 @@ impl PublishDhtOpsWorkspace {
         let db = env.get_db(&*AUTHORED_DHT_OPS)?;
         let authored_dht_ops = KvBufFresh::new(env.clone(), db);
         // Note that this must always be false as we don't want private entries being published
-        let elements = ElementBuf::authored(env, false)?;
+        let elements = ElementBuf::authored(env.clone(), false)?;
+        let validation_receipts = ValidationReceiptsBuf::new(&env)?;
         Ok(Self {
             authored_dht_ops,
             elements,
+            validation_receipts,
         })
     }
 
-    fn authored(&mut self) -> &mut AuthoredDhtOpsStore {
+    pub(crate) fn authored(&mut self) -> &mut AuthoredDhtOpsStore {
         &mut self.authored_dht_ops
     }
 
-    fn elements(&self) -> &ElementBuf<AuthoredPrefix> {
+    pub(crate) fn elements(&self) -> &ElementBuf<AuthoredPrefix> {
         &self.elements
     }
+
+    pub(crate) fn validation_receipts(&self) -> &ValidationReceiptsBuf {
+        &self.validation_receipts
+    }
 }
 
 #[cfg(test)]
@@ -176,6 +202,7 @@ mod tests {
     use crate::test_utils::TestNetwork;
     use ::fixt::prelude::*;
     use futures::future::FutureExt;
+    use holochain_keystore::AgentPubKeyExt;
     use holochain_p2p::actor::HolochainP2pSender;
     use holochain_p2p::HolochainP2pRef;
     use matches::assert_matches;
@@ -253,9 +280,6 @@ mod tests {
 
         // Create cell data
         let dna = fixt!(DnaHash);
-        let agents = AgentPubKeyFixturator::new(Unpredictable)
-            .take(num_agents as usize)
-            .collect::<Vec<_>>();
 
         // Create the network
         let filter_events = |evt: &_| match evt {
@@ -263,15 +287,11 @@ mod tests {
             _ => false,
         };
         let (tx, mut recv) = tokio::sync::mpsc::channel(10);
-        let test_network = test_network_with_events(
-            Some(dna.clone()),
-            Some(agents[0].clone()),
-            filter_events,
-            tx,
-        )
-        .await;
+        let test_network =
+            test_network_with_events(Some(dna.clone()), None, filter_events, tx).await;
         let (tx_complete, rx_complete) = tokio::sync::oneshot::channel();
         let cell_network = test_network.cell_network();
+        let agents = test_agents(&test_network, num_agents).await;
         let network = test_network.network();
         let mut recv_count: u32 = 0;
         let total_expected = num_agents * num_hash;
@@ -313,6 +333,20 @@ mod tests {
         (test_network, cell_network, recv_task, rx_complete)
     }
 
+    /// The agent joined by `test_network` followed by new agents
+    /// from its keystore, `num_agents` in total
+    async fn test_agents(test_network: &TestNetwork, num_agents: u32) -> Vec<AgentPubKey> {
+        let mut agents = vec![test_network.cell_network().from_agent()];
+        for _ in 1..num_agents {
+            agents.push(
+                AgentPubKey::new_from_pure_entropy(&test_network.keystore())
+                    .await
+                    .unwrap(),
+            );
+        }
+        agents
+    }
+
     /// Call the workflow
     async fn call_workflow(env: EnvironmentWrite, mut cell_network: HolochainP2pCell) {
         let workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
@@ -644,9 +678,6 @@ mod tests {
 
                 // Create cell data
                 let dna = fixt!(DnaHash);
-                let agents = AgentPubKeyFixturator::new(Unpredictable)
-                    .take(num_agents as usize)
-                    .collect::<Vec<_>>();
 
                 // Create the network
 
@@ -655,14 +686,10 @@ mod tests {
                     _ => false,
                 };
                 let (tx, mut recv) = tokio::sync::mpsc::channel(10);
-                let test_network = test_network_with_events(
-                    Some(dna.clone()),
-                    Some(agents[0].clone()),
-                    filter_events,
-                    tx,
-                )
-                .await;
+                let test_network =
+                    test_network_with_events(Some(dna.clone()), None, filter_events, tx).await;
                 let cell_network = test_network.cell_network();
+                let agents = test_agents(&test_network, num_agents).await;
                 let (tx_complete, rx_complete) = tokio::sync::oneshot::channel();
                 // We are expecting five ops per agent
                 let total_expected = num_agents * 6;
@@ -3,6 +3,7 @@
 
 use super::*;
 use crate::conductor::api::CellConductorApiT;
+use crate::core::metrics::record_workflow_run;
 use crate::core::queue_consumer::OneshotWriter;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
@@ -29,6 +30,7 @@ use holochain_zome_types::ValidationStatus;
 use std::collections::BinaryHeap;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::time::Instant;
 use tracing::*;
 
 use produce_dht_ops_workflow::dht_op_light::light_to_op;
@@ -84,6 +86,7 @@ async fn sys_validation_workflow_inner(
     conductor_api: impl CellConductorApiT,
     sys_validation_trigger: TriggerSender,
 ) -> WorkflowResult<WorkComplete> {
+    let start = Instant::now();
     let env = workspace.validation_limbo.env().clone();
     // Drain all the ops
     let sorted_ops: BinaryHeap<OrderedOp<ValidationLimboValue>> = fresh_reader!(env, |r| {
@@ -121,6 +124,9 @@ async fn sys_validation_workflow_inner(
         sorted_ops
     })?;
 
+    let queued = sorted_ops.len();
+    let mut processed = 0;
+
     // Process each op
     for so in sorted_ops.into_sorted_vec() {
         let OrderedOp {
@@ -147,13 +153,16 @@ async fn sys_validation_workflow_inner(
 
         match outcome {
             Outcome::Accepted => {
+                processed += 1;
                 vlv.status = ValidationLimboStatus::SysValidated;
                 workspace.put_val_limbo(op_hash, vlv)?;
             }
             Outcome::SkipAppValidation => {
+                processed += 1;
                 let iv = IntegrationLimboValue {
                     op: vlv.op,
                     validation_status: ValidationStatus::Valid,
+                    send_receipt: vlv.send_receipt,
                 };
                 workspace.put_int_limbo(op_hash, iv)?;
             }
@@ -175,28 +184,31 @@ async fn sys_validation_workflow_inner(
                 workspace.put_val_limbo(op_hash, vlv)?;
             }
             Outcome::Rejected => {
+                processed += 1;
                 let iv = IntegrationLimboValue {
                     op: vlv.op,
                     validation_status: ValidationStatus::Rejected,
+                    send_receipt: vlv.send_receipt,
                 };
                 workspace.put_int_limbo(op_hash, iv)?;
             }
         }
     }
+    record_workflow_run("sys_validation", env.kind(), queued, processed, start);
     Ok(WorkComplete::Complete)
 }
 
 async fn validate_op(
     op: &DhtOp,
     workspace: &mut SysValidationWorkspace,
-    network: HolochainP2pCell,
+    mut network: HolochainP2pCell,
     conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> WorkflowResult<Outcome> {
     match validate_op_inner(
         op,
         workspace,
-        network,
+        network.clone(),
         conductor_api,
         incoming_dht_ops_sender,
     )
@@ -205,7 +217,22 @@ async fn validate_op(
         Ok(_) => match op {
             // TODO: Check strict mode where store element
             // is also run through app validation
-            DhtOp::RegisterAgentActivity(_, _) => Ok(Outcome::SkipAppValidation),
+            DhtOp::RegisterAgentActivity(signature, header) => {
+                if let Some(other) = find_chain_fork(header, workspace)? {
+                    let proof = WarrantProof::ChainFork(
+                        SignedHeader(header.clone(), signature.clone()),
+                        other,
+                    );
+                    issue_warrant(
+                        proof,
+                        &mut workspace.meta_vault,
+                        &mut network,
+                        conductor_api,
+                    )
+                    .await?;
+                }
+                Ok(Outcome::SkipAppValidation)
+            }
             _ => Ok(Outcome::Accepted),
         },
         // Handle the errors that result in pending or awaiting deps
@@ -217,12 +244,79 @@ async fn validate_op(
                 error = ?e,
                 error_msg = %e
             );
-            Ok(handle_failed(e))
+            let reason = e.to_string();
+            let warrantable = !matches!(
+                e,
+                ValidationOutcome::Counterfeit(_, _) | ValidationOutcome::VerifySignature(_, _)
+            );
+            let outcome = handle_failed(e);
+            // A bad signature can't be pinned on the author
+            // so only rejections of genuine headers are warranted
+            if warrantable && matches!(outcome, Outcome::Rejected) {
+                let (signature, header, entry) = op.clone().into_inner();
+                let proof = WarrantProof::InvalidChainOp {
+                    header: SignedHeader(header, signature),
+                    entry,
+                    op_type: op.op_type(),
+                    validation_type: ValidationType::Sys,
+                    reason,
+                };
+                issue_warrant(
+                    proof,
+                    &mut workspace.meta_vault,
+                    &mut network,
+                    conductor_api,
+                )
+                .await?;
+            }
+            Ok(outcome)
         }
         Err(e) => Err(e.into()),
     }
 }
 
+/// Find a header held by this authority that is at the same
+/// position in the author's chain as this one.
+fn find_chain_fork(
+    header: &Header,
+    workspace: &SysValidationWorkspace,
+) -> WorkflowResult<Option<SignedHeader>> {
+    let header_hash = HeaderHash::with_data_sync(header);
+    let k = ChainItemKey::AgentStatusSequence(
+        header.author().clone(),
+        ValidationStatus::Valid,
+        header.header_seq(),
+    );
+    let env = workspace.meta_vault.env();
+    let others: Vec<HeaderHash> = fresh_reader!(env, |r| {
+        let mut others: Vec<HeaderHash> = workspace
+            .meta_vault
+            .get_activity(&r, k.clone())?
+            .filter(|thh| Ok(thh.header_hash != header_hash))
+            .map(|thh| Ok(thh.header_hash))
+            .collect()?;
+        others.extend(
+            workspace
+                .meta_pending
+                .get_activity(&r, k.clone())?
+                .filter(|thh| Ok(thh.header_hash != header_hash))
+                .map(|thh| Ok(thh.header_hash))
+                .collect::<Vec<_>>()?,
+        );
+        DatabaseResult::Ok(others)
+    })?;
+    for other in others {
+        let signed_header = match workspace.element_vault.get_header(&other)? {
+            Some(shh) => Some(shh),
+            None => workspace.element_pending.get_header(&other)?,
+        };
+        if let Some(shh) = signed_header {
+            return Ok(Some(shh.into_inner().0));
+        }
+    }
+    Ok(None)
+}
+
 /// For now errors result in an outcome but in the future
 /// we might find it useful to include the reason something
 /// was rejected etc.
@@ -230,6 +324,7 @@ async fn validate_op(
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
+        ValidationOutcome::BlobManifest(_) => Rejected,
         ValidationOutcome::Counterfeit(_, _) => {
             unreachable!("Counterfeit ops are dropped before sys validation")
         }
@@ -242,6 +337,7 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         ValidationOutcome::TagTooLarge(_, _) => Rejected,
         ValidationOutcome::NotCreateLink(_) => Rejected,
         ValidationOutcome::NotNewEntry(_) => Rejected,
+        ValidationOutcome::NotBlobEntry(_) => Rejected,
         ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
         ValidationOutcome::PrevHeaderError(PrevHeaderError::MissingMeta(dep)) => {
             AwaitingOpDep(dep.into())
@@ -461,7 +557,7 @@ async fn register_agent_activity(
             workspace,
             network,
             incoming_dht_ops_sender,
-            |_| Ok(()),
+            |prev_element| check_prev_not_closed(prev_element.header()),
         )
         .await?;
     }
@@ -487,6 +583,7 @@ async fn store_element(
             .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_header_hash.clone().into()))?;
         check_prev_timestamp(&header, prev_header.header())?;
         check_prev_seq(&header, prev_header.header())?;
+        check_prev_not_closed(prev_header.header())?;
     }
     Ok(())
 }
@@ -507,6 +604,10 @@ async fn store_entry(
     if let EntryType::App(app_entry_type) = entry_type {
         let entry_def = check_app_entry_type(app_entry_type, conductor_api).await?;
         check_not_private(&entry_def)?;
+        if entry_def.id == BlobManifest::entry_def_id() {
+            let manifest = check_blob_manifest(entry_hash, entry)?;
+            check_blob_chunks(&manifest, workspace, network.clone()).await?;
+        }
     }
     check_entry_hash(entry_hash, entry).await?;
     check_entry_size(entry)?;
@@ -838,6 +939,8 @@ impl Workspace for SysValidationWorkspace {
 
         self.element_pending.flush_to_txn_ref(writer)?;
         self.meta_pending.flush_to_txn_ref(writer)?;
+        // Only holds warrants issued during validation
+        self.meta_vault.flush_to_txn_ref(writer)?;
         Ok(())
     }
 }
@@ -856,6 +959,7 @@ impl TryFrom<&CallZomeWorkspace> for SysValidationWorkspace {
             meta_rejected,
             element_cache,
             meta_cache,
+            ..
         } = call_zome;
         let mut sys_val = Self::new(call_zome.env().clone())?;
         sys_val.element_authored = source_chain.elements().into();
@@ -0,0 +1,69 @@
+//! # Validation Receipt Workflow
+//!
+//! Authors ask for a validation receipt when they publish an op to us. Once
+//! the op has been validated and integrated we sign a receipt for it and send
+//! it back, so the author knows we hold it and can stop publishing it.
+
+use super::error::WorkflowResult;
+use holo_hash::AgentPubKey;
+use holo_hash::DhtOpHash;
+use holochain_keystore::KeystoreSender;
+use holochain_lmdb::env::EnvironmentRead;
+use holochain_p2p::HolochainP2pCell;
+use holochain_p2p::HolochainP2pCellT;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use holochain_zome_types::ValidationStatus;
+use std::convert::TryInto;
+use tracing::*;
+
+/// Sign a receipt for each op and send it to the op's author.
+///
+/// A receipt which can't be sent is only logged, because the author will
+/// publish the op again and ask for another one.
+pub async fn send_validation_receipts(
+    network: &mut HolochainP2pCell,
+    keystore: &KeystoreSender,
+    receipts: Vec<(DhtOpHash, AgentPubKey)>,
+) -> WorkflowResult<()> {
+    let validator = network.from_agent();
+    for (dht_op_hash, author) in receipts {
+        let receipt = ValidationReceipt {
+            dht_op_hash,
+            validation_result: ValidationResult::Valid,
+            validator: validator.clone(),
+        }
+        .sign(keystore)
+        .await?;
+        if let Err(e) = network
+            .send_validation_receipt(author, receipt.try_into()?)
+            .await
+        {
+            warn!(msg = "Failed to send a validation receipt", ?e);
+        }
+    }
+    Ok(())
+}
+
+/// The ops in this batch which we have already integrated as valid, along
+/// with their authors.
+///
+/// These won't pass through validation again, so an author publishing them
+/// to us again has to be answered straight away.
+pub fn integrated_valid_ops(
+    env: &EnvironmentRead,
+    ops: &[(DhtOpHash, DhtOp)],
+) -> WorkflowResult<Vec<(DhtOpHash, AgentPubKey)>> {
+    let integrated_dht_ops = IntegratedDhtOpsBuf::new(env.clone())?;
+    let mut held = Vec::new();
+    for (hash, op) in ops {
+        let valid = integrated_dht_ops
+            .get(hash)?
+            .map(|value| value.validation_status == ValidationStatus::Valid)
+            .unwrap_or(false);
+        if valid {
+            held.push((hash.clone(), op.header().author().clone()));
+        }
+    }
+    Ok(held)
+}
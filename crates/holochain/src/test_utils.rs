@@ -16,9 +16,12 @@ use holo_hash::fixt::*;
 use holo_hash::*;
 use holochain_cascade::Cascade;
 use holochain_cascade::DbPair;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::KeystoreSender;
 use holochain_lmdb::env::EnvironmentWrite;
 use holochain_lmdb::fresh_reader_test;
 use holochain_lmdb::test_utils::test_environments;
+use holochain_lmdb::test_utils::test_keystore;
 use holochain_lmdb::test_utils::TestEnvironments;
 use holochain_p2p::actor::HolochainP2pRefToCell;
 use holochain_p2p::event::HolochainP2pEvent;
@@ -113,6 +116,7 @@ pub struct TestNetwork {
     network: Option<HolochainP2pRef>,
     respond_task: Option<tokio::task::JoinHandle<()>>,
     cell_network: HolochainP2pCell,
+    keystore: KeystoreSender,
 }
 
 impl TestNetwork {
@@ -121,11 +125,13 @@ impl TestNetwork {
         network: HolochainP2pRef,
         respond_task: tokio::task::JoinHandle<()>,
         cell_network: HolochainP2pCell,
+        keystore: KeystoreSender,
     ) -> Self {
         Self {
             network: Some(network),
             respond_task: Some(respond_task),
             cell_network,
+            keystore,
         }
     }
 
@@ -141,6 +147,12 @@ impl TestNetwork {
     pub fn cell_network(&self) -> HolochainP2pCell {
         self.cell_network.clone()
     }
+
+    /// Get the keystore that signs for agents joining this network.
+    /// Agents must be created in it to join.
+    pub fn keystore(&self) -> KeystoreSender {
+        self.keystore.clone()
+    }
 }
 
 impl Drop for TestNetwork {
@@ -155,7 +167,9 @@ impl Drop for TestNetwork {
     }
 }
 
-/// Convenience constructor for cell networks
+/// Convenience constructor for cell networks.
+/// The agent must be one of the fixture agents of the test keystore,
+/// i.e. from the `Predictable` fixturator, so its agent info can be signed.
 pub async fn test_network(
     dna_hash: Option<DnaHash>,
     agent_key: Option<AgentPubKey>,
@@ -193,6 +207,8 @@ where
     )
     .await
     .unwrap();
+    let keystore = test_keystore();
+    let signing_keystore = keystore.clone();
     let respond_task = tokio::task::spawn(async move {
         use futures::future::FutureExt;
         use tokio::stream::StreamExt;
@@ -205,8 +221,20 @@ where
             }
             use holochain_p2p::event::HolochainP2pEvent::*;
             match evt {
-                SignNetworkData { respond, .. } => {
-                    respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                SignNetworkData {
+                    respond,
+                    to_agent,
+                    data,
+                    ..
+                } => {
+                    let signature = to_agent.sign_raw(&signing_keystore, &data);
+                    respond.r(Ok(async move {
+                        signature
+                            .await
+                            .map_err(holochain_p2p::HolochainP2pError::other)
+                    }
+                    .boxed()
+                    .into()));
                 }
                 PutAgentInfoSigned { respond, .. } => {
                     respond.r(Ok(async move { Ok(()) }.boxed().into()));
@@ -223,7 +251,7 @@ where
     let agent_key = agent_key.unwrap_or_else(|| key_fixt.next().unwrap());
     let cell_network = network.to_cell(dna.clone(), agent_key.clone());
     network.join(dna.clone(), agent_key).await.unwrap();
-    TestNetwork::new(network, respond_task, cell_network)
+    TestNetwork::new(network, respond_task, cell_network, keystore)
 }
 
 /// Do what's necessary to install an app
@@ -285,6 +313,7 @@ pub async fn setup_app_inner(
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port: 0 },
+                auth: Default::default(),
             }]),
             network,
             ..Default::default()
@@ -297,10 +297,10 @@ impl HostFnCaller {
         &self,
         base: EntryHash,
         link_tag: Option<LinkTag>,
-        _options: GetLinksOptions,
+        options: GetLinksOptions,
     ) -> Vec<Link> {
         let (env, ribosome, call_context, workspace_lock) = self.explode();
-        let input = GetLinksInput::new((base.clone(), link_tag));
+        let input = GetLinksInput::new((base.clone(), link_tag, options.filter));
         let output = { host_fn::get_links::get_links(ribosome, call_context, input).unwrap() };
 
         // Write
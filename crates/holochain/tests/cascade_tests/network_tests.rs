@@ -305,6 +305,128 @@ async fn get_from_another_agent() {
     shutdown.await.unwrap();
 }
 
+#[tokio::test(threaded_scheduler)]
+#[ignore = "flaky"]
+async fn get_meta_from_another_agent() {
+    observability::test_run().ok();
+    let dna_file = DnaFile::new(
+        DnaDef {
+            name: "dht_get_test".to_string(),
+            uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
+            properties: SerializedBytes::try_from(()).unwrap(),
+            zomes: vec![TestWasm::Create.into()].into(),
+        },
+        vec![TestWasm::Create.into()],
+    )
+    .await
+    .unwrap();
+
+    let alice_agent_id = fake_agent_pubkey_1();
+    let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), alice_agent_id.clone());
+    let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+    let bob_agent_id = fake_agent_pubkey_2();
+    let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), bob_agent_id.clone());
+    let bob_installed_cell = InstalledCell::new(bob_cell_id.clone(), "bob_handle".into());
+
+    let mut dna_store = MockDnaStore::new();
+
+    dna_store.expect_get().return_const(Some(dna_file.clone()));
+    dna_store
+        .expect_add_dnas::<Vec<_>>()
+        .times(2)
+        .return_const(());
+    dna_store
+        .expect_add_entry_defs::<Vec<_>>()
+        .times(2)
+        .return_const(());
+    dna_store.expect_get_entry_def().return_const(None);
+
+    let (_tmpdir, _app_api, handle) = setup_app(
+        vec![(alice_installed_cell, None), (bob_installed_cell, None)],
+        dna_store,
+    )
+    .await;
+
+    // Bob stores, deletes and updates an element
+    let entry = Post("Bananas are good for you".into());
+    let entry_hash = EntryHash::with_data_sync(&Entry::try_from(entry.clone()).unwrap());
+    let (header_hash, remove_hash, update_hash) = {
+        let call_data = HostFnCaller::create(&bob_cell_id, &handle, &dna_file).await;
+        let header_hash = call_data
+            .commit_entry(entry.clone().try_into().unwrap(), POST_ID)
+            .await;
+        fake_authority(header_hash.clone().into(), &call_data).await;
+
+        let remove_hash = call_data.delete_entry(header_hash.clone()).await;
+        fake_authority(remove_hash.clone().into(), &call_data).await;
+
+        let update_hash = call_data
+            .update_entry(
+                Post("Bananas are bendy".into()).try_into().unwrap(),
+                POST_ID,
+                header_hash.clone(),
+            )
+            .await;
+        fake_authority(update_hash.clone().into(), &call_data).await;
+        (header_hash, remove_hash, update_hash)
+    };
+
+    let options = GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Alice gets the metadata on the entry and the header from bob
+    let (entry_meta, header_meta) = {
+        use holochain_p2p::HolochainP2pCellT;
+        let mut call_data = HostFnCaller::create(&alice_cell_id, &handle, &dna_file).await;
+        let entry_meta = call_data
+            .network
+            .get_meta(entry_hash.clone().into(), options.clone())
+            .await
+            .unwrap();
+        let header_meta = call_data
+            .network
+            .get_meta(header_hash.clone().into(), options)
+            .await
+            .unwrap();
+        (entry_meta, header_meta)
+    };
+
+    // Only bob is an authority so ignore any empty responses
+    let entry_meta = entry_meta
+        .into_iter()
+        .find(|m| !m.headers.is_empty())
+        .unwrap();
+    let header_meta = header_meta
+        .into_iter()
+        .find(|m| !m.deletes.is_empty())
+        .unwrap();
+
+    let hashes = |set: &std::collections::BTreeSet<TimedHeaderHash>| {
+        set.iter()
+            .map(|t| t.header_hash.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(hashes(&entry_meta.headers), vec![header_hash]);
+    assert_eq!(hashes(&entry_meta.deletes), vec![remove_hash.clone()]);
+    assert_eq!(hashes(&entry_meta.updates), vec![update_hash.clone()]);
+    assert_eq!(entry_meta.entry_dht_status, Some(EntryDhtStatus::Dead));
+
+    assert!(header_meta.headers.is_empty());
+    assert_eq!(hashes(&header_meta.deletes), vec![remove_hash]);
+    assert_eq!(hashes(&header_meta.updates), vec![update_hash]);
+    assert_eq!(header_meta.entry_dht_status, None);
+
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap();
+}
+
 #[tokio::test(threaded_scheduler)]
 #[ignore = "flaky for some reason"]
 async fn get_links_from_another_agent() {
@@ -348,7 +470,10 @@ async fn get_links_from_another_agent() {
     )
     .await;
 
-    let link_options = GetLinksOptions { timeout_ms: None };
+    let link_options = GetLinksOptions {
+        timeout_ms: None,
+        ..Default::default()
+    };
 
     // Bob store links
     let base = Post("Bananas are good for you".into());
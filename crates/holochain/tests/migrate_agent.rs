@@ -0,0 +1,61 @@
+use hdk3::prelude::*;
+use holochain::conductor::api::ZomeCall;
+use holochain::test_utils::cool::{CoolAgents, CoolConductor, CoolDnaFile};
+use holochain_types::dna::zome::inline_zome::InlineZome;
+
+fn unit_zome() -> InlineZome {
+    let unit_entry_def = EntryDef::default_with_id("unit");
+
+    InlineZome::new_unique(vec![unit_entry_def.clone()]).callback("create_unit", move |api, ()| {
+        let entry_def_id: EntryDefId = unit_entry_def.id.clone();
+        let entry = Entry::app(().try_into().unwrap()).unwrap();
+        let hash = api.create((entry_def_id, entry))?;
+        Ok(hash)
+    })
+}
+
+#[tokio::test(threaded_scheduler)]
+#[cfg(feature = "test_utils")]
+async fn migrate_agent_closes_old_chain_and_opens_new_one() -> anyhow::Result<()> {
+    let conductor = CoolConductor::from_standard_config().await;
+
+    let (dna_old, _) = CoolDnaFile::unique_from_inline_zome("zome", unit_zome()).await?;
+    let (dna_new, _) = CoolDnaFile::unique_from_inline_zome("zome", unit_zome()).await?;
+
+    let alice = CoolAgents::one(conductor.keystore()).await;
+    let apps = conductor
+        .setup_app_for_agents("app", &[alice.clone()], &[dna_old, dna_new])
+        .await;
+    let ((alice_old, alice_new),) = apps.into_tuples();
+
+    let _: HeaderHash = alice_old.call("zome", "create_unit", ()).await;
+
+    let successor_cell_id = conductor
+        .migrate_agent(alice_old.cell_id().clone(), alice_new.dna_hash().clone())
+        .await?;
+    assert_eq!(&successor_cell_id, alice_new.cell_id());
+
+    // Nothing more can be committed to the closed chain
+    let response = conductor
+        .call_zome(ZomeCall {
+            cell_id: alice_old.cell_id().clone(),
+            zome_name: "zome".into(),
+            fn_name: "create_unit".into(),
+            cap: None,
+            provenance: alice.clone(),
+            payload: ExternInput::new(().try_into().unwrap()),
+        })
+        .await;
+    assert!(!matches!(response, Ok(Ok(ZomeCallResponse::Ok(_)))));
+
+    // Closing again fails
+    assert!(conductor
+        .migrate_agent(alice_old.cell_id().clone(), alice_new.dna_hash().clone())
+        .await
+        .is_err());
+
+    // The new chain carries on as normal
+    let _: HeaderHash = alice_new.call("zome", "create_unit", ()).await;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use hdk3::prelude::*;
+use holochain::conductor::api::ZomeCall;
+use holochain::conductor::ConductorBuilder;
+use holochain::conductor::ConductorHandle;
+use holochain_lmdb::test_utils::test_environments;
+use holochain_test_wasm_common::TestInt;
+use holochain_types::app::InstalledCell;
+use holochain_types::test_utils::fake_agent_pubkey_1;
+use holochain_types::test_utils::fake_dna_zomes;
+use holochain_wasm_test_utils::TestWasm;
+use std::time::Duration;
+
+async fn call_schedule_zome<I>(
+    handle: &ConductorHandle,
+    cell_id: &CellId,
+    fn_name: &str,
+    input: I,
+) -> SerializedBytes
+where
+    I: TryInto<SerializedBytes, Error = SerializedBytesError>,
+{
+    let response = handle
+        .call_zome(ZomeCall {
+            cell_id: cell_id.clone(),
+            zome_name: TestWasm::Schedule.into(),
+            fn_name: fn_name.into(),
+            cap: None,
+            payload: ExternInput::new(input.try_into().unwrap()),
+            provenance: cell_id.agent_pubkey().clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    match response {
+        ZomeCallResponse::Ok(output) => output.into_inner(),
+        other => panic!("{:?}", other),
+    }
+}
+
+async fn shutdown(handle: ConductorHandle) {
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+#[cfg(feature = "test_utils")]
+async fn scheduled_fns_survive_a_restart() {
+    observability::test_run().ok();
+    let envs = test_environments();
+    let dna = fake_dna_zomes(
+        "scheduled_fns_survive_a_restart",
+        vec![(TestWasm::Schedule.into(), TestWasm::Schedule.into())],
+    );
+    let cell_id = CellId::new(dna.dna_hash().clone(), fake_agent_pubkey_1());
+    let installed_cell = InstalledCell::new(cell_id.clone(), "cell".into());
+
+    let handle = ConductorBuilder::new().test(&envs).await.unwrap();
+    holochain::test_utils::install_app(
+        "app",
+        vec![(installed_cell, None)],
+        vec![dna],
+        handle.clone(),
+    )
+    .await;
+
+    // Not due until the conductor has been shut down
+    call_schedule_zome(&handle, &cell_id, "schedule_tick", TestInt(2_000)).await;
+    let count: TestInt = call_schedule_zome(&handle, &cell_id, "tick_count", ())
+        .await
+        .try_into()
+        .unwrap();
+    assert_eq!(count, TestInt(0));
+    shutdown(handle).await;
+
+    let handle = ConductorBuilder::new().test(&envs).await.unwrap();
+    let mut count = TestInt(0);
+    for _ in 0..50 {
+        count = call_schedule_zome(&handle, &cell_id, "tick_count", ())
+            .await
+            .try_into()
+            .unwrap();
+        if count.0 > 0 {
+            break;
+        }
+        tokio::time::delay_for(Duration::from_millis(200)).await;
+    }
+    // Called once, and not again
+    assert_eq!(count, TestInt(1));
+    tokio::time::delay_for(Duration::from_millis(500)).await;
+    let count: TestInt = call_schedule_zome(&handle, &cell_id, "tick_count", ())
+        .await
+        .try_into()
+        .unwrap();
+    assert_eq!(count, TestInt(1));
+    shutdown(handle).await;
+}
@@ -193,7 +193,11 @@ async fn speed_test(n: Option<usize>) -> TestEnvironments {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        auth: Default::default(),
+        installed_app_ids: Vec::new(),
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
@@ -327,6 +331,7 @@ pub async fn setup_app(
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
                 driver: InterfaceDriver::Websocket { port: 0 },
+                auth: Default::default(),
             }]),
             ..Default::default()
         })
@@ -0,0 +1,68 @@
+use futures::future;
+use hdk3::prelude::*;
+use holochain::core::workflow::publish_dht_ops_workflow::publish_dht_ops_workflow_inner;
+use holochain::core::workflow::publish_dht_ops_workflow::PublishDhtOpsWorkspace;
+use holochain::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE;
+use holochain::test_utils::cool::{CoolAgents, CoolConductorBatch, CoolDnaFile};
+use holochain_types::dna::zome::inline_zome::InlineZome;
+
+fn simple_create_zome() -> InlineZome {
+    let entry_def = EntryDef::default_with_id("entrydef");
+
+    InlineZome::new_unique(vec![entry_def.clone()]).callback("create", move |api, ()| {
+        let entry_def_id: EntryDefId = entry_def.id.clone();
+        let entry = Entry::app(().try_into().unwrap()).unwrap();
+        let hash = api.create((entry_def_id, entry))?;
+        Ok(hash)
+    })
+}
+
+#[tokio::test(threaded_scheduler)]
+#[cfg(feature = "test_utils")]
+async fn authored_ops_are_not_republished_once_held() -> anyhow::Result<()> {
+    observability::test_run().ok();
+    const NUM_CONDUCTORS: usize = 2;
+    // Enough authorities between the two conductors to reach the receipt target
+    const AGENTS_PER_CONDUCTOR: usize = 3;
+    assert!(NUM_CONDUCTORS * AGENTS_PER_CONDUCTOR >= DEFAULT_RECEIPT_BUNDLE_SIZE as usize);
+
+    let conductors = CoolConductorBatch::from_standard_config(NUM_CONDUCTORS).await;
+    let (dna_file, _) = CoolDnaFile::unique_from_inline_zome("zome1", simple_create_zome())
+        .await
+        .unwrap();
+
+    let apps = future::join_all(conductors.iter().map(|c| {
+        let dna_file = dna_file.clone();
+        async move {
+            let agents = CoolAgents::get(c.keystore(), AGENTS_PER_CONDUCTOR).await;
+            c.setup_app_for_agents("app", &agents, &[dna_file]).await
+        }
+    }))
+    .await;
+    conductors.exchange_peer_info().await;
+
+    let alice = apps[0].cells_flattened()[0].clone();
+    let conductor = conductors.iter().next().unwrap();
+    let _: HeaderHash = alice.call("zome1", "create", ()).await;
+
+    // Wait for the authorities to send back enough receipts for every op
+    let mut pending = None;
+    for _ in 0..60 {
+        let dump = conductor.dump_cell_state(alice.cell_id()).await?;
+        assert!(dump.integration.authored > 0);
+        pending = Some(dump.pending_validation_receipts);
+        if pending == Some(0) {
+            break;
+        }
+        tokio::time::delay_for(std::time::Duration::from_millis(500)).await;
+    }
+    assert_eq!(pending, Some(0));
+
+    // Nothing is left to publish
+    let env = conductor.get_cell_env(alice.cell_id()).await?;
+    let mut workspace = PublishDhtOpsWorkspace::new(env.into())?;
+    let to_publish = publish_dht_ops_workflow_inner(&mut workspace).await?;
+    assert!(to_publish.is_empty());
+
+    Ok(())
+}
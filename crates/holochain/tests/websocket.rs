@@ -74,6 +74,7 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
             driver: InterfaceDriver::Websocket { port },
+            auth: Default::default(),
         }]),
         environment_path: environment_path.into(),
         network: None,
@@ -85,6 +86,7 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
             passphrase: "password".into(),
         }),
         keystore_path: None,
+        metrics_port: None,
         use_dangerous_test_keystore: true,
     }
 }
@@ -258,7 +260,11 @@ pub async fn attach_app_interface(
     holochain: &mut Child,
     port: Option<u16>,
 ) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        auth: Default::default(),
+        installed_app_ids: Vec::new(),
+    };
     let response = client.request(request);
     let response = check_timeout(holochain, response, 1000).await;
     match response {
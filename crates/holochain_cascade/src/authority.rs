@@ -2,7 +2,9 @@ use super::error::AuthorityDataError;
 use super::error::CascadeError;
 use super::error::CascadeResult;
 use fallible_iterator::FallibleIterator;
+use holo_hash::hash_type::AnyDht;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holo_hash::HeaderHash;
 use holochain_lmdb::env::EnvironmentRead;
@@ -246,6 +248,60 @@ pub fn handle_get_element(
     Ok(GetElementResponse::GetHeader(r))
 }
 
+#[instrument(skip(env, options))]
+pub fn handle_get_meta(
+    env: EnvironmentRead,
+    basis: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let meta_vault = MetadataBuf::vault(env.clone())?;
+    let request = options.metadata_request;
+    let mut metadata = MetadataSet {
+        headers: BTreeSet::new(),
+        invalid_headers: BTreeSet::new(),
+        deletes: BTreeSet::new(),
+        updates: BTreeSet::new(),
+        entry_dht_status: None,
+    };
+
+    fresh_reader!(env, |r| {
+        match *basis.hash_type() {
+            AnyDht::Entry => {
+                let entry_hash: EntryHash = basis.clone().into();
+                if request.all_valid_headers {
+                    metadata.headers = meta_vault.get_headers(&r, entry_hash.clone())?.collect()?;
+                }
+                if request.all_invalid_headers {
+                    metadata.invalid_headers = meta_vault
+                        .get_rejected_headers(&r, entry_hash.clone())?
+                        .collect()?;
+                }
+                if request.all_deletes {
+                    metadata.deletes = meta_vault
+                        .get_deletes_on_entry(&r, entry_hash.clone())?
+                        .collect()?;
+                }
+                if request.entry_dht_status {
+                    metadata.entry_dht_status = Some(meta_vault.get_dht_status(&r, &entry_hash)?);
+                }
+            }
+            // Headers don't have headers or a status of their own
+            AnyDht::Header => {
+                if request.all_deletes {
+                    metadata.deletes = meta_vault
+                        .get_deletes_on_header(&r, basis.clone().into())?
+                        .collect()?;
+                }
+            }
+        }
+        if request.all_updates {
+            metadata.updates = meta_vault.get_updates(&r, basis)?.collect()?;
+        }
+        debug!(handle_get_meta_return = ?metadata);
+        Ok(metadata)
+    })
+}
+
 #[instrument(skip(env))]
 pub fn handle_get_agent_activity(
     env: EnvironmentRead,
@@ -263,6 +319,7 @@ pub fn handle_get_agent_activity(
         .get_activity_status(&agent)?
         .unwrap_or(ChainStatus::Empty);
     let highest_observed = meta_integrated.get_activity_observed(&agent)?;
+    let warrants = meta_integrated.get_warrants(&agent)?;
 
     // Valid headers
     let valid_activity = if options.include_valid_activity {
@@ -302,6 +359,7 @@ pub fn handle_get_agent_activity(
         agent,
         status,
         highest_observed,
+        warrants,
     })
 }
 
@@ -393,11 +451,11 @@ fn _show_agent_activity_read_times(env: EnvironmentRead, agent: AgentPubKey) {
     });
 }
 
-#[instrument(skip(env, _options))]
+#[instrument(skip(env, options))]
 pub fn handle_get_links(
     env: EnvironmentRead,
     link_key: WireLinkMetaKey,
-    _options: holochain_p2p::event::GetLinksOptions,
+    options: holochain_p2p::event::GetLinksOptions,
 ) -> CascadeResult<GetLinksResponse> {
     // Get the vaults
     let env_ref = env.guard();
@@ -422,29 +480,36 @@ pub fn handle_get_links(
         })
         .collect::<BTreeMap<_, _>>()?;
 
-    // Get the headers from the element stores
-    let mut result_adds: Vec<(CreateLink, Signature)> = Vec::with_capacity(links.len());
-    let mut result_removes: Vec<(DeleteLink, Signature)> = Vec::with_capacity(links.len());
+    // Get the link adds from the element store so the filter
+    // can check their authors and timestamps
+    let mut link_adds = Vec::with_capacity(links.len());
     for (link_add, link_removes) in links {
         if let Some(link_add) = element_vault.get_header(&link_add.header_hash)? {
-            for link_remove in link_removes {
-                if let Some(link_remove) = element_vault.get_header(&link_remove.header_hash)? {
-                    let (h, s) = link_remove.into_header_and_signature();
-                    let h = h
-                        .into_content()
-                        .try_into()
-                        .map_err(AuthorityDataError::from)?;
-                    result_removes.push((h, s));
-                }
-            }
             let (h, s) = link_add.into_header_and_signature();
-            let h = h
-                .into_content()
-                .try_into()
-                .map_err(AuthorityDataError::from)?;
-            result_adds.push((h, s));
+            let (h, hash) = h.into_inner();
+            let h: CreateLink = h.try_into().map_err(AuthorityDataError::from)?;
+            link_adds.push((h, hash, s, link_removes));
         }
     }
+    // Only send the requested page of links
+    let link_adds = options.filter.apply(link_adds, |(h, hash, _, _)| (h, hash));
+
+    // Get the link removes from the element stores
+    let mut result_adds: Vec<(CreateLink, Signature)> = Vec::with_capacity(link_adds.len());
+    let mut result_removes: Vec<(DeleteLink, Signature)> = Vec::with_capacity(link_adds.len());
+    for (h, _, s, link_removes) in link_adds {
+        for link_remove in link_removes {
+            if let Some(link_remove) = element_vault.get_header(&link_remove.header_hash)? {
+                let (h, s) = link_remove.into_header_and_signature();
+                let h = h
+                    .into_content()
+                    .try_into()
+                    .map_err(AuthorityDataError::from)?;
+                result_removes.push((h, s));
+            }
+        }
+        result_adds.push((h, s));
+    }
 
     // Return the links
     Ok(GetLinksResponse {
@@ -452,3 +517,100 @@ pub fn handle_get_links(
         link_removes: result_removes,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::EntryHashFixturator;
+    use holochain_lmdb::prelude::*;
+    use holochain_lmdb::test_utils::test_cell_env;
+    use holochain_zome_types::fixt::CreateFixturator;
+    use holochain_zome_types::fixt::DeleteFixturator;
+    use holochain_zome_types::fixt::UpdateFixturator;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_authority_handle_get_meta() {
+        let cell_env = test_cell_env();
+        let env = cell_env.env();
+
+        // An entry which was created, updated and then deleted
+        let entry_hash = fixt!(EntryHash);
+        let mut create = fixt!(Create);
+        create.entry_hash = entry_hash.clone();
+        let create_hash = HeaderHash::with_data_sync(&Header::Create(create.clone()));
+        let mut update = fixt!(Update);
+        update.original_entry_address = entry_hash.clone();
+        update.original_header_address = create_hash.clone();
+        let update_hash = HeaderHash::with_data_sync(&Header::Update(update.clone()));
+        let mut delete = fixt!(Delete);
+        delete.deletes_address = create_hash.clone();
+        delete.deletes_entry_address = entry_hash.clone();
+        let delete_hash = HeaderHash::with_data_sync(&Header::Delete(delete.clone()));
+
+        let mut meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+        meta_vault
+            .register_header(NewEntryHeader::Create(create))
+            .unwrap();
+        meta_vault.register_update(update).unwrap();
+        meta_vault.register_delete(delete).unwrap();
+        env.guard()
+            .with_commit(|writer| meta_vault.flush_to_txn(writer))
+            .unwrap();
+
+        let hashes = |set: BTreeSet<TimedHeaderHash>| {
+            set.into_iter().map(|t| t.header_hash).collect::<Vec<_>>()
+        };
+        let get_meta = |basis: AnyDhtHash, metadata_request: MetadataRequest| {
+            handle_get_meta(
+                env.clone().into(),
+                basis,
+                holochain_p2p::event::GetMetaOptions { metadata_request },
+            )
+            .unwrap()
+        };
+
+        // Everything on the entry
+        let meta = get_meta(
+            entry_hash.clone().into(),
+            MetadataRequest {
+                entry_dht_status: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(hashes(meta.headers), vec![create_hash.clone()]);
+        assert!(meta.invalid_headers.is_empty());
+        assert_eq!(hashes(meta.updates), vec![update_hash.clone()]);
+        assert_eq!(hashes(meta.deletes), vec![delete_hash.clone()]);
+        assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Dead));
+
+        // Headers have updates and deletes but no headers or status
+        let meta = get_meta(
+            create_hash.clone().into(),
+            MetadataRequest {
+                entry_dht_status: true,
+                ..Default::default()
+            },
+        );
+        assert!(meta.headers.is_empty());
+        assert_eq!(hashes(meta.updates), vec![update_hash]);
+        assert_eq!(hashes(meta.deletes), vec![delete_hash]);
+        assert_eq!(meta.entry_dht_status, None);
+
+        // Only what was asked for is returned
+        let meta = get_meta(
+            entry_hash.into(),
+            MetadataRequest {
+                all_valid_headers: false,
+                all_invalid_headers: false,
+                all_deletes: false,
+                all_updates: true,
+                entry_dht_status: false,
+            },
+        );
+        assert!(meta.headers.is_empty());
+        assert!(meta.deletes.is_empty());
+        assert_eq!(meta.updates.len(), 1);
+        assert_eq!(meta.entry_dht_status, None);
+    }
+}
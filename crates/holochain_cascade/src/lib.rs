@@ -310,7 +310,8 @@ where
             highest_observed,
             valid_activity,
             rejected_activity,
-            ..
+            // Cache any warrants that hold up
+            warrants,
         } = agent_activity;
         match valid_activity {
             ChainItems::Full(headers) => {
@@ -365,6 +366,23 @@ where
                 .meta
                 .register_activity_observed(&agent, highest_observed)?;
         }
+        for warrant in warrants {
+            if warrant.warrant().warranted_agent() != &agent {
+                warn!(
+                    ?warrant,
+                    "Authority returned a warrant for a different agent"
+                );
+                continue;
+            }
+            match warrant.verify().await {
+                Ok(()) => cache_data.meta.register_warrant(warrant)?,
+                // We can't trust another authority's word that an op is invalid
+                Err(WarrantError::Unproven) => {
+                    debug!(?warrant, "Ignoring an unproven invalid op warrant")
+                }
+                Err(e) => warn!(?warrant, ?e, "Authority returned an invalid warrant"),
+            }
+        }
         Ok(())
     }
 
@@ -1530,6 +1548,7 @@ where
         key: &'link LinkMetaKey<'link>,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<Link>> {
+        let filter = options.filter.clone();
         if self.am_i_an_authority(key.base().clone().into()).await? {
             // Short circuit. This makes sense for full sharding.
             self.update_link_cache_from_integrated(key, options)?;
@@ -1541,26 +1560,49 @@ where
         let cache_data = ok_or_return!(self.cache_data.as_ref(), vec![]);
         let authored_data = ok_or_return!(self.authored_data.as_ref(), vec![]);
         let env = ok_or_return!(self.env.as_ref(), vec![]);
-        fresh_reader!(env, |r| {
+        let mut links = fresh_reader!(env, |r| {
             // Meta Cache
             // Return any links from the meta cache that don't have removes.
-            let mut links = cache_data
+            cache_data
                 .meta
                 .get_live_links(&r, key)?
-                .map(|l| Ok(l.into_link()))
-                .chain(
-                    authored_data
-                        .meta
-                        .get_live_links(&r, key)?
-                        .map(|l| Ok(l.into_link())),
-                )
-                // Need to collect into a Set first to remove
-                // duplicates from authored and cache
-                .collect::<Vec<_>>()?;
+                .map(Ok)
+                .chain(authored_data.meta.get_live_links(&r, key)?.map(Ok))
+                .collect::<Vec<_>>()
+        })?;
+        // Remove duplicates from authored and cache
+        links.sort_by(|a, b| a.link_add_hash.cmp(&b.link_add_hash));
+        links.dedup_by(|a, b| a.link_add_hash == b.link_add_hash);
+
+        if filter.is_empty() {
             links.sort_by_key(|l| l.timestamp);
-            links.dedup();
-            Ok(links)
-        })
+            return Ok(links.into_iter().map(|l| l.into_link()).collect());
+        }
+
+        // The cache can hold links outside of the page the
+        // authorities returned so the filter is applied again.
+        // Only the authors filter needs the CreateLink header. If we don't
+        // have the header the authority has already checked the author.
+        let links = links
+            .into_iter()
+            .map(|link| {
+                let author = match filter.authors {
+                    Some(_) => self
+                        .get_header_local_raw_with_sig(&link.link_add_hash)?
+                        .map(|h| h.header().author().clone()),
+                    None => None,
+                };
+                let timestamp: holochain_zome_types::timestamp::Timestamp = link.timestamp.into();
+                Ok((timestamp, author, link))
+            })
+            .collect::<CascadeResult<Vec<_>>>()?;
+        Ok(filter
+            .apply_parts(links, |(timestamp, author, link)| {
+                (timestamp, author.as_ref(), &link.link_add_hash)
+            })
+            .into_iter()
+            .map(|(_, _, link)| link.into_link())
+            .collect())
     }
 
     #[instrument(skip(self, key, options))]
@@ -1662,11 +1704,11 @@ where
 
     fn get_agent_activity_from_cache(
         agent: AgentPubKey,
-        range: &Option<std::ops::Range<u32>>,
+        range: &Option<SequenceRange>,
         cache_data: &DbPairMut<'a, MetaCache>,
         env: &EnvironmentRead,
     ) -> CascadeResult<Vec<(u32, HeaderHash)>> {
-        match range {
+        match range.as_ref().and_then(|r| r.end.map(|end| r.start..end)) {
             Some(range) => {
                 // One less than the end of an exclusive range is actually
                 // the last header we want in the chain.
@@ -1703,16 +1745,20 @@ where
                     }
                 })
             }
-            // Requesting full chain so return all everything we have
-            None => fresh_reader!(env, |r| {
-                Ok(cache_data
-                    .meta
-                    .get_activity_sequence(
-                        &r,
-                        ChainItemKey::AgentStatus(agent, ValidationStatus::Valid),
-                    )?
-                    .collect()?)
-            }),
+            // Requesting the rest of the chain so return everything we have
+            None => {
+                let start = range.as_ref().map(|r| r.start).unwrap_or(0);
+                fresh_reader!(env, |r| {
+                    Ok(cache_data
+                        .meta
+                        .get_activity_sequence(
+                            &r,
+                            ChainItemKey::AgentStatus(agent, ValidationStatus::Valid),
+                        )?
+                        .skip_while(|(s, _)| Ok(*s < start))
+                        .collect()?)
+                })
+            }
         }
     }
 
@@ -1721,7 +1767,7 @@ where
     fn find_valid_activity_cache_hit(
         &self,
         agent: AgentPubKey,
-        sequence_range: &Option<std::ops::Range<u32>>,
+        sequence_range: &Option<SequenceRange>,
     ) -> CascadeResult<Option<Vec<(u32, HeaderHash)>>> {
         let cache_data = ok_or_return!(self.cache_data.as_ref(), None);
         let env = ok_or_return!(self.env.as_ref(), None);
@@ -1730,12 +1776,12 @@ where
         // This also makes it safe to do `range.end - 1`
         match sequence_range {
             // The range is empty so there's not hashes to get
-            Some(r) if r.end == 0 => return Ok(Some(vec![])),
+            Some(SequenceRange { end: Some(0), .. }) => return Ok(Some(vec![])),
             // It only makes sense to check the cache first if
-            // a range has been requested otherwise
+            // a bounded range has been requested otherwise
             // we must go to the network because we don't
             // know how long the chain is.
-            None => return Ok(None),
+            None | Some(SequenceRange { end: None, .. }) => return Ok(None),
             _ => {}
         }
         // Try getting the activity from the cache.
@@ -1806,6 +1852,7 @@ where
         );
         // Now try getting the latest activity from cache
         let highest_observed = cache_data.meta.get_activity_observed(&agent)?;
+        let warrants = cache_data.meta.get_warrants(&agent)?;
         match cache_data.meta.get_activity_status(&agent)? {
             Some(status) => Ok(AgentActivityResponse {
                 agent,
@@ -1813,6 +1860,7 @@ where
                 rejected_activity: ChainItems::NotRequested,
                 status,
                 highest_observed,
+                warrants,
             }),
             // If we don't have any status then we must return an empty chain
             None => Ok(AgentActivityResponse {
@@ -1821,6 +1869,7 @@ where
                 rejected_activity: ChainItems::NotRequested,
                 status: ChainStatus::Empty,
                 highest_observed,
+                warrants,
             }),
         }
     }
@@ -1837,7 +1886,7 @@ where
     /// - include_full_headers will fetch the valid headers in parallel (requires include_valid_activity)
     /// Query:
     /// - include_entries will also fetch the entries in parallel (requires include_full_headers)
-    /// - sequence_range will get all the activity in the exclusive range
+    /// - sequence_range will get all the activity in the range, or from its start if it has no end
     /// - header_type and entry_type will filter the activity (requires include_full_headers)
     pub async fn get_agent_activity(
         &mut self,
@@ -1859,7 +1908,7 @@ where
 
         // See if we have a cache hit
         let chain_hashes = match &query.sequence_range {
-            Some(_) => {
+            Some(SequenceRange { end: Some(_), .. }) => {
                 // If we have some cached agent activity then don't fetch the activity.
                 // Instead fetch just the status and see if the chain is still valid
                 // up to that point.
@@ -1874,7 +1923,7 @@ where
                 // See if our cache is still valid
                 self.find_valid_activity_cache_hit(agent.clone(), &query.sequence_range)?
             }
-            None => None,
+            _ => None,
         };
 
         // Create the activity
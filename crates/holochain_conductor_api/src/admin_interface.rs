@@ -1,3 +1,4 @@
+use crate::config::InterfaceAuthConfig;
 use holo_hash::*;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
@@ -21,6 +22,7 @@ pub enum AdminRequest {
     /// Set up and register one or more new Admin interfaces
     /// as specified by a list of configurations. See [`AdminInterfaceConfig`]
     /// for details on the configuration.
+    /// The interfaces are persisted and started again when the conductor restarts.
     ///
     /// Will be responded to with an [`AdminResponse::AdminInterfacesAdded`]
     /// or an [`AdminResponse::Error`]
@@ -111,6 +113,40 @@ pub enum AdminRequest {
         /// The InstalledAppId to deactivate
         installed_app_id: InstalledAppId,
     },
+    /// Removes the `App` specified by argument `installed_app_id` from the conductor, whether
+    /// it is active or inactive. Its cells stop processing work and leave the network,
+    /// and if `delete_data` is `true` their source chains and DHT data are deleted from disk.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUninstalled`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AppUninstalled`]: enum.AdminResponse.html#variant.AppUninstalled
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    UninstallApp {
+        /// The InstalledAppId to uninstall
+        installed_app_id: InstalledAppId,
+        /// Whether to also delete the data of the app's cells
+        delete_data: bool,
+    },
+    /// Migrates the agent of the `Cell` specified by argument `cell_id` to the DNA specified by
+    /// argument `successor_dna_hash`.
+    ///
+    /// The successor `Cell` for the same agent must already be installed and active.
+    /// The `migrate_agent` callbacks run on both sides: first the old chain is closed with a
+    /// `CloseChain` header, then the new chain is opened with an `OpenChain` header.
+    /// Nothing more can be committed to the old chain afterwards.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentMigrated`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AgentMigrated`]: enum.AdminResponse.html#variant.AgentMigrated
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    MigrateAgent {
+        /// The `CellId` whose chain will be closed
+        cell_id: CellId,
+        /// The hash of the DNA whose chain will be opened
+        successor_dna_hash: DnaHash,
+    },
     /// Open up a new websocket interface at the networking port
     /// (optionally) specified by argument `port` (or using any free port if argument `port` is `None`)
     /// over which you can then use the [`AppRequest`] API.
@@ -127,9 +163,18 @@ pub enum AdminRequest {
         /// Optional port, use None to let the
         /// OS choose a free port
         port: Option<u16>,
+        /// Who may connect to the interface.
+        /// Anyone on this machine if omitted.
+        #[serde(default)]
+        auth: InterfaceAuthConfig,
+        /// The apps whose agents can make crypto requests over the interface.
+        /// None of them can if omitted.
+        #[serde(default)]
+        installed_app_ids: Vec<InstalledAppId>,
     },
-    /// Dump the full state of the `Cell` specified by argument `cell_id`,
-    /// including its chain, as a string containing JSON.
+    /// Dump the state of the `Cell` specified by argument `cell_id`:
+    /// its source chain head, integration queues, held arc, known peers
+    /// and pending validation receipts.
     ///
     /// Will be responded to with an [`AdminResponse::StateDumped`]
     /// or an [`AdminResponse::Error`]
@@ -140,6 +185,20 @@ pub enum AdminRequest {
         /// The `CellId` for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Compact the database environment of the `Cell` specified by argument `cell_id`,
+    /// giving back the disk space left behind by deleted data, such as cache churn.
+    ///
+    /// The cell can't read or write its data while it is being compacted.
+    ///
+    /// Will be responded to with an [`AdminResponse::EnvironmentCompacted`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::EnvironmentCompacted`]: enum.AdminResponse.html#variant.EnvironmentCompacted
+    CompactEnvironment {
+        /// The `CellId` whose environment to compact
+        cell_id: Box<CellId>,
+    },
     /// Add a list [AgentInfoSigned] to this conductor's peer store.
     /// This is another way of finding peers on a dht.
     ///
@@ -165,6 +224,97 @@ pub enum AdminRequest {
         /// Optionally choose a specific agent info
         cell_id: Option<CellId>,
     },
+    /// Issue a new bearer token which app interfaces that require a token
+    /// will accept, until it is revoked. Issued tokens persist across restarts.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppInterfaceTokenIssued`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AppInterfaceTokenIssued`]: enum.AdminResponse.html#variant.AppInterfaceTokenIssued
+    IssueAppInterfaceToken,
+    /// Revoke a token issued with [`AdminRequest::IssueAppInterfaceToken`],
+    /// so it is refused by new connections.
+    /// Connections already made with the token stay open.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppInterfaceTokenRevoked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::IssueAppInterfaceToken`]: enum.AdminRequest.html#variant.IssueAppInterfaceToken
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AppInterfaceTokenRevoked`]: enum.AdminResponse.html#variant.AppInterfaceTokenRevoked
+    RevokeAppInterfaceToken {
+        /// The token to revoke
+        token: String,
+    },
+    /// Issue a new bearer token which admin interfaces that require a token
+    /// will accept, until it is revoked. Issued tokens persist across restarts.
+    ///
+    /// Will be responded to with an [`AdminResponse::AdminInterfaceTokenIssued`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AdminInterfaceTokenIssued`]: enum.AdminResponse.html#variant.AdminInterfaceTokenIssued
+    IssueAdminInterfaceToken,
+    /// Revoke a token issued with [`AdminRequest::IssueAdminInterfaceToken`],
+    /// so it is refused by new connections.
+    /// Connections already made with the token stay open.
+    ///
+    /// Will be responded to with an [`AdminResponse::AdminInterfaceTokenRevoked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::IssueAdminInterfaceToken`]: enum.AdminRequest.html#variant.IssueAdminInterfaceToken
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AdminInterfaceTokenRevoked`]: enum.AdminResponse.html#variant.AdminInterfaceTokenRevoked
+    RevokeAdminInterfaceToken {
+        /// The token to revoke
+        token: String,
+    },
+    /// List the ports of all the attached app interfaces.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppInterfacesListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AppInterfacesListed`]: enum.AdminResponse.html#variant.AppInterfacesListed
+    ListAppInterfaces,
+    /// Detach the app interface on argument `port`, closing its connections.
+    /// It will not be started again when the conductor restarts.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppInterfaceDetached`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AppInterfaceDetached`]: enum.AdminResponse.html#variant.AppInterfaceDetached
+    DetachAppInterface {
+        /// The port the app interface is attached on
+        port: u16,
+    },
+    /// List the ports of all the running admin interfaces,
+    /// both those in the conductor config and those added since.
+    ///
+    /// Will be responded to with an [`AdminResponse::AdminInterfacesListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AdminInterfacesListed`]: enum.AdminResponse.html#variant.AdminInterfacesListed
+    ListAdminInterfaces,
+    /// Stop an admin interface added with [`AdminRequest::AddAdminInterfaces`],
+    /// closing its connections, including the one this request came in on
+    /// if it was sent to that interface.
+    /// It will not be started again when the conductor restarts.
+    /// Admin interfaces in the conductor config can't be removed.
+    ///
+    /// Will be responded to with an [`AdminResponse::AdminInterfaceRemoved`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::AddAdminInterfaces`]: enum.AdminRequest.html#variant.AddAdminInterfaces
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AdminInterfaceRemoved`]: enum.AdminResponse.html#variant.AdminInterfaceRemoved
+    RemoveAdminInterface {
+        /// The port the admin interface is running on
+        port: u16,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -252,13 +402,30 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::DeactivateApp`]: enum.AdminRequest.html#variant.DeactivateApp
     AppDeactivated,
+    /// The succesful response to an [`AdminRequest::UninstallApp`].
+    ///
+    /// It means the `App` was uninstalled successfully.
+    ///
+    /// [`AdminRequest::UninstallApp`]: enum.AdminRequest.html#variant.UninstallApp
+    AppUninstalled,
+    /// The succesful response to an [`AdminRequest::MigrateAgent`].
+    ///
+    /// It means the old chain was closed and the new chain opened successfully.
+    /// Contains the `CellId` of the successor `Cell`.
+    ///
+    /// [`AdminRequest::MigrateAgent`]: enum.AdminRequest.html#variant.MigrateAgent
+    AgentMigrated(CellId),
     /// The succesful response to an [`AdminRequest::DumpState`].
     ///
-    /// The result contains a string of serialized JSON data which can be deserialized to access the
-    /// full state dump, and inspect the source chain.
+    /// The result contains a [`CellStateDump`] describing the state of the `Cell`.
     ///
     /// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
-    StateDumped(String),
+    /// [`CellStateDump`]: struct.CellStateDump.html
+    StateDumped(Box<CellStateDump>),
+    /// The succesful response to an [`AdminRequest::CompactEnvironment`].
+    ///
+    /// [`AdminRequest::CompactEnvironment`]: enum.AdminRequest.html#variant.CompactEnvironment
+    EnvironmentCompacted,
     /// The succesful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -271,6 +438,54 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::RequestAgentInfo`]: enum.AdminRequest.html#variant.RequestAgentInfo
     AgentInfoRequested(Vec<AgentInfoSigned>),
+    /// The succesful response to an [`AdminRequest::IssueAppInterfaceToken`].
+    ///
+    /// Contains the new bearer token
+    ///
+    /// [`AdminRequest::IssueAppInterfaceToken`]: enum.AdminRequest.html#variant.IssueAppInterfaceToken
+    AppInterfaceTokenIssued(String),
+    /// The succesful response to an [`AdminRequest::RevokeAppInterfaceToken`].
+    ///
+    /// The token will no longer be accepted, whether or not it had been issued.
+    ///
+    /// [`AdminRequest::RevokeAppInterfaceToken`]: enum.AdminRequest.html#variant.RevokeAppInterfaceToken
+    AppInterfaceTokenRevoked,
+    /// The succesful response to an [`AdminRequest::IssueAdminInterfaceToken`].
+    ///
+    /// Contains the new bearer token
+    ///
+    /// [`AdminRequest::IssueAdminInterfaceToken`]: enum.AdminRequest.html#variant.IssueAdminInterfaceToken
+    AdminInterfaceTokenIssued(String),
+    /// The succesful response to an [`AdminRequest::RevokeAdminInterfaceToken`].
+    ///
+    /// The token will no longer be accepted, whether or not it had been issued.
+    ///
+    /// [`AdminRequest::RevokeAdminInterfaceToken`]: enum.AdminRequest.html#variant.RevokeAdminInterfaceToken
+    AdminInterfaceTokenRevoked,
+    /// The succesful response to an [`AdminRequest::ListAppInterfaces`].
+    ///
+    /// Contains the ports of the attached app interfaces, in ascending order
+    ///
+    /// [`AdminRequest::ListAppInterfaces`]: enum.AdminRequest.html#variant.ListAppInterfaces
+    AppInterfacesListed(Vec<u16>),
+    /// The succesful response to an [`AdminRequest::DetachAppInterface`].
+    ///
+    /// It means the app interface was stopped and removed from the conductor state.
+    ///
+    /// [`AdminRequest::DetachAppInterface`]: enum.AdminRequest.html#variant.DetachAppInterface
+    AppInterfaceDetached,
+    /// The succesful response to an [`AdminRequest::ListAdminInterfaces`].
+    ///
+    /// Contains the ports of the running admin interfaces
+    ///
+    /// [`AdminRequest::ListAdminInterfaces`]: enum.AdminRequest.html#variant.ListAdminInterfaces
+    AdminInterfacesListed(Vec<u16>),
+    /// The succesful response to an [`AdminRequest::RemoveAdminInterface`].
+    ///
+    /// It means the admin interface was stopped and removed from the conductor state.
+    ///
+    /// [`AdminRequest::RemoveAdminInterface`]: enum.AdminRequest.html#variant.RemoveAdminInterface
+    AdminInterfaceRemoved,
 }
 
 /// Error type that goes over the websocket wire.
@@ -20,10 +20,18 @@ pub enum AppRequest {
         /// The InstalledAppId for which to get information
         installed_app_id: InstalledAppId,
     },
-    /// Asks the conductor to do some crypto.
+    /// Asks the conductor to do some crypto with keys held by the keystore.
+    /// See the inner [`CryptoRequest`] for the operations available.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// The agent named in the request must be the agent of a cell in an active app
+    /// that was listed when the interface was attached.
+    ///
+    /// Will be responded to with an [`AppResponse::Crypto`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`CryptoRequest`]: enum.CryptoRequest.html
+    /// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     Crypto(Box<CryptoRequest>),
     /// Call a zome function. See the inner [`ZomeCall`]
     /// struct to understand the data that must be provided.
@@ -39,10 +47,18 @@ pub enum AppRequest {
     /// DEPRECATED. Use `ZomeCall`.
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Update signal subscriptions.
+    /// Update the signal subscriptions of this connection for one app.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// Until the first subscription is made a connection receives every signal.
+    /// After that it only receives signals from the cells of subscribed apps
+    /// which are let through by that app's filters. A later subscription for the
+    /// same app replaces the earlier one.
+    ///
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
 }
 
@@ -83,6 +99,16 @@ pub enum AppResponse {
 
     /// DEPRECATED. See `ZomeCall`.
     ZomeCallInvocation(Box<ExternOutput>),
+
+    /// The successful response to an [`AppRequest::Crypto`].
+    ///
+    /// [`AppRequest::Crypto`]: enum.AppRequest.html#variant.Crypto
+    Crypto(Box<CryptoResponse>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
 }
 
 /// The data provided across an App interface in order to make a zome call
@@ -109,11 +135,89 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
-#[allow(missing_docs)]
+/// Prepended to the data of a [`CryptoRequest::Sign`] before it is signed,
+/// so the signature can't pass for one over a header or anything else
+/// the agent signs.
+///
+/// [`CryptoRequest::Sign`]: enum.CryptoRequest.html#variant.Sign
+pub const CRYPTO_SIGN_PREFIX: &[u8] = b"holochain-app-interface-sign:";
+
+/// The crypto operations available over an App interface
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum CryptoRequest {
-    Sign(String),
-    Decrypt(String),
-    Encrypt(String),
+    /// Sign the raw bytes of `data`, prefixed with [`CRYPTO_SIGN_PREFIX`],
+    /// with the private key of `agent`.
+    ///
+    /// [`CRYPTO_SIGN_PREFIX`]: constant.CRYPTO_SIGN_PREFIX.html
+    Sign {
+        /// The agent whose key signs the data
+        agent: AgentPubKey,
+        /// The data to sign
+        data: SerializedBytes,
+    },
+    /// Generate a new X25519 keypair in the keystore belonging to `agent`.
+    CreateX25519Keypair {
+        /// The agent the keypair belongs to
+        agent: AgentPubKey,
+    },
+    /// Encrypt data from an X25519 sender key to an X25519 recipient key.
+    /// The sender key must have been created for `agent`
+    /// with a [`CryptoRequest::CreateX25519Keypair`].
+    ///
+    /// [`CryptoRequest::CreateX25519Keypair`]: enum.CryptoRequest.html#variant.CreateX25519Keypair
+    Encrypt {
+        /// The agent on whose behalf the data is encrypted
+        agent: AgentPubKey,
+        /// The keys and data to encrypt
+        input: X25519XSalsa20Poly1305Encrypt,
+    },
+    /// Decrypt data sent from an X25519 sender key to an X25519 recipient key.
+    /// The recipient key must have been created for `agent`
+    /// with a [`CryptoRequest::CreateX25519Keypair`].
+    ///
+    /// [`CryptoRequest::CreateX25519Keypair`]: enum.CryptoRequest.html#variant.CreateX25519Keypair
+    Decrypt {
+        /// The agent on whose behalf the data is decrypted
+        agent: AgentPubKey,
+        /// The keys and data to decrypt
+        input: X25519XSalsa20Poly1305Decrypt,
+    },
+}
+
+impl CryptoRequest {
+    /// The agent this request is made on behalf of
+    pub fn agent(&self) -> &AgentPubKey {
+        match self {
+            CryptoRequest::Sign { agent, .. }
+            | CryptoRequest::CreateX25519Keypair { agent }
+            | CryptoRequest::Encrypt { agent, .. }
+            | CryptoRequest::Decrypt { agent, .. } => agent,
+        }
+    }
+}
+
+/// The results of the crypto operations in [`CryptoRequest`]
+///
+/// [`CryptoRequest`]: enum.CryptoRequest.html
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CryptoResponse {
+    /// The signature of the data in a [`CryptoRequest::Sign`]
+    ///
+    /// [`CryptoRequest::Sign`]: enum.CryptoRequest.html#variant.Sign
+    Signature(Signature),
+    /// The public key created by a [`CryptoRequest::CreateX25519Keypair`]
+    ///
+    /// [`CryptoRequest::CreateX25519Keypair`]: enum.CryptoRequest.html#variant.CreateX25519Keypair
+    X25519PubKey(X25519PubKey),
+    /// The data encrypted by a [`CryptoRequest::Encrypt`]
+    ///
+    /// [`CryptoRequest::Encrypt`]: enum.CryptoRequest.html#variant.Encrypt
+    Encrypted(XSalsa20Poly1305EncryptedData),
+    /// The data decrypted by a [`CryptoRequest::Decrypt`],
+    /// or `None` if it could not be decrypted with those keys.
+    ///
+    /// [`CryptoRequest::Decrypt`]: enum.CryptoRequest.html#variant.Decrypt
+    Decrypted(Option<XSalsa20Poly1305Data>),
 }
@@ -73,6 +73,11 @@ pub struct ConductorConfig {
     /// Setup admin interfaces to control this conductor through a websocket connection
     pub admin_interfaces: Option<Vec<AdminInterfaceConfig>>,
 
+    /// Serve metrics in the Prometheus text format on this port on localhost.
+    /// Use 0 to bind to any free port. Metrics are not served if omitted.
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+
     /// Config options for the network module. Optional.
     pub network: Option<holochain_p2p::kitsune_p2p::KitsuneP2pConfig>,
     //
@@ -149,6 +154,7 @@ pub mod tests {
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 keystore_path: None,
                 admin_interfaces: None,
+                metrics_port: None,
                 use_dangerous_test_keystore: false,
             }
         );
@@ -174,6 +180,14 @@ pub mod tests {
       - driver:
           type: websocket
           port: 1234
+        auth:
+          allowed_origins:
+            - http://localhost:8888
+          require_token: true
+          tokens:
+            - admin-token
+
+    metrics_port: 9100
 
     network:
       bootstrap_service: https://bootstrap.holo.host
@@ -216,8 +230,14 @@ pub mod tests {
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 keystore_path: None,
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::Websocket { port: 1234 },
+                    auth: InterfaceAuthConfig {
+                        allowed_origins: Some(vec!["http://localhost:8888".into()]),
+                        require_token: true,
+                        tokens: vec!["admin-token".into()],
+                    },
                 }]),
+                metrics_port: Some(9100),
                 network: Some(network_config),
             }
         );
@@ -249,6 +269,7 @@ pub mod tests {
                 }),
                 keystore_path: Some(PathBuf::from("/path/to/keystore").into()),
                 admin_interfaces: None,
+                metrics_port: None,
                 use_dangerous_test_keystore: true,
             }
         );
@@ -7,11 +7,38 @@ pub struct AdminInterfaceConfig {
     /// By what means will the interface be exposed?
     /// Current only option is a local websocket running on a configurable port.
     pub driver: InterfaceDriver,
+    /// Who may connect to this interface. Anyone on this machine if omitted.
+    #[serde(default)]
+    pub auth: InterfaceAuthConfig,
     // /// How long will this interface be accessible between authentications?
     // /// TODO: implement once we have authentication
     // _session_duration_seconds: Option<u32>,
 }
 
+/// Who may connect to an interface, checked during the websocket handshake.
+/// The default lets any connection from this machine through.
+///
+/// Interfaces added through the admin interface are persisted with only the
+/// hashes of these tokens.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct InterfaceAuthConfig {
+    /// Browser origins which may connect, e.g. `http://localhost:8888`.
+    /// Only browsers send an origin, so this stops web pages from reaching
+    /// the interface but does not affect other local processes.
+    /// Any origin may connect if omitted.
+    #[serde(default)]
+    pub allowed_origins: Option<Vec<String>>,
+    /// Require connections to present a bearer token, either in an
+    /// `Authorization: Bearer` header or an `access_token` query parameter.
+    #[serde(default)]
+    pub require_token: bool,
+    /// Tokens this interface always accepts. Interfaces also accept the tokens
+    /// issued with `AdminRequest::IssueAdminInterfaceToken` or
+    /// `AdminRequest::IssueAppInterfaceToken`, depending on their kind.
+    #[serde(default)]
+    pub tokens: Vec<String>,
+}
+
 /// Configuration for interfaces, specifying the means by which an interface
 /// should be opened.
 ///
@@ -2,7 +2,9 @@ mod admin_interface;
 mod app_interface;
 pub mod config;
 pub mod signal_subscription;
+mod state_dump;
 
 pub use admin_interface::*;
 pub use app_interface::*;
 pub use config::*;
+pub use state_dump::*;
@@ -0,0 +1,48 @@
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::cell::CellId;
+use kitsune_p2p::dht_arc::DhtArc;
+
+/// A report on the state of a Cell, returned by [`AdminRequest::DumpState`].
+///
+/// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct CellStateDump {
+    /// The Cell this report is about
+    pub cell_id: CellId,
+    /// The length and head of the source chain
+    pub source_chain: SourceChainDump,
+    /// How many ops are in each stage of integration
+    pub integration: IntegrationDump,
+    /// The arc of the DHT this Cell's agent holds, as signed into its
+    /// agent info. None if the agent has not joined the network yet.
+    pub held_arc: Option<DhtArc>,
+    /// The number of other agents in this Cell's space
+    /// with unexpired agent info in the peer store
+    pub known_peers: usize,
+    /// The number of published ops authored by this Cell
+    /// which are still waiting on validation receipts
+    pub pending_validation_receipts: usize,
+}
+
+/// The length and head of a source chain.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SourceChainDump {
+    /// The number of elements on the chain
+    pub len: usize,
+    /// The header at the top of the chain, if there is one
+    pub head: Option<HeaderHash>,
+}
+
+/// How many ops are in each database an op passes through.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IntegrationDump {
+    /// Ops waiting on or undergoing validation
+    pub validation_limbo: usize,
+    /// Ops which are validated and waiting to be integrated
+    pub integration_limbo: usize,
+    /// Ops which have been integrated
+    pub integrated: usize,
+    /// Ops produced from this Cell's source chain
+    pub authored: usize,
+}
@@ -5,15 +5,109 @@ use crate::*;
 use ghost_actor::dependencies::futures::future::FutureExt;
 use holo_hash::{HOLO_HASH_CORE_LEN, HOLO_HASH_PREFIX_LEN};
 use holochain_zome_types::signature::{Sign, Signature};
+use holochain_zome_types::x_salsa20_poly1305::data::XSalsa20Poly1305Data;
+use holochain_zome_types::x_salsa20_poly1305::encrypted_data::XSalsa20Poly1305EncryptedData;
+use holochain_zome_types::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef;
+use holochain_zome_types::x_salsa20_poly1305::key_ref::KEY_REF_BYTES;
+use holochain_zome_types::x_salsa20_poly1305::nonce::NONCE_BYTES;
 use holochain_zome_types::x_salsa20_poly1305::{
-    X25519XSalsa20Poly1305Decrypt, X25519XSalsa20Poly1305Encrypt,
+    X25519XSalsa20Poly1305Decrypt, X25519XSalsa20Poly1305Encrypt, XSalsa20Poly1305Decrypt,
+    XSalsa20Poly1305DeriveKey, XSalsa20Poly1305Encrypt,
 };
 use lair_keystore_api::actor::{
-    Cert, CertDigest, CertPrivKey, LairClientApiSender, LairEntryType, TlsCertOptions,
+    Cert, CertDigest, CertPrivKey, KeystoreIndex, LairClientApiSender, LairEntryType,
+    TlsCertOptions,
 };
+use ring::rand::SecureRandom;
+use std::path::PathBuf;
+use std::sync::Arc;
+use xsalsa20poly1305::aead::{generic_array::GenericArray, Aead, NewAead};
+use xsalsa20poly1305::XSalsa20Poly1305;
 
-/// GhostSender type for the KeystoreApi
-pub type KeystoreSender = ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>;
+/// Hashed along with the secretbox secret and a key ref
+/// to resolve the secretbox key it refers to.
+const SECRETBOX_KEY_DOMAIN: &[u8] = b"holochain-secretbox-key";
+
+/// Hashed along with a key ref and a context to derive a new key ref.
+const SECRETBOX_DERIVE_DOMAIN: &[u8] = b"holochain-secretbox-derive";
+
+/// A handle to the keystore, through which all the lair calls can be made.
+/// This struct is cheaply clone-able, and clones share the same keystore.
+#[derive(Clone)]
+pub struct KeystoreSender {
+    sender: ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>,
+    secretbox_secret: Arc<SecretboxSecret>,
+}
+
+impl KeystoreSender {
+    /// Wrap a lair client.
+    /// The lair index of the secretbox secret is persisted at `secretbox_index_path`,
+    /// which should be given for any lair that persists its entries.
+    pub fn new(
+        sender: ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>,
+        secretbox_index_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            sender,
+            secretbox_secret: Arc::new(SecretboxSecret {
+                path: secretbox_index_path,
+                found: tokio::sync::Mutex::new(None),
+            }),
+        }
+    }
+}
+
+impl std::ops::Deref for KeystoreSender {
+    type Target = ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sender
+    }
+}
+
+impl std::fmt::Debug for KeystoreSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeystoreSender").finish()
+    }
+}
+
+/// Where to find the TLS cert whose private key is the secretbox secret.
+/// Nothing in lair tells the secret apart from any other TLS cert, so its
+/// index is remembered here along with its digest, to check it is still there.
+struct SecretboxSecret {
+    /// Where the index and digest are persisted, if lair persists its entries
+    path: Option<PathBuf>,
+    /// The index and digest, once they have been loaded or the secret created.
+    /// Held while the secret is created so only one is.
+    found: tokio::sync::Mutex<Option<(u32, Vec<u8>)>>,
+}
+
+impl SecretboxSecret {
+    /// Read the persisted index and digest, if there are any
+    fn load(&self) -> KeystoreApiResult<Option<(u32, Vec<u8>)>> {
+        let path = match &self.path {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+        let bytes = std::fs::read(path).map_err(|e| KeystoreError::from(e.to_string()))?;
+        if bytes.len() < 4 {
+            return Err("the persisted secretbox secret index is corrupt".into());
+        }
+        let (index, digest) = bytes.split_at(4);
+        let index = u32::from_le_bytes([index[0], index[1], index[2], index[3]]);
+        Ok(Some((index, digest.to_vec())))
+    }
+
+    /// Persist the index and digest, if lair persists its entries
+    fn store(&self, index: u32, digest: &[u8]) -> KeystoreApiResult<()> {
+        if let Some(path) = &self.path {
+            let mut bytes = index.to_le_bytes().to_vec();
+            bytes.extend_from_slice(digest);
+            std::fs::write(path, bytes).map_err(|e| KeystoreError::from(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
 
 /// Result type for legacy API calls.
 pub type KeystoreApiResult<T> = Result<T, KeystoreError>;
@@ -60,6 +154,35 @@ pub trait KeystoreSenderExt {
     ) -> KeystoreApiFuture<
         Option<holochain_zome_types::x_salsa20_poly1305::data::XSalsa20Poly1305Data>,
     >;
+
+    /// Generate a new secretbox key, returning an opaque ref to it.
+    ///
+    /// Keys are never stored or returned as such. A ref resolves to its key by
+    /// hashing it with the private key of the keystore's secretbox TLS cert,
+    /// so refs stay valid for as long as that cert is in the keystore.
+    fn create_x_salsa20_poly1305_key(&self) -> KeystoreApiFuture<XSalsa20Poly1305KeyRef>;
+
+    /// Derive a secretbox key from an existing key ref and a context,
+    /// returning an opaque ref to it.
+    /// The same key ref and context always derive the same key.
+    fn derive_x_salsa20_poly1305_key(
+        &self,
+        input: XSalsa20Poly1305DeriveKey,
+    ) -> KeystoreApiFuture<XSalsa20Poly1305KeyRef>;
+
+    /// Salsa20Poly1305 encrypt the data with the key the ref resolves to and a random nonce.
+    /// a.k.a. libsodium crypto_secretbox()
+    fn x_salsa20_poly1305_encrypt(
+        &self,
+        input: XSalsa20Poly1305Encrypt,
+    ) -> KeystoreApiFuture<XSalsa20Poly1305EncryptedData>;
+
+    /// The inverse of x_salsa20_poly1305_encrypt.
+    /// Returns None if decryption fails.
+    fn x_salsa20_poly1305_decrypt(
+        &self,
+        input: XSalsa20Poly1305Decrypt,
+    ) -> KeystoreApiFuture<Option<XSalsa20Poly1305Data>>;
 }
 
 impl KeystoreSenderExt for KeystoreSender {
@@ -197,6 +320,146 @@ impl KeystoreSenderExt for KeystoreSender {
         .boxed()
         .into()
     }
+
+    fn create_x_salsa20_poly1305_key(&self) -> KeystoreApiFuture<XSalsa20Poly1305KeyRef> {
+        async move {
+            let mut key_ref = [0; KEY_REF_BYTES];
+            ring::rand::SystemRandom::new()
+                .fill(&mut key_ref)
+                .map_err(|_| KeystoreError::from("failed to generate a key ref"))?;
+            Ok(key_ref.into())
+        }
+        .boxed()
+        .into()
+    }
+
+    fn derive_x_salsa20_poly1305_key(
+        &self,
+        input: XSalsa20Poly1305DeriveKey,
+    ) -> KeystoreApiFuture<XSalsa20Poly1305KeyRef> {
+        async move {
+            let hash = blake2b_simd::Params::new()
+                .hash_length(KEY_REF_BYTES)
+                .to_state()
+                .update(SECRETBOX_DERIVE_DOMAIN)
+                .update(input.as_key_ref_ref().as_ref())
+                .update(input.as_context_ref())
+                .finalize();
+            Ok(hash.as_bytes().try_into()?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn x_salsa20_poly1305_encrypt(
+        &self,
+        input: XSalsa20Poly1305Encrypt,
+    ) -> KeystoreApiFuture<XSalsa20Poly1305EncryptedData> {
+        let this = self.clone();
+        async move {
+            let key = secretbox_key(&this, input.as_key_ref_ref()).await?;
+            let mut nonce = [0; NONCE_BYTES];
+            ring::rand::SystemRandom::new()
+                .fill(&mut nonce)
+                .map_err(|_| KeystoreError::from("failed to generate a nonce"))?;
+            let cipher = XSalsa20Poly1305::new(GenericArray::from_slice(&key));
+            let encrypted_data = cipher
+                .encrypt(
+                    GenericArray::from_slice(&nonce),
+                    input.as_data_ref().as_ref(),
+                )
+                .map_err(|_| KeystoreError::from("secretbox encryption failed"))?;
+            Ok(XSalsa20Poly1305EncryptedData::new(
+                nonce.into(),
+                encrypted_data,
+            ))
+        }
+        .boxed()
+        .into()
+    }
+
+    fn x_salsa20_poly1305_decrypt(
+        &self,
+        input: XSalsa20Poly1305Decrypt,
+    ) -> KeystoreApiFuture<Option<XSalsa20Poly1305Data>> {
+        let this = self.clone();
+        async move {
+            let key = secretbox_key(&this, input.as_key_ref_ref()).await?;
+            let cipher = XSalsa20Poly1305::new(GenericArray::from_slice(&key));
+            let encrypted_data = input.as_encrypted_data_ref();
+            // Any failure to decrypt means the data was not encrypted with this key
+            Ok(cipher
+                .decrypt(
+                    GenericArray::from_slice(encrypted_data.as_nonce_ref().as_ref()),
+                    encrypted_data.as_encrypted_data_ref(),
+                )
+                .ok()
+                .map(XSalsa20Poly1305Data::from))
+        }
+        .boxed()
+        .into()
+    }
+}
+
+/// The index of the secretbox secret in lair, creating it if there is none.
+///
+/// The secret is the private key of a TLS cert created for this purpose.
+/// Lair only ever hands the private key to this process and has no call which
+/// signs with it, so nothing reachable through the keystore's signing calls
+/// can recreate a secretbox key.
+async fn get_or_create_secretbox_secret(
+    keystore: &KeystoreSender,
+) -> KeystoreApiResult<KeystoreIndex> {
+    let secret = &keystore.secretbox_secret;
+    let mut found = secret.found.lock().await;
+    if found.is_none() {
+        *found = secret.load()?;
+    }
+    if let Some((index, digest)) = &*found {
+        // Digests are unique to a cert, so a match means this is our secret
+        if let Ok((_, current)) = keystore.tls_cert_get((*index).into()).await {
+            if current.to_vec() == *digest {
+                return Ok((*index).into());
+            }
+        }
+        tracing::warn!(
+            index,
+            "The secretbox secret is missing from lair, so a new one is created. \
+            Data encrypted with the old one can't be decrypted."
+        );
+    }
+
+    // Make sure the network cert comes first, so it is never the secret
+    keystore.get_or_create_first_tls_cert().await?;
+    let mut tls_opt = TlsCertOptions::default();
+    tls_opt.alg = lair_keystore_api::actor::TlsCertAlg::PkcsEcdsaP256Sha256;
+    let (index, _, digest) = keystore
+        .tls_cert_new_self_signed_from_entropy(tls_opt)
+        .await?;
+    secret.store(*index, &digest.to_vec())?;
+    *found = Some((*index, digest.to_vec()));
+    Ok(index)
+}
+
+/// Resolve a key ref to the secretbox key it refers to.
+/// The same ref always resolves to the same key,
+/// and only this keystore can resolve it.
+async fn secretbox_key(
+    keystore: &KeystoreSender,
+    key_ref: &XSalsa20Poly1305KeyRef,
+) -> KeystoreApiResult<[u8; KEY_REF_BYTES]> {
+    let index = get_or_create_secretbox_secret(keystore).await?;
+    let secret = keystore.tls_cert_get_priv_key_by_index(index).await?;
+    let hash = blake2b_simd::Params::new()
+        .hash_length(KEY_REF_BYTES)
+        .to_state()
+        .update(SECRETBOX_KEY_DOMAIN)
+        .update(&secret)
+        .update(key_ref.as_ref())
+        .finalize();
+    let mut key = [0; KEY_REF_BYTES];
+    key.copy_from_slice(hash.as_bytes());
+    Ok(key)
 }
 
 #[cfg(test)]
@@ -213,4 +476,126 @@ mod tests {
         assert_eq!(cert1, cert2);
         assert_eq!(priv1, priv2);
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_x_salsa20_poly1305_round_trip() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let key_ref = keystore.create_x_salsa20_poly1305_key().await.unwrap();
+        let data = XSalsa20Poly1305Data::from(vec![1, 2, 3, 4]);
+
+        let encrypted = keystore
+            .x_salsa20_poly1305_encrypt(XSalsa20Poly1305Encrypt::new(key_ref, data.clone()))
+            .await
+            .unwrap();
+        assert_ne!(encrypted.as_encrypted_data_ref(), data.as_ref());
+        let decrypted = keystore
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(key_ref, encrypted.clone()))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, Some(data));
+
+        let other_ref = keystore.create_x_salsa20_poly1305_key().await.unwrap();
+        assert_ne!(key_ref, other_ref);
+        let decrypted = keystore
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(other_ref, encrypted))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, None);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_x_salsa20_poly1305_derive() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let key_ref = keystore.create_x_salsa20_poly1305_key().await.unwrap();
+        let derive = |context: &[u8]| {
+            keystore.derive_x_salsa20_poly1305_key(XSalsa20Poly1305DeriveKey::new(
+                key_ref,
+                context.to_vec(),
+            ))
+        };
+        let a = derive(b"a").await.unwrap();
+        assert_eq!(a, derive(b"a").await.unwrap());
+        assert_ne!(a, derive(b"b").await.unwrap());
+        assert_ne!(a, key_ref);
+
+        // A derived key decrypts what it encrypted, on any keystore
+        // handle to the same lair instance
+        let data = XSalsa20Poly1305Data::from(vec![5, 6, 7]);
+        let encrypted = keystore
+            .x_salsa20_poly1305_encrypt(XSalsa20Poly1305Encrypt::new(a, data.clone()))
+            .await
+            .unwrap();
+        let decrypted = keystore
+            .clone()
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(a, encrypted))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, Some(data));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_x_salsa20_poly1305_keys_are_per_keystore() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let other_keystore = spawn_test_keystore().await.unwrap();
+        let key_ref = keystore.create_x_salsa20_poly1305_key().await.unwrap();
+        let data = XSalsa20Poly1305Data::from(vec![8, 9]);
+        let encrypted = keystore
+            .x_salsa20_poly1305_encrypt(XSalsa20Poly1305Encrypt::new(key_ref, data.clone()))
+            .await
+            .unwrap();
+
+        // The same ref resolves to a different key in another lair
+        let decrypted = other_keystore
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(key_ref, encrypted.clone()))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, None);
+
+        // The secret is only created once per lair
+        let last_index = keystore.lair_get_last_entry_index().await.unwrap();
+        let decrypted = keystore
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(key_ref, encrypted))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, Some(data));
+        assert_eq!(
+            keystore.lair_get_last_entry_index().await.unwrap(),
+            last_index
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_x_salsa20_poly1305_secret_index_is_persisted() {
+        let dir = tempdir::TempDir::new("secretbox").unwrap();
+        let path = dir.path().join("index");
+        let lair = spawn_test_keystore().await.unwrap();
+        // Another TLS cert besides the network one, which must not become the secret
+        lair.get_or_create_first_tls_cert().await.unwrap();
+        let mut tls_opt = TlsCertOptions::default();
+        tls_opt.alg = lair_keystore_api::actor::TlsCertAlg::PkcsEcdsaP256Sha256;
+        let (other_cert, _, _) = lair
+            .tls_cert_new_self_signed_from_entropy(tls_opt)
+            .await
+            .unwrap();
+
+        let keystore = KeystoreSender::new((*lair).clone(), Some(path.clone()));
+        let key_ref = keystore.create_x_salsa20_poly1305_key().await.unwrap();
+        let data = XSalsa20Poly1305Data::from(vec![10, 11]);
+        let encrypted = keystore
+            .x_salsa20_poly1305_encrypt(XSalsa20Poly1305Encrypt::new(key_ref, data.clone()))
+            .await
+            .unwrap();
+        let (index, _) = keystore.secretbox_secret.load().unwrap().unwrap();
+        assert!(index > *other_cert);
+
+        // A new handle on the same lair finds the secret through the persisted index
+        let last_index = lair.lair_get_last_entry_index().await.unwrap();
+        let keystore = KeystoreSender::new((*lair).clone(), Some(path));
+        let decrypted = keystore
+            .x_salsa20_poly1305_decrypt(XSalsa20Poly1305Decrypt::new(key_ref, encrypted))
+            .await
+            .unwrap();
+        assert_eq!(decrypted, Some(data));
+        assert_eq!(lair.lair_get_last_entry_index().await.unwrap(), last_index);
+    }
 }
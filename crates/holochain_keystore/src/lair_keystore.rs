@@ -6,6 +6,9 @@ use ghost_actor::dependencies::futures::stream::StreamExt;
 use lair_keystore_api::actor::*;
 use lair_keystore_api::*;
 
+/// The file in the lair directory where the lair index of the secretbox secret is kept.
+const SECRETBOX_INDEX_FILE: &str = "holochain-secretbox-index";
+
 /// Spawn a new keystore backed by lair_keystore_client.
 pub async fn spawn_lair_keystore(
     lair_dir: Option<&std::path::Path>,
@@ -15,6 +18,7 @@ pub async fn spawn_lair_keystore(
         config = config.set_root_path(lair_dir);
     }
     let config = config.build();
+    let secretbox_index_path = config.get_root_path().join(SECRETBOX_INDEX_FILE);
     let (api, mut evt) = lair_keystore_client::assert_running_lair_and_connect(config).await?;
 
     // TODO - actual passphrase handling
@@ -30,5 +34,5 @@ pub async fn spawn_lair_keystore(
         }
     });
 
-    Ok(api)
+    Ok(KeystoreSender::new(api, Some(secretbox_index_path)))
 }
@@ -78,7 +78,7 @@ pub async fn spawn_test_keystore() -> KeystoreApiResult<KeystoreSender> {
         ],
     )
     .await?;
-    Ok(api)
+    Ok(KeystoreSender::new(api, None))
 }
 
 #[cfg(test)]
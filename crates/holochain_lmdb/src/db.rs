@@ -68,6 +68,9 @@ pub enum DbName {
     ValidationLimbo,
     /// KVV store to accumulate validation receipts for a published EntryHash
     ValidationReceipts,
+    /// KV store of zome functions scheduled to be called back, keyed by the
+    /// time they are next due
+    ScheduledFns,
     /// Single store for all known agents on the network
     Agent,
 }
@@ -99,6 +102,7 @@ impl DbName {
             IntegrationLimbo => Single,
             ValidationLimbo => Single,
             ValidationReceipts => Multi,
+            ScheduledFns => Single,
             Agent => Single,
         }
     }
@@ -170,6 +174,8 @@ lazy_static! {
     pub static ref VALIDATION_LIMBO: DbKey<SingleStore> = DbKey::new(DbName::ValidationLimbo);
     /// The key to access the ValidationReceipts database
     pub static ref VALIDATION_RECEIPTS: DbKey<MultiStore> = DbKey::new(DbName::ValidationReceipts);
+    /// The key to access the ScheduledFns database
+    pub static ref SCHEDULED_FNS: DbKey<SingleStore> = DbKey::new(DbName::ScheduledFns);
     /// The key to access the Agent database
     pub static ref AGENT: DbKey<SingleStore> = DbKey::new(DbName::Agent);
 }
@@ -196,6 +202,47 @@ pub(super) fn initialize_databases(rkv: &Rkv, kind: &EnvironmentKind) -> Databas
     Ok(())
 }
 
+/// Open the databases of an environment again after it has been reopened,
+/// replacing the references to them.
+pub(super) fn reinitialize_databases(rkv: &Rkv, kind: &EnvironmentKind) -> DatabaseResult<()> {
+    let mut um = UniversalMap::new();
+    register_databases(rkv, kind, &mut um)?;
+    DB_MAP_MAP.write().insert(rkv.path().to_owned(), um);
+    Ok(())
+}
+
+/// Copy every database of an environment into a new, empty environment.
+/// The databases are created in the new environment in the same order,
+/// so references to them stay valid for it.
+pub(super) fn copy_databases(from: &Rkv, to: &Rkv, kind: &EnvironmentKind) -> DatabaseResult<()> {
+    let mut um = UniversalMap::new();
+    register_databases(to, kind, &mut um)?;
+    // Opening an existing database by name gives a plain handle to it,
+    // which iterates over every value, including duplicates.
+    let stores = um
+        .keys()
+        .map(|db_name| {
+            let db_str = format!("{}", db_name);
+            Ok((
+                from.open_single(db_str.as_str(), StoreOptions::default())?,
+                to.open_single(db_str.as_str(), StoreOptions::default())?,
+            ))
+        })
+        .collect::<DatabaseResult<Vec<_>>>()?;
+    let reader = from.read()?;
+    let mut writer = to.write()?;
+    for (from_store, to_store) in stores {
+        for item in from_store.iter_start(&reader)? {
+            let (k, v) = item?;
+            if let Some(v) = v {
+                to_store.put(&mut writer, k, &v)?;
+            }
+        }
+    }
+    writer.commit()?;
+    Ok(())
+}
+
 pub(super) fn get_db<V: 'static + Copy + Send + Sync>(
     path: &Path,
     key: &'static DbKey<V>,
@@ -230,6 +277,7 @@ fn register_databases(env: &Rkv, kind: &EnvironmentKind, um: &mut DbMap) -> Data
             register_db(env, um, &*INTEGRATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_RECEIPTS)?;
+            register_db(env, um, &*SCHEDULED_FNS)?;
         }
         EnvironmentKind::Conductor => {
             register_db(env, um, &*CONDUCTOR_STATE)?;
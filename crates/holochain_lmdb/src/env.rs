@@ -1,9 +1,12 @@
 //! Functions dealing with obtaining and referencing singleton LMDB environments
 
+use crate::db::copy_databases;
 use crate::db::get_db;
 use crate::db::initialize_databases;
+use crate::db::reinitialize_databases;
 use crate::db::DbKey;
 use crate::db::GetDb;
+use crate::error::is_map_full;
 use crate::error::DatabaseError;
 use crate::error::DatabaseResult;
 use crate::transaction::Reader;
@@ -22,9 +25,15 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 const DEFAULT_INITIAL_MAP_SIZE: usize = 100 * 1024 * 1024; // 100MB
 const MAX_DBS: u32 = 32;
+/// How many times a transaction which filled the map is retried
+const MAP_FULL_RETRIES: usize = 4;
+/// How long to wait for other transactions to finish before
+/// resizing or compacting an environment
+const EXCLUSIVE_ACCESS_TIMEOUT: Duration = Duration::from_secs(10);
 
 lazy_static! {
     static ref ENVIRONMENTS: RwLock<HashMap<PathBuf, EnvironmentWrite>> = {
@@ -87,6 +96,15 @@ fn rkv_builder(
     }
 }
 
+/// Double the size of the map.
+/// There must not be any transactions open in this environment.
+fn grow_map(rkv: &Rkv) -> DatabaseResult<()> {
+    let map_size = rkv.info()?.map_size() * 2;
+    tracing::info!(path = ?rkv.path(), map_size, "Growing LMDB map");
+    rkv.set_map_size(map_size)?;
+    Ok(())
+}
+
 /// A read-only version of [EnvironmentWrite].
 /// This environment can only generate read-only transactions, never read-write.
 #[derive(Clone)]
@@ -156,6 +174,15 @@ impl EnvironmentWrite {
         path_prefix: &Path,
         kind: EnvironmentKind,
         keystore: KeystoreSender,
+    ) -> DatabaseResult<EnvironmentWrite> {
+        Self::open(path_prefix, kind, keystore, None)
+    }
+
+    fn open(
+        path_prefix: &Path,
+        kind: EnvironmentKind,
+        keystore: KeystoreSender,
+        initial_map_size: Option<usize>,
     ) -> DatabaseResult<EnvironmentWrite> {
         let mut map = ENVIRONMENTS.write();
         let path = path_prefix.join(kind.path());
@@ -167,7 +194,7 @@ impl EnvironmentWrite {
             hash_map::Entry::Occupied(e) => e.get().clone(),
             hash_map::Entry::Vacant(e) => e
                 .insert({
-                    let rkv = rkv_builder(None, None)(&path)?;
+                    let rkv = rkv_builder(initial_map_size, None)(&path)?;
                     tracing::debug!("Initializing databases for path {:?}", path);
                     initialize_databases(&rkv, &kind)?;
                     EnvironmentWrite(EnvironmentRead {
@@ -197,6 +224,93 @@ impl EnvironmentWrite {
         EnvironmentWriteRef(self.0.guard())
     }
 
+    /// Run a closure, passing in a mutable reference to a read-write
+    /// transaction, and commit the transaction after the closure has run.
+    ///
+    /// If the map fills up during the transaction the map is grown and
+    /// the closure is run again in a new transaction, so it must be safe
+    /// to run more than once.
+    pub fn with_commit_retry<E, R, F: Send>(&self, mut f: F) -> Result<R, E>
+    where
+        E: From<DatabaseError> + std::error::Error + 'static,
+        F: FnMut(&mut Writer) -> Result<R, E>,
+    {
+        let mut retries = 0;
+        loop {
+            // The guard must be dropped before the map can be grown
+            let result = self.guard().with_commit(&mut f);
+            match result {
+                Err(e) if retries < MAP_FULL_RETRIES && is_map_full(&e) => {
+                    retries += 1;
+                    let rkv = self
+                        .0
+                        .arc
+                        .try_write_for(EXCLUSIVE_ACCESS_TIMEOUT)
+                        .ok_or_else(|| DatabaseError::EnvironmentBusy(self.0.path.clone()))?;
+                    grow_map(&rkv)?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Compact the environment by copying its data into a new environment
+    /// and swapping that in for the original, which gives back the space
+    /// left behind by deleted data.
+    ///
+    /// Waits for open transactions to finish, and blocks new ones
+    /// until the compaction is done. This blocks the thread, so run it
+    /// with `spawn_blocking` from async code.
+    pub fn compact(&self) -> DatabaseResult<()> {
+        let path = &self.0.path;
+        let mut rkv = self
+            .0
+            .arc
+            .try_write_for(EXCLUSIVE_ACCESS_TIMEOUT)
+            .ok_or_else(|| DatabaseError::EnvironmentBusy(path.clone()))?;
+        let compact_path = path.join("compact");
+        if compact_path.is_dir() {
+            std::fs::remove_dir_all(&compact_path)?;
+        }
+        std::fs::create_dir(&compact_path)?;
+        let map_size = rkv.info()?.map_size();
+        let compacted = rkv_builder(Some(map_size), None)(&compact_path)?;
+        copy_databases(&rkv, &compacted, &self.0.kind)?;
+        drop(compacted);
+
+        // Close the original environment before its data file is replaced.
+        // The lock has to hold an environment, so an empty one stands in
+        // until the compacted data can be reopened.
+        let placeholder_path = path.join("compact-placeholder");
+        if placeholder_path.is_dir() {
+            std::fs::remove_dir_all(&placeholder_path)?;
+        }
+        std::fs::create_dir(&placeholder_path)?;
+        let placeholder = rkv_builder(None, None)(&placeholder_path)?;
+        drop(std::mem::replace(&mut *rkv, placeholder));
+        std::fs::rename(compact_path.join("data.mdb"), path.join("data.mdb"))?;
+        let reopened = rkv_builder(Some(map_size), None)(path)?;
+        reinitialize_databases(&reopened, &self.0.kind)?;
+        *rkv = reopened;
+
+        std::fs::remove_dir_all(&compact_path)?;
+        std::fs::remove_dir_all(&placeholder_path)?;
+        tracing::info!(?path, "Compacted LMDB environment");
+        Ok(())
+    }
+
+    /// Remove the environment of a cell which isn't running, going through
+    /// its environment if that is still open and without opening it if not.
+    pub async fn remove_cell(path_prefix: &Path, cell_id: CellId) -> DatabaseResult<()> {
+        let path = path_prefix.join(EnvironmentKind::Cell(cell_id).path());
+        let open = ENVIRONMENTS.read().get(&path).cloned();
+        match open {
+            Some(env) => env.remove().await,
+            None if path.is_dir() => Ok(std::fs::remove_dir_all(&path)?),
+            None => Ok(()),
+        }
+    }
+
     /// Remove the db and directory
     pub async fn remove(self) -> DatabaseResult<()> {
         let mut map = ENVIRONMENTS.write();
@@ -341,3 +455,108 @@ impl<'e> ReadManager<'e> for EnvironmentWriteRef<'e> {
         self.0.with_reader(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::ELEMENT_VAULT_PUBLIC_ENTRIES;
+    use crate::test_utils::test_keystore;
+    use holochain_zome_types::test_utils::fake_cell_id;
+    use tempdir::TempDir;
+
+    /// How much of the map is taken up by pages which have been written
+    fn used_bytes(env: &EnvironmentWrite) -> usize {
+        let guard = env.guard();
+        let info = guard.rkv().info().unwrap();
+        (info.last_pgno() + 1) * guard.rkv().stat().unwrap().page_size() as usize
+    }
+
+    fn map_size(env: &EnvironmentWrite) -> usize {
+        env.guard().rkv().info().unwrap().map_size()
+    }
+
+    fn count(env: &EnvironmentWrite, db: rkv::SingleStore) -> usize {
+        env.guard()
+            .with_reader(|reader| DatabaseResult::Ok(db.iter_start(&reader)?.count()))
+            .unwrap()
+    }
+
+    #[test]
+    fn map_grows_when_full() {
+        let tmpdir = TempDir::new("holochain-test-environments").unwrap();
+        let env = EnvironmentWrite::open(
+            tmpdir.path(),
+            EnvironmentKind::Cell(fake_cell_id(1)),
+            test_keystore(),
+            Some(1024 * 1024),
+        )
+        .unwrap();
+        let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES).unwrap();
+        let value = vec![0u8; 16 * 1024];
+
+        // A single transaction which is bigger than the whole map
+        env.with_commit_retry(|writer| {
+            for i in 0u32..128 {
+                db.put(writer, i.to_be_bytes(), &rkv::Value::Blob(&value))?;
+            }
+            DatabaseResult::Ok(())
+        })
+        .unwrap();
+        assert!(map_size(&env) > 2 * 1024 * 1024);
+        assert_eq!(count(&env, db), 128);
+
+        // Later transactions grow the map again as it fills up
+        for i in 128u32..256 {
+            env.with_commit_retry(|writer| {
+                db.put(writer, i.to_be_bytes(), &rkv::Value::Blob(&value))?;
+                DatabaseResult::Ok(())
+            })
+            .unwrap();
+        }
+        assert_eq!(count(&env, db), 256);
+    }
+
+    #[test]
+    fn compact_gives_back_deleted_space() {
+        let tmpdir = TempDir::new("holochain-test-environments").unwrap();
+        let env = EnvironmentWrite::new(
+            tmpdir.path(),
+            EnvironmentKind::Cell(fake_cell_id(1)),
+            test_keystore(),
+        )
+        .unwrap();
+        let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES).unwrap();
+        let value = vec![0u8; 16 * 1024];
+
+        env.guard()
+            .with_commit(|writer| {
+                for i in 0u32..128 {
+                    db.put(writer, i.to_be_bytes(), &rkv::Value::Blob(&value))?;
+                }
+                DatabaseResult::Ok(())
+            })
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| {
+                for i in 1u32..128 {
+                    db.delete(writer, i.to_be_bytes())?;
+                }
+                DatabaseResult::Ok(())
+            })
+            .unwrap();
+        let used_before = used_bytes(&env);
+
+        env.compact().unwrap();
+        assert!(used_bytes(&env) < used_before / 2);
+        assert_eq!(count(&env, db), 1);
+
+        // The same database references still work after compaction
+        env.guard()
+            .with_commit(|writer| {
+                db.put(writer, 1u32.to_be_bytes(), &rkv::Value::Blob(&value))?;
+                DatabaseResult::Ok(())
+            })
+            .unwrap();
+        assert_eq!(count(&env, db), 2);
+    }
+}
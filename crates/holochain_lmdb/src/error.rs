@@ -23,6 +23,9 @@ pub enum DatabaseError {
     #[error("LMDB environment directory does not exist at configured path: {0}")]
     EnvironmentMissing(PathBuf),
 
+    #[error("Timed out waiting for exclusive access to the LMDB environment at: {0}")]
+    EnvironmentBusy(PathBuf),
+
     #[error("There is an unexpected value in an LMDB database (TODO: more info)")]
     InvalidValue,
 
@@ -91,6 +94,21 @@ impl PartialEq for DatabaseError {
 
 pub type DatabaseResult<T> = Result<T, DatabaseError>;
 
+/// Whether an error, or any error which caused it, is LMDB reporting that
+/// the environment's map is full.
+pub fn is_map_full(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(e);
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<failure::Compat<rkv::StoreError>>() {
+            if let rkv::StoreError::LmdbError(rkv::LmdbError::MapFull) = e.get_ref() {
+                return true;
+            }
+        }
+        source = e.source();
+    }
+    false
+}
+
 // Note: these are necessary since rkv Errors do not have std::Error impls,
 // so we have to do some finagling
 
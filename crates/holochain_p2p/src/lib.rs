@@ -99,6 +99,13 @@ pub trait HolochainP2pCellT {
         to_agent: AgentPubKey,
         receipt: SerializedBytes,
     ) -> actor::HolochainP2pResult<()>;
+
+    /// Send a warrant to the authorities for the warranted agent.
+    async fn put_warrant(
+        &mut self,
+        warrant: SignedWarrant,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()>;
 }
 
 /// A wrapper around HolochainP2pSender that partially applies the dna_hash / agent_pub_key.
@@ -276,6 +283,22 @@ impl HolochainP2pCellT for HolochainP2pCell {
             )
             .await
     }
+
+    /// Send a warrant to the authorities for the warranted agent.
+    async fn put_warrant(
+        &mut self,
+        warrant: SignedWarrant,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .put_warrant(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                warrant,
+                timeout_ms,
+            )
+            .await
+    }
 }
 
 pub use kitsune_p2p::dht_arc;
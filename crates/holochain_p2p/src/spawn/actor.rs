@@ -179,6 +179,25 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming warrant from a remote node
+    fn handle_incoming_put_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        from_agent: AgentPubKey,
+        warrant: SignedWarrant,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender
+                .put_warrant(dna_hash, to_agent, from_agent, warrant)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming validation receipt from a remote node
     fn handle_incoming_validation_receipt(
         &mut self,
@@ -326,7 +345,8 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             } => self.handle_incoming_get_agent_activity(space, to_agent, agent, query, options),
             // holochain_p2p never publishes via request
             // these only occur on broadcasts
-            crate::wire::WireMessage::Publish { .. } => {
+            crate::wire::WireMessage::Publish { .. }
+            | crate::wire::WireMessage::PutWarrant { .. } => {
                 Err(HolochainP2pError::invalid_p2p_message(
                     "invalid: publish is a broadcast type, not a request".to_string(),
                 )
@@ -381,6 +401,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 dht_hash,
                 ops,
             ),
+            crate::wire::WireMessage::PutWarrant { warrant } => {
+                self.handle_incoming_put_warrant(space, to_agent, from_agent, warrant)
+            }
         }
     }
 
@@ -829,4 +852,38 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_put_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        warrant: SignedWarrant,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+        // Warrants are held by the warranted agent's activity authorities
+        let basis =
+            holo_hash::AnyDhtHash::from(warrant.warrant().warranted_agent().clone()).to_kitsune();
+
+        let payload = crate::wire::WireMessage::put_warrant(warrant).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p
+                .notify_multi(kitsune_p2p::actor::NotifyMulti {
+                    space,
+                    from_agent,
+                    basis,
+                    remote_agent_count: None, // default best-effort
+                    timeout_ms,
+                    payload,
+                })
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
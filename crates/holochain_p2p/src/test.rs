@@ -103,6 +103,15 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<()> {
         Err("stub".into())
     }
+    fn handle_put_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        warrant: SignedWarrant,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -148,6 +157,10 @@ mod tests {
     use futures::future::FutureExt;
     use ghost_actor::GhostControlSender;
 
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+    use holochain_keystore::AgentPubKeyExt;
+    use holochain_keystore::KeystoreSender;
+    use holochain_zome_types::signature::Signature;
     use holochain_zome_types::HeaderHashed;
     use holochain_zome_types::ValidationStatus;
     use kitsune_p2p::dependencies::kitsune_p2p_proxy::TlsConfig;
@@ -159,24 +172,42 @@ mod tests {
         };
     }
 
-    fn test_setup() -> (
+    async fn test_setup() -> (
+        KeystoreSender,
         holo_hash::DnaHash,
         holo_hash::AgentPubKey,
         holo_hash::AgentPubKey,
         holo_hash::AgentPubKey,
     ) {
         observability::test_run().unwrap();
-        (
-            newhash!(DnaHash, 's'),
-            newhash!(AgentPubKey, '1'),
-            newhash!(AgentPubKey, '2'),
-            newhash!(AgentPubKey, '3'),
-        )
+        let keystore = spawn_test_keystore().await.unwrap();
+        let mut agents = Vec::new();
+        for _ in 0..3 {
+            agents.push(
+                holo_hash::AgentPubKey::new_from_pure_entropy(&keystore)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let a3 = agents.pop().unwrap();
+        let a2 = agents.pop().unwrap();
+        let a1 = agents.pop().unwrap();
+        (keystore, newhash!(DnaHash, 's'), a1, a2, a3)
+    }
+
+    /// Sign with the agent's key so the agent infos made on join verify.
+    fn sign_network_data(
+        keystore: &KeystoreSender,
+        to_agent: holo_hash::AgentPubKey,
+        data: Vec<u8>,
+    ) -> futures::future::BoxFuture<'static, Result<Signature, HolochainP2pError>> {
+        let signature = to_agent.sign_raw(keystore, &data);
+        async move { signature.await.map_err(HolochainP2pError::other) }.boxed()
     }
 
     #[tokio::test(threaded_scheduler)]
     async fn test_call_remote_workflow() {
-        let (dna, a1, a2, _) = test_setup();
+        let (keystore, dna, a1, a2, _) = test_setup().await;
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -197,8 +228,13 @@ mod tests {
                                 .into(),
                         ));
                     }
-                    SignNetworkData { respond, .. } => {
-                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    SignNetworkData {
+                        respond,
+                        to_agent,
+                        data,
+                        ..
+                    } => {
+                        respond.r(Ok(sign_network_data(&keystore, to_agent, data).into()));
                     }
                     PutAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
@@ -233,7 +269,7 @@ mod tests {
 
     #[tokio::test(threaded_scheduler)]
     async fn test_send_validation_receipt_workflow() {
-        let (dna, a1, a2, _) = test_setup();
+        let (keystore, dna, a1, a2, _) = test_setup().await;
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -254,8 +290,13 @@ mod tests {
                         assert_eq!(b"receipt-test".to_vec(), receipt);
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
                     }
-                    SignNetworkData { respond, .. } => {
-                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    SignNetworkData {
+                        respond,
+                        to_agent,
+                        data,
+                        ..
+                    } => {
+                        respond.r(Ok(sign_network_data(&keystore, to_agent, data).into()));
                     }
                     PutAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
@@ -283,7 +324,7 @@ mod tests {
 
     #[tokio::test(threaded_scheduler)]
     async fn test_publish_workflow() {
-        let (dna, a1, a2, a3) = test_setup();
+        let (keystore, dna, a1, a2, a3) = test_setup().await;
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -304,8 +345,13 @@ mod tests {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
                         recv_count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
                     }
-                    SignNetworkData { respond, .. } => {
-                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    SignNetworkData {
+                        respond,
+                        to_agent,
+                        data,
+                        ..
+                    } => {
+                        respond.r(Ok(sign_network_data(&keystore, to_agent, data).into()));
                     }
                     PutAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
@@ -339,7 +385,7 @@ mod tests {
 
     #[tokio::test(threaded_scheduler)]
     async fn test_get_workflow() {
-        let (dna, a1, a2, _a3) = test_setup();
+        let (keystore, dna, a1, a2, _a3) = test_setup().await;
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -391,8 +437,13 @@ mod tests {
                         };
                         respond.r(Ok(async move { Ok(resp) }.boxed().into()));
                     }
-                    SignNetworkData { respond, .. } => {
-                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    SignNetworkData {
+                        respond,
+                        to_agent,
+                        data,
+                        ..
+                    } => {
+                        respond.r(Ok(sign_network_data(&keystore, to_agent, data).into()));
                     }
                     PutAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
@@ -427,7 +478,7 @@ mod tests {
 
     #[tokio::test(threaded_scheduler)]
     async fn test_get_links_workflow() {
-        let (dna, a1, a2, _) = test_setup();
+        let (keystore, dna, a1, a2, _) = test_setup().await;
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -451,8 +502,13 @@ mod tests {
                     GetLinks { respond, .. } => {
                         respond.r(Ok(async move { Ok(test_1_clone) }.boxed().into()));
                     }
-                    SignNetworkData { respond, .. } => {
-                        respond.r(Ok(async move { Ok(vec![0; 64].into()) }.boxed().into()));
+                    SignNetworkData {
+                        respond,
+                        to_agent,
+                        data,
+                        ..
+                    } => {
+                        respond.r(Ok(sign_network_data(&keystore, to_agent, data).into()));
                     }
                     PutAgentInfoSigned { respond, .. } => {
                         respond.r(Ok(async move { Ok(()) }.boxed().into()));
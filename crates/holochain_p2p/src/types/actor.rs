@@ -136,11 +136,18 @@ pub struct GetLinksOptions {
     /// Note - if all requests time-out you will receive an empty result,
     /// not a timeout error.
     pub timeout_ms: Option<u64>,
+
+    /// [Remote]
+    /// Tells the remote-end which links to return
+    pub filter: LinkFilter,
 }
 
 impl Default for GetLinksOptions {
     fn default() -> Self {
-        Self { timeout_ms: None }
+        Self {
+            timeout_ms: None,
+            filter: LinkFilter::default(),
+        }
     }
 }
 
@@ -256,6 +263,14 @@ ghost_actor::ghost_chan! {
 
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, from_agent: AgentPubKey, receipt: SerializedBytes) -> ();
+
+        /// Send a warrant to the authorities for the warranted agent.
+        fn put_warrant(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            warrant: SignedWarrant,
+            timeout_ms: Option<u64>,
+        ) -> ();
     }
 }
 
@@ -27,21 +27,32 @@ impl From<&actor::GetOptions> for GetOptions {
 
 /// GetMeta options help control how the get is processed at various levels.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+pub struct GetMetaOptions {
+    /// Which metadata the authority should return
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
 /// GetLinks options help control how the get is processed at various levels.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetLinksOptions {}
+pub struct GetLinksOptions {
+    /// Which links the authority should return
+    #[serde(default)]
+    pub filter: LinkFilter,
+}
 
 impl From<&actor::GetLinksOptions> for GetLinksOptions {
-    fn from(_a: &actor::GetLinksOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetLinksOptions) -> Self {
+        Self {
+            filter: a.filter.clone(),
+        }
     }
 }
 
@@ -150,6 +161,15 @@ ghost_actor::ghost_chan! {
             receipt: SerializedBytes,
         ) -> ();
 
+        /// A remote node has sent us a warrant about an agent
+        /// whose activity we are an authority for.
+        fn put_warrant(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            from_agent: AgentPubKey,
+            warrant: SignedWarrant,
+        ) -> ();
+
         /// The p2p module wishes to query our DhtOpHash store.
         fn fetch_op_hashes_for_constraints(
             dna_hash: DnaHash,
@@ -190,6 +210,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
+            HolochainP2pEvent::PutWarrant { $i, .. } => { $($t)* }
             HolochainP2pEvent::FetchOpHashesForConstraints { $i, .. } => { $($t)* }
             HolochainP2pEvent::FetchOpHashData { $i, .. } => { $($t)* }
             HolochainP2pEvent::SignNetworkData { $i, .. } => { $($t)* }
@@ -58,6 +58,9 @@ pub(crate) enum WireMessage {
     GetValidationPackage {
         header_hash: HeaderHash,
     },
+    PutWarrant {
+        warrant: SignedWarrant,
+    },
 }
 
 impl WireMessage {
@@ -131,4 +134,8 @@ impl WireMessage {
     pub fn get_validation_package(header_hash: HeaderHash) -> WireMessage {
         Self::GetValidationPackage { header_hash }
     }
+
+    pub fn put_warrant(warrant: SignedWarrant) -> WireMessage {
+        Self::PutWarrant { warrant }
+    }
 }
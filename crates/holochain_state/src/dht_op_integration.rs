@@ -100,6 +100,8 @@ pub struct IntegrationLimboValue {
     pub validation_status: ValidationStatus,
     /// The op
     pub op: DhtOpLight,
+    /// Whether the author asked for a validation receipt
+    pub send_receipt: bool,
 }
 
 impl IntegratedDhtOpsBuf {
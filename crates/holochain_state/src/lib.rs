@@ -40,6 +40,7 @@ pub mod element_buf;
 pub mod metadata;
 #[allow(missing_docs)]
 pub mod prelude;
+pub mod scheduled_fn_db;
 #[allow(missing_docs)]
 pub mod source_chain;
 pub mod validation_db;
@@ -207,6 +207,9 @@ where
     /// Deregister the highest observed sequence number on an agents chain
     fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()>;
 
+    /// Registers a warrant on the warranted agent's public key
+    fn register_warrant(&mut self, warrant: SignedWarrant) -> DatabaseResult<()>;
+
     /// Registers a [Header::Update] on the referenced [Header] or [Entry]
     fn register_update(&mut self, update: header::Update) -> DatabaseResult<()>;
 
@@ -284,6 +287,9 @@ where
     fn get_activity_observed(&self, agent: &AgentPubKey)
         -> DatabaseResult<Option<HighestObserved>>;
 
+    /// Get all the warrants issued against this agent
+    fn get_warrants(&self, agent: &AgentPubKey) -> DatabaseResult<Vec<SignedWarrant>>;
+
     /// Returns all the hashes of [Update] headers registered on an [Entry]
     fn get_updates<'r, R: Readable>(
         &'r self,
@@ -954,6 +960,14 @@ where
             .delete(MiscMetaKey::chain_observed(&agent).into())
     }
 
+    fn register_warrant(&mut self, warrant: SignedWarrant) -> DatabaseResult<()> {
+        let proof = &warrant.warrant().proof;
+        let hash = HeaderHash::with_data_sync(proof.warranted_header().header());
+        let key =
+            MiscMetaKey::warrant(proof.warranted_agent(), &hash, &warrant.warrant().author).into();
+        self.misc_meta.put(key, MiscMetaValue::Warrant(warrant))
+    }
+
     fn get_headers<'r, R: Readable>(
         &'r self,
         r: &'r R,
@@ -1148,6 +1162,15 @@ where
             .map(MiscMetaValue::chain_observed))
     }
 
+    fn get_warrants(&self, agent: &AgentPubKey) -> DatabaseResult<Vec<SignedWarrant>> {
+        let key = MiscMetaKey::warrants(&agent).into();
+        fresh_reader!(self.env, |r| self
+            .misc_meta
+            .iter_all_key_matches(&r, key)?
+            .map(|(_, v)| Ok(MiscMetaValue::warrant(v)))
+            .collect())
+    }
+
     // TODO: For now this is only checking for deletes
     // Once the validation is finished this should check for that as well
     fn get_dht_status<'r, R: Readable>(
@@ -163,3 +163,64 @@ async fn check_large_seq_queries() {
         &[(1, h2_hash), (256, h1_hash)]
     );
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn warrants_are_stored_per_agent_header_and_author() {
+    let (_te, mut meta_buf, mut h1, mut h2, agent_pubkey) = setup();
+    h1.header_seq = 1;
+    h2.header_seq = 1;
+    let fork = SignedHeader(h1.clone().into(), fixt!(Signature));
+    let other = SignedHeader(h2.into(), fixt!(Signature));
+    let authority = fixt!(AgentPubKey);
+    let warrant_by = |author: &AgentPubKey, proof| SignedWarrant {
+        warrant: Warrant {
+            proof,
+            author: author.clone(),
+            timestamp: Timestamp::now().into(),
+        },
+        signature: fixt!(Signature),
+    };
+    let warrant = |proof| warrant_by(&fixt!(AgentPubKey), proof);
+    let invalid = |header: SignedHeader| WarrantProof::InvalidChainOp {
+        header,
+        entry: None,
+        op_type: DhtOpType::RegisterAgentActivity,
+        validation_type: ValidationType::App,
+        reason: "bad".into(),
+    };
+
+    meta_buf
+        .register_warrant(warrant(WarrantProof::ChainFork(
+            fork.clone(),
+            other.clone(),
+        )))
+        .unwrap();
+    meta_buf.register_warrant(warrant(invalid(other))).unwrap();
+    // A warrant about the same header from another authority is kept too
+    meta_buf
+        .register_warrant(warrant(invalid(fork.clone())))
+        .unwrap();
+    // A second warrant about the same header from the same authority
+    // replaces its first
+    meta_buf
+        .register_warrant(warrant_by(&authority, invalid(fork.clone())))
+        .unwrap();
+    meta_buf
+        .register_warrant(warrant_by(&authority, invalid(fork.clone())))
+        .unwrap();
+    // Warrants about other agents are kept separate
+    let mut h3 = h1;
+    h3.author = fixt!(AgentPubKey);
+    meta_buf
+        .register_warrant(warrant(invalid(SignedHeader(h3.into(), fixt!(Signature)))))
+        .unwrap();
+
+    let warrants = meta_buf.get_warrants(&agent_pubkey).unwrap();
+    assert_eq!(warrants.len(), 4);
+    assert!(warrants
+        .iter()
+        .all(|w| *w.warrant().warranted_agent() == agent_pubkey));
+    assert!(warrants
+        .iter()
+        .any(|w| w.warrant().proof == invalid(fork.clone())));
+}
@@ -48,6 +48,12 @@ impl PrefixType for RejectedStoreElementPrefix {
     const PREFIX: u8 = 0x5;
 }
 
+#[derive(PartialOrd, Clone, Ord, PartialEq, Eq, Debug)]
+pub struct WarrantPrefix;
+impl PrefixType for WarrantPrefix {
+    const PREFIX: u8 = 0x6;
+}
+
 impl<P: PrefixType> MiscMetaKey<P> {
     /// Create a new prefix bytes key
     pub fn new<I: IntoIterator<Item = u8>>(bytes: I) -> Self {
@@ -77,6 +83,8 @@ pub enum MiscMetaValue {
     ChainStatus(ChainStatus),
     /// The highest observed header for an agents chain.
     ChainObserved(HighestObserved),
+    /// A warrant issued against an agent.
+    Warrant(SignedWarrant),
 }
 
 impl MiscMetaKey<EntryStatusPrefix> {
@@ -131,6 +139,39 @@ impl MiscMetaKey<ChainObservedPrefix> {
     }
 }
 
+impl MiscMetaKey<WarrantPrefix> {
+    /// Create a key for all the warrants against an agent
+    pub fn warrants(agent: &AgentPubKey) -> MiscMetaKey<WarrantPrefix> {
+        let bytes: SerializedBytes = agent.try_into().expect("Agent key can't fail to serialize");
+        MiscMetaKey::new(bytes.bytes().iter().copied())
+    }
+
+    /// Create a key for a warrant against an agent about a header,
+    /// issued by the authority `author`.
+    /// Each authority's warrant about a header is kept.
+    pub fn warrant(
+        agent: &AgentPubKey,
+        hash: &HeaderHash,
+        author: &AgentPubKey,
+    ) -> MiscMetaKey<WarrantPrefix> {
+        let agent: SerializedBytes = agent.try_into().expect("Agent key can't fail to serialize");
+        let hash: SerializedBytes = hash
+            .try_into()
+            .expect("Header Hash can't fail to serialize");
+        let author: SerializedBytes = author
+            .try_into()
+            .expect("Agent key can't fail to serialize");
+        MiscMetaKey::new(
+            agent
+                .bytes()
+                .iter()
+                .copied()
+                .chain(hash.bytes().iter().copied())
+                .chain(author.bytes().iter().copied()),
+        )
+    }
+}
+
 impl<PM, PB> From<MiscMetaKey<PM>> for PrefixBytesKey<PB>
 where
     PM: PrefixType,
@@ -183,6 +224,13 @@ impl MiscMetaValue {
         }
     }
 
+    pub fn warrant(self) -> SignedWarrant {
+        match self {
+            MiscMetaValue::Warrant(w) => w,
+            _ => unreachable!("Tried to go from {:?} to {:?}", self, "warrant"),
+        }
+    }
+
     pub fn new_store_element() -> Self {
         Self::StoreElement
     }
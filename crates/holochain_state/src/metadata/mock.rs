@@ -42,6 +42,7 @@ mock! {
             observed: HighestObserved,
         ) -> DatabaseResult<()>;
         fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()>;
+        fn register_warrant(&mut self, warrant: SignedWarrant) -> DatabaseResult<()>;
         fn register_update(&mut self, update: header::Update) -> DatabaseResult<()>;
         fn register_delete(&mut self, delete: header::Delete) -> DatabaseResult<()>;
         fn deregister_header(&mut self, new_entry_header: NewEntryHeader) -> DatabaseResult<()>;
@@ -101,6 +102,7 @@ mock! {
         fn get_activity_status(&self, agent: &AgentPubKey) -> DatabaseResult<Option<ChainStatus>>;
         fn get_activity_observed(&self, agent: &AgentPubKey)
         -> DatabaseResult<Option<HighestObserved>>;
+        fn get_warrants(&self, agent: &AgentPubKey) -> DatabaseResult<Vec<SignedWarrant>>;
         fn get_updates(
             &self,
             hash: AnyDhtHash,
@@ -230,6 +232,10 @@ impl MetadataBufT for MockMetadataBuf {
         self.get_activity_observed(agent)
     }
 
+    fn get_warrants(&self, agent: &AgentPubKey) -> DatabaseResult<Vec<SignedWarrant>> {
+        self.get_warrants(agent)
+    }
+
     fn get_updates<'r, R: Readable>(
         &'r self,
         _reader: &'r R,
@@ -353,6 +359,9 @@ impl MetadataBufT for MockMetadataBuf {
     fn deregister_activity_observed(&mut self, agent: &AgentPubKey) -> DatabaseResult<()> {
         self.deregister_activity_observed(agent)
     }
+    fn register_warrant(&mut self, warrant: SignedWarrant) -> DatabaseResult<()> {
+        self.register_warrant(warrant)
+    }
 
     fn register_update(&mut self, update: header::Update) -> DatabaseResult<()> {
         self.register_update(update)
@@ -0,0 +1,227 @@
+//! # Scheduled Function Database Types
+//!
+//! Zome functions scheduled via the `schedule` host function are persisted
+//! here, keyed by the time they are next due, so that they survive a conductor
+//! restart and can be found in chronological order.
+
+use fallible_iterator::FallibleIterator;
+use holochain_lmdb::buffer::KvBufFresh;
+use holochain_lmdb::db::SCHEDULED_FNS;
+use holochain_lmdb::error::DatabaseError;
+use holochain_lmdb::error::DatabaseResult;
+use holochain_lmdb::key::BufKey;
+use holochain_lmdb::prelude::BufferedStore;
+use holochain_lmdb::prelude::EnvironmentRead;
+use holochain_lmdb::prelude::GetDb;
+use holochain_lmdb::prelude::Readable;
+use holochain_lmdb::prelude::Writer;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+use holochain_types::timestamp::TimestampKey;
+use holochain_types::timestamp::TS_SIZE;
+
+/// Database key for a scheduled function.
+///
+/// The first bytes are the [TimestampKey] of when the function is due, so
+/// iterating the database visits functions in the order they are due.
+/// The remainder is a hash of the zome name, function name and payload, so
+/// that different calls due at the same instant don't collide.
+/// Scheduling exactly the same call twice for the same instant only
+/// schedules it once.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScheduledFnKey(Vec<u8>);
+
+impl ScheduledFnKey {
+    /// Create the key for a function that is due at the given time
+    pub fn new(due: Timestamp, scheduled_fn: &ScheduledFn) -> Self {
+        let mut bytes = TimestampKey::from(due).as_ref().to_vec();
+        let mut id = scheduled_fn.zome_name.0.as_bytes().to_vec();
+        id.push(0);
+        id.extend_from_slice(scheduled_fn.schedule.fn_name.0.as_bytes());
+        id.push(0);
+        id.extend_from_slice(scheduled_fn.schedule.payload.bytes());
+        bytes.extend(holo_hash::encode::blake2b_128(&id));
+        Self(bytes)
+    }
+
+    /// The time this function is due
+    pub fn due(&self) -> Timestamp {
+        TimestampKey::from(&self.0[..TS_SIZE]).into()
+    }
+}
+
+impl AsRef<[u8]> for ScheduledFnKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BufKey for ScheduledFnKey {
+    fn to_key_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() > TS_SIZE,
+            "Holochain detected database corruption.\n\nInvalid ScheduledFnKey: expected more than {} bytes but got {}",
+            TS_SIZE,
+            bytes.len()
+        );
+        Self(bytes.to_vec())
+    }
+}
+
+/// A zome function waiting to be called back by the conductor
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduledFn {
+    /// The zome that scheduled the function
+    pub zome_name: ZomeName,
+    /// What to call and when
+    pub schedule: Schedule,
+}
+
+impl ScheduledFn {
+    /// Constructor
+    pub fn new(zome_name: ZomeName, schedule: Schedule) -> Self {
+        Self {
+            zome_name,
+            schedule,
+        }
+    }
+}
+
+/// The database of functions scheduled to be called back
+pub struct ScheduledFnStore(KvBufFresh<ScheduledFnKey, ScheduledFn>);
+
+impl ScheduledFnStore {
+    /// Create a new ScheduledFnStore
+    pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
+        let db = env.get_db(&*SCHEDULED_FNS)?;
+        Ok(Self(KvBufFresh::new(env, db)))
+    }
+
+    /// Schedule a function to be called at the given time
+    pub fn schedule(&mut self, due: Timestamp, scheduled_fn: ScheduledFn) -> DatabaseResult<()> {
+        self.0
+            .put(ScheduledFnKey::new(due, &scheduled_fn), scheduled_fn)
+    }
+
+    /// Remove a function from the schedule
+    pub fn unschedule(&mut self, key: ScheduledFnKey) -> DatabaseResult<()> {
+        self.0.delete(key)
+    }
+
+    /// All the functions that are due at or before `now`, in the order
+    /// they became due
+    pub fn due<R: Readable>(
+        &self,
+        r: &R,
+        now: Timestamp,
+    ) -> DatabaseResult<Vec<(ScheduledFnKey, ScheduledFn)>> {
+        self.0
+            .iter(r)?
+            .map(|(k, v)| Ok((ScheduledFnKey::from_key_bytes_or_friendly_panic(k), v)))
+            .take_while(|(k, _)| Ok(k.due() <= now))
+            .collect()
+    }
+
+    /// The time the next function is due, if any are scheduled
+    pub fn next_due<R: Readable>(&self, r: &R) -> DatabaseResult<Option<Timestamp>> {
+        Ok(self
+            .0
+            .iter(r)?
+            .next()?
+            .map(|(k, _)| ScheduledFnKey::from_key_bytes_or_friendly_panic(k).due()))
+    }
+
+    /// Access the environment backing this store
+    pub fn env(&self) -> &EnvironmentRead {
+        self.0.env()
+    }
+}
+
+impl BufferedStore for ScheduledFnStore {
+    type Error = DatabaseError;
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.0.flush_to_txn_ref(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_lmdb::env::ReadManager;
+    use holochain_lmdb::env::WriteManager;
+    use holochain_serialized_bytes::UnsafeBytes;
+
+    fn scheduled_fn(fn_name: &str, secs: u64) -> ScheduledFn {
+        ScheduledFn::new(
+            "foo".into(),
+            Schedule::once(
+                fn_name.into(),
+                SerializedBytes::try_from(()).unwrap(),
+                std::time::Duration::from_secs(secs),
+            ),
+        )
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn scheduled_fns_are_due_in_order() -> DatabaseResult<()> {
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        let mut store = ScheduledFnStore::new(env.clone().into())?;
+        store.schedule(Timestamp(30, 0), scheduled_fn("c", 30))?;
+        store.schedule(Timestamp(10, 0), scheduled_fn("a", 10))?;
+        store.schedule(Timestamp(20, 0), scheduled_fn("b", 20))?;
+        env_ref.with_commit(|writer| store.flush_to_txn(writer))?;
+
+        let store = ScheduledFnStore::new(env.clone().into())?;
+        env_ref.with_reader(|r| {
+            assert_eq!(store.next_due(&r)?, Some(Timestamp(10, 0)));
+            let due: Vec<_> = store
+                .due(&r, Timestamp(20, 0))?
+                .into_iter()
+                .map(|(_, f)| f.schedule.fn_name.0)
+                .collect();
+            assert_eq!(due, vec!["a".to_string(), "b".to_string()]);
+            DatabaseResult::Ok(())
+        })?;
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn same_fn_at_same_time_is_kept_per_payload() -> DatabaseResult<()> {
+        let test_env = holochain_lmdb::test_utils::test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        let with_payload = |n: u8| {
+            let mut scheduled_fn = scheduled_fn("a", 10);
+            scheduled_fn.schedule.payload = SerializedBytes::from(UnsafeBytes::from(vec![n]));
+            scheduled_fn
+        };
+        let mut store = ScheduledFnStore::new(env.clone().into())?;
+        store.schedule(Timestamp(10, 0), with_payload(1))?;
+        store.schedule(Timestamp(10, 0), with_payload(2))?;
+        // the same call again is only scheduled once
+        store.schedule(Timestamp(10, 0), with_payload(2))?;
+        env_ref.with_commit(|writer| store.flush_to_txn(writer))?;
+
+        let store = ScheduledFnStore::new(env.clone().into())?;
+        env_ref.with_reader(|r| {
+            let mut due: Vec<_> = store
+                .due(&r, Timestamp(10, 0))?
+                .into_iter()
+                .map(|(_, f)| f.schedule.payload.bytes()[0])
+                .collect();
+            due.sort_unstable();
+            assert_eq!(due, vec![1, 2]);
+            DatabaseResult::Ok(())
+        })?;
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@ use holochain_lmdb::prelude::*;
 use holochain_types::prelude::*;
 use shrinkwraprs::Shrinkwrap;
 pub use source_chain_buffer::*;
+use std::collections::HashMap;
 use std::collections::HashSet;
 
 mod error;
@@ -55,6 +56,9 @@ impl SourceChain {
         header_builder: B,
         maybe_entry: Option<Entry>,
     ) -> SourceChainResult<HeaderHash> {
+        if self.is_closed()? {
+            return Err(SourceChainError::ChainClosed);
+        }
         let common = HeaderBuilderCommon {
             author: self.agent_pubkey()?,
             timestamp: Timestamp::now().into(),
@@ -108,67 +112,7 @@ impl SourceChain {
         // chain to see if there is a local grant that is valid for the provided secret/agent
         // combination
         let committed_valid_grant = fresh_reader!(self.env(), |r| {
-            let (references, headers): (
-                HashSet<HeaderHash>,
-                Vec<HoloHashed<holochain_zome_types::element::SignedHeader>>,
-            ) = self
-                .0
-                .headers()
-                .iter_fail(&r)?
-                .filter(|header| {
-                    Ok(match header.as_content().header() {
-                        Header::Create(create) => {
-                            // filter out authorship and everything else
-                            matches!(create.entry_type, EntryType::CapGrant)
-                        }
-                        Header::Update(update) => matches!(
-                            update.entry_type,
-                            // filter out authorship and everything else
-                            EntryType::CapGrant,
-                        ),
-                        Header::Delete(_) => true,
-                        // no other headers are relevant
-                        _ => false,
-                    })
-                })
-                // extract all the header references
-                // if a header is referenced by an update/delete then it is no longer valid
-                // with all the references in a bucket we can use it to filter out entries below
-                .fold(
-                    (HashSet::new(), vec![]),
-                    |(mut references, mut headers), header| {
-                        match header.as_content().header() {
-                            Header::Update(update) => {
-                                references.insert(update.original_header_address.clone());
-                            }
-                            Header::Delete(delete) => {
-                                references.insert(delete.deletes_address.clone());
-                            }
-                            _ => {}
-                        }
-                        // this is a best-effort attempt to avoid putting things we already know as
-                        // referenced into the returned vec
-                        // it isn't comprehensive because it relies on ordering but it's an easy
-                        // and relatively safe optimisation to not do further processing here
-                        if !references.contains(header.as_hash()) {
-                            headers.push(header);
-                        }
-
-                        Ok((references, headers))
-                    },
-                )?;
-
-            // second pass over the headers to make sure that all referenced headers are removed
-            // this makes the process reliable even if the iterators don't follow the chain order
-            let live_cap_grants: HashSet<_> = headers
-                .iter()
-                .filter(|header| !references.contains(header.as_hash()))
-                .filter_map(|header| match header.as_content().header() {
-                    Header::Create(create) => Some(create.entry_hash.clone()),
-                    Header::Update(update) => Some(update.entry_hash.clone()),
-                    _ => None,
-                })
-                .collect();
+            let live_cap_grants = self.live_entry_headers(&r, &EntryType::CapGrant)?;
 
             self
             .0
@@ -180,7 +124,7 @@ impl SourceChain {
             .iter_fail(&r)?
             // ensure we respect the header filtering we already did above
             .filter(|entry| {
-                Ok(live_cap_grants.contains(entry.as_hash()))
+                Ok(live_cap_grants.contains_key(entry.as_hash()))
             })
             .filter_map(|entry| Ok(entry.as_cap_grant()))
             // filter down to only the grants for this function
@@ -236,6 +180,135 @@ impl SourceChain {
         Ok(committed_valid_grant)
     }
 
+    /// List the grants on the chain which haven't been updated or deleted,
+    /// filtered by the query's tag.
+    ///
+    /// NB: [B-01676] the entries must be persisted to be listed.
+    pub fn live_cap_grants(&self, query: &CapQuery) -> SourceChainResult<Vec<LiveCapGrant>> {
+        fresh_reader!(self.env(), |r| {
+            let live_cap_grants = self.live_entry_headers(&r, &EntryType::CapGrant)?;
+            Ok(self
+                .0
+                .elements()
+                .private_entries()
+                .expect(
+                    "SourceChainBuf must have access to private entries in order to access CapGrants",
+                )
+                .iter_fail(&r)?
+                .filter_map(|entry| {
+                    Ok(match (live_cap_grants.get(entry.as_hash()), entry.as_content()) {
+                        (Some(header_hash), Entry::CapGrant(grant)) if query.matches(&grant.tag) => {
+                            Some(LiveCapGrant {
+                                header_hash: header_hash.clone(),
+                                grant: grant.clone(),
+                            })
+                        }
+                        _ => None,
+                    })
+                })
+                .collect::<Vec<_>>()?)
+        })
+    }
+
+    /// List the claims on the chain which haven't been deleted,
+    /// filtered by the query's tag.
+    ///
+    /// NB: [B-01676] the entries must be persisted to be listed.
+    pub fn live_cap_claims(&self, query: &CapQuery) -> SourceChainResult<Vec<LiveCapClaim>> {
+        fresh_reader!(self.env(), |r| {
+            let live_cap_claims = self.live_entry_headers(&r, &EntryType::CapClaim)?;
+            Ok(self
+                .0
+                .elements()
+                .private_entries()
+                .expect(
+                    "SourceChainBuf must have access to private entries in order to access CapClaims",
+                )
+                .iter_fail(&r)?
+                .filter_map(|entry| {
+                    Ok(match (live_cap_claims.get(entry.as_hash()), entry.as_content()) {
+                        (Some(header_hash), Entry::CapClaim(claim)) if query.matches(claim.tag()) => {
+                            Some(LiveCapClaim {
+                                header_hash: header_hash.clone(),
+                                claim: claim.clone(),
+                            })
+                        }
+                        _ => None,
+                    })
+                })
+                .collect::<Vec<_>>()?)
+        })
+    }
+
+    /// Map the entry hash of every live entry of the given type to the header
+    /// that created it. Entries whose header has been updated or deleted are
+    /// not live.
+    fn live_entry_headers<R: Readable>(
+        &self,
+        r: &R,
+        entry_type: &EntryType,
+    ) -> SourceChainResult<HashMap<EntryHash, HeaderHash>> {
+        let (references, headers): (
+            HashSet<HeaderHash>,
+            Vec<HoloHashed<holochain_zome_types::element::SignedHeader>>,
+        ) = self
+            .0
+            .headers()
+            .iter_fail(r)?
+            .filter(|header| {
+                Ok(match header.as_content().header() {
+                    // filter out authorship and everything else
+                    Header::Create(create) => &create.entry_type == entry_type,
+                    Header::Update(update) => &update.entry_type == entry_type,
+                    Header::Delete(_) => true,
+                    // no other headers are relevant
+                    _ => false,
+                })
+            })
+            // extract all the header references
+            // if a header is referenced by an update/delete then it is no longer valid
+            // with all the references in a bucket we can use it to filter out entries below
+            .fold(
+                (HashSet::new(), vec![]),
+                |(mut references, mut headers), header| {
+                    match header.as_content().header() {
+                        Header::Update(update) => {
+                            references.insert(update.original_header_address.clone());
+                        }
+                        Header::Delete(delete) => {
+                            references.insert(delete.deletes_address.clone());
+                        }
+                        _ => {}
+                    }
+                    // this is a best-effort attempt to avoid putting things we already know as
+                    // referenced into the returned vec
+                    // it isn't comprehensive because it relies on ordering but it's an easy
+                    // and relatively safe optimisation to not do further processing here
+                    if !references.contains(header.as_hash()) {
+                        headers.push(header);
+                    }
+
+                    Ok((references, headers))
+                },
+            )?;
+
+        // second pass over the headers to make sure that all referenced headers are removed
+        // this makes the process reliable even if the iterators don't follow the chain order
+        Ok(headers
+            .iter()
+            .filter(|header| !references.contains(header.as_hash()))
+            .filter_map(|header| match header.as_content().header() {
+                Header::Create(create) => {
+                    Some((create.entry_hash.clone(), header.as_hash().clone()))
+                }
+                Header::Update(update) => {
+                    Some((update.entry_hash.clone(), header.as_hash().clone()))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
     // @todo bring all this back when we want to administer cap claims better
     //         /// Fetch a CapClaim from the private entries.
     //         ///
@@ -285,18 +358,48 @@ impl SourceChain {
     /// Query Headers in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
+    ///
+    /// Only the chain sequence positions inside the query's range are visited,
+    /// and the walk stops as soon as the limit is reached. Elements are
+    /// returned oldest first unless the query is descending.
     pub fn query(&self, query: &ChainQueryFilter) -> SourceChainResult<Vec<Element>> {
-        let include_entries = query.include_entries;
-        self.iter_back()
-            .filter(|shh| Ok(query.check(shh.header())))
-            .map(|shh| {
-                let entry = match shh.header().entry_hash() {
-                    Some(eh) if include_entries => self.0.get_entry(eh)?,
-                    _ => None,
-                };
-                Ok(Element::new(shh, entry.map(|e| e.into_content())))
-            })
-            .collect()
+        let len = self.0.len() as u32;
+        let range = query
+            .sequence_range
+            .as_ref()
+            .map(|r| r.clamp(len))
+            .unwrap_or(0..len);
+        let limit = query.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+        let indices: Box<dyn Iterator<Item = u32>> = if query.descending {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+
+        let mut elements = Vec::new();
+        for i in indices {
+            if elements.len() >= limit {
+                break;
+            }
+            let header_hash = self
+                .0
+                .sequence()
+                .get(i)?
+                .ok_or_else(|| SourceChainError::ElementMissing(format!("at index {}", i)))?;
+            let shh = self
+                .0
+                .get_header(&header_hash)?
+                .ok_or_else(|| SourceChainError::ElementMissing(header_hash.to_string()))?;
+            if !query.check(shh.header()) {
+                continue;
+            }
+            let entry = match shh.header().entry_hash() {
+                Some(eh) if query.include_entries => self.0.get_entry(eh)?,
+                _ => None,
+            };
+            elements.push(Element::new(shh, entry.map(|e| e.into_content())));
+        }
+        Ok(elements)
     }
 }
 
@@ -395,6 +498,17 @@ pub mod tests {
                 chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
                 Some(grant.clone().into())
             );
+
+            assert_eq!(
+                chain.live_cap_grants(&CapQuery::all())?,
+                vec![LiveCapGrant {
+                    header_hash: original_header_address.clone(),
+                    grant: grant.clone(),
+                }]
+            );
+            assert!(chain
+                .live_cap_grants(&CapQuery::tagged("other".into()))?
+                .is_empty());
         }
 
         // let's roll the secret and assign the grant to bob specifically
@@ -442,7 +556,16 @@ pub mod tests {
             );
             assert_eq!(
                 chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
-                Some(updated_grant.into())
+                Some(updated_grant.clone().into())
+            );
+
+            // only the update is live
+            assert_eq!(
+                chain.live_cap_grants(&CapQuery::tagged("tag".into()))?,
+                vec![LiveCapGrant {
+                    header_hash: updated_header_hash.clone(),
+                    grant: updated_grant,
+                }]
             );
         }
 
@@ -479,7 +602,113 @@ pub mod tests {
                 chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
                 None
             );
+
+            assert!(chain.live_cap_grants(&CapQuery::all())?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_query() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice.clone(), None).await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let mut chain = SourceChain::new(env.clone().into())?;
+        for tag in &["a", "b"] {
+            let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+            chain
+                .put_cap_claim(CapClaim::new(tag.to_string(), bob.clone(), secret))
+                .await?;
         }
+        let seqs = |query: ChainQueryFilter| -> SourceChainResult<Vec<u32>> {
+            Ok(chain
+                .query(&query)?
+                .iter()
+                .map(|el| el.header().header_seq())
+                .collect())
+        };
+
+        assert_eq!(seqs(ChainQueryFilter::new())?, vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            seqs(ChainQueryFilter::new().sequence_range(1..=3))?,
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            seqs(
+                ChainQueryFilter::new()
+                    .sequence_range(2..)
+                    .descending(true)
+                    .limit(2)
+            )?,
+            vec![4, 3]
+        );
+        assert_eq!(
+            seqs(
+                ChainQueryFilter::new()
+                    .entry_type(EntryType::AgentPubKey)
+                    .entry_type(EntryType::CapClaim)
+            )?,
+            vec![2, 3, 4]
+        );
+        assert_eq!(
+            seqs(ChainQueryFilter::new().header_types(vec![HeaderType::Dna, HeaderType::Create]))?,
+            vec![0, 2, 3, 4]
+        );
+        assert!(seqs(ChainQueryFilter::new().sequence_range(10..))?.is_empty());
+
+        let elements = chain.query(
+            &ChainQueryFilter::new()
+                .entry_hashes(vec![alice.clone().into()])
+                .include_entries(true),
+        )?;
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].entry().as_option(), Some(&Entry::Agent(alice)));
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_live_cap_claims() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let claim = CapClaim::new("tag".into(), bob, secret);
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice, None).await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let header_hash = {
+            let mut chain = SourceChain::new(env.clone().into())?;
+            let header_hash = chain.put_cap_claim(claim.clone()).await?;
+            env.guard()
+                .with_commit(|writer| chain.flush_to_txn(writer))?;
+            header_hash
+        };
+
+        let chain = SourceChain::new(env.clone().into())?;
+        assert_eq!(
+            chain.live_cap_claims(&CapQuery::tagged("tag".into()))?,
+            vec![LiveCapClaim { header_hash, claim }]
+        );
+        assert!(chain
+            .live_cap_claims(&CapQuery::tagged("other".into()))?
+            .is_empty());
 
         Ok(())
     }
@@ -21,6 +21,9 @@ pub enum SourceChainError {
     )]
     InvalidStructure(ChainInvalidReason),
 
+    #[error("The source chain has been closed by a CloseChain header so nothing more can be committed to it")]
+    ChainClosed,
+
     #[error("The source chain's head is pointing to an address which has no content.")]
     MissingHead,
 
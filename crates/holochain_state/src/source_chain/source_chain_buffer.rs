@@ -151,6 +151,18 @@ impl SourceChainBuf {
         self.len() > 3
     }
 
+    /// Has the chain been closed by a `CloseChain` header?
+    /// Nothing can follow a `CloseChain`.
+    pub fn is_closed(&self) -> SourceChainResult<bool> {
+        Ok(match self.chain_head() {
+            Some(head) => self
+                .get_header(head)?
+                .map(|header| matches!(header.header(), Header::CloseChain(_)))
+                .unwrap_or(false),
+            None => false,
+        })
+    }
+
     /// Get the AgentPubKey from the entry committed to the chain.
     /// If this returns None, the chain was not initialized.
     pub fn agent_pubkey(&self) -> SourceChainResult<Option<AgentPubKey>> {
@@ -37,6 +37,8 @@ pub struct ValidationLimboValue {
     pub num_tries: u32,
     /// The agent that sent you this op
     pub from_agent: Option<AgentPubKey>,
+    /// Whether the author asked for a validation receipt
+    pub send_receipt: bool,
 }
 
 /// The status of a [DhtOp] in limbo
@@ -20,6 +20,8 @@ pub struct AgentActivityResponse<T = SignedHeaderHashed> {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
+    /// Warrants issued against this agent.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -56,7 +58,7 @@ impl From<AgentActivityResponse<Element>> for holochain_zome_types::query::Agent
             rejected_activity,
             status: a.status,
             highest_observed: a.highest_observed,
-            warrants: Vec::with_capacity(0),
+            warrants: a.warrants,
         }
     }
 }
@@ -1,9 +1,14 @@
 //! Holochain autonomic type helpers.
 
+use crate::Timestamp;
+use holo_hash::DhtOpHash;
+
 /// The various processes which run "autonomically", aka subconsciously.
-/// These are currently not used.
 pub enum AutonomicProcess {
     /// Validation / Correction may propagate much slower.
+    /// Runs a health check and also republishes the ops which are
+    /// already replicated, so that authorities which joined since
+    /// they were published come to hold them too.
     SlowHeal,
 
     /// See how many validators we can find on the network for all of our entries
@@ -11,12 +16,36 @@ pub enum AutonomicProcess {
     HealthCheck,
 }
 
+/// The replication of the ops a cell has authored, as found by the last
+/// [AutonomicProcess::HealthCheck] or [AutonomicProcess::SlowHeal].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReplicationHealth {
+    /// When the check ran.
+    pub checked_at: Timestamp,
+    /// How many valid receipts an op needs to count as replicated.
+    pub target: u32,
+    /// The number of ops authored by the cell which are published.
+    pub authored_ops: usize,
+    /// The number of authored ops with at least `target` valid receipts.
+    pub replicated_ops: usize,
+    /// The ops which fell short of `target`, with the receipts they have.
+    /// These were republished by the check.
+    pub under_replicated: Vec<(DhtOpHash, u32)>,
+}
+
+impl ReplicationHealth {
+    /// True if every authored op has reached its replication target.
+    pub fn is_healthy(&self) -> bool {
+        self.under_replicated.is_empty()
+    }
+}
+
 /// A cue that the autonomic system should perform one of its functions now,
 /// rather than at the next scheduled time
 pub enum AutonomicCue {
     // /// Cue sent when it is known that entries are ready for initial publishing,
-// /// i.e. after committing new entries to your source chain
-// Publish(Address),
+    // /// i.e. after committing new entries to your source chain
+    // Publish(Address),
 }
 
 impl From<AutonomicCue> for AutonomicProcess {
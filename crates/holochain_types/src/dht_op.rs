@@ -251,6 +251,54 @@ impl DhtOp {
             DhtOp::RegisterRemoveLink(_, h) => h.clone().into(),
         }
     }
+
+    /// The kind of this op
+    pub fn op_type(&self) -> DhtOpType {
+        match self {
+            DhtOp::StoreElement(_, _, _) => DhtOpType::StoreElement,
+            DhtOp::StoreEntry(_, _, _) => DhtOpType::StoreEntry,
+            DhtOp::RegisterAgentActivity(_, _) => DhtOpType::RegisterAgentActivity,
+            DhtOp::RegisterUpdatedContent(_, _, _) => DhtOpType::RegisterUpdatedContent,
+            DhtOp::RegisterUpdatedElement(_, _, _) => DhtOpType::RegisterUpdatedElement,
+            DhtOp::RegisterDeletedBy(_, _) => DhtOpType::RegisterDeletedBy,
+            DhtOp::RegisterDeletedEntryHeader(_, _) => DhtOpType::RegisterDeletedEntryHeader,
+            DhtOp::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
+            DhtOp::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+        }
+    }
+
+    /// Build the op of this kind for a header and, if the op carries one, its entry.
+    /// This is the inverse of [DhtOp::into_inner] for ops of the given kind.
+    pub fn from_type(
+        op_type: DhtOpType,
+        signature: Signature,
+        header: Header,
+        entry: Option<Entry>,
+    ) -> DhtOpResult<Self> {
+        let op = match op_type {
+            DhtOpType::StoreElement => DhtOp::StoreElement(signature, header, entry.map(Box::new)),
+            DhtOpType::StoreEntry => {
+                let entry = entry.ok_or_else(|| DhtOpError::HeaderWithoutEntry(header.clone()))?;
+                DhtOp::StoreEntry(signature, header.try_into()?, Box::new(entry))
+            }
+            DhtOpType::RegisterAgentActivity => DhtOp::RegisterAgentActivity(signature, header),
+            DhtOpType::RegisterUpdatedContent => {
+                DhtOp::RegisterUpdatedContent(signature, header.try_into()?, entry.map(Box::new))
+            }
+            DhtOpType::RegisterUpdatedElement => {
+                DhtOp::RegisterUpdatedElement(signature, header.try_into()?, entry.map(Box::new))
+            }
+            DhtOpType::RegisterDeletedBy => DhtOp::RegisterDeletedBy(signature, header.try_into()?),
+            DhtOpType::RegisterDeletedEntryHeader => {
+                DhtOp::RegisterDeletedEntryHeader(signature, header.try_into()?)
+            }
+            DhtOpType::RegisterAddLink => DhtOp::RegisterAddLink(signature, header.try_into()?),
+            DhtOpType::RegisterRemoveLink => {
+                DhtOp::RegisterRemoveLink(signature, header.try_into()?)
+            }
+        };
+        Ok(op)
+    }
 }
 
 impl DhtOpLight {
@@ -26,6 +26,7 @@ pub mod prelude;
 pub mod signal;
 pub mod timestamp;
 pub mod validate;
+pub mod warrant;
 
 // #[cfg(test)]
 pub mod test_utils;
@@ -31,6 +31,7 @@ pub use crate::metadata::*;
 pub use crate::signal::*;
 pub use crate::timestamp::*;
 pub use crate::validate::*;
+pub use crate::warrant::*;
 
 pub use crate::fixt::TimestampFixturator;
 #[cfg(feature = "fixturators")]
@@ -26,6 +26,18 @@ impl Timestamp {
     pub fn now() -> Self {
         chrono::offset::Utc::now().into()
     }
+
+    /// The Timestamp after some duration has elapsed from this one,
+    /// saturating at the latest representable time.
+    pub fn saturating_add(&self, d: &std::time::Duration) -> Self {
+        let secs = i64::try_from(d.as_secs()).unwrap_or(i64::MAX);
+        let nsec = self.1 as u64 + d.subsec_nanos() as u64;
+        let sec = self
+            .0
+            .saturating_add(secs)
+            .saturating_add((nsec / 1_000_000_000) as i64);
+        Timestamp(sec, (nsec % 1_000_000_000) as u32)
+    }
 }
 
 impl std::fmt::Display for Timestamp {
@@ -239,4 +251,17 @@ mod tests {
         assert!(k5 < k6);
         assert!(k6 < k7);
     }
+
+    #[test]
+    fn test_timestamp_saturating_add() {
+        let d = std::time::Duration::from_millis(1_500);
+        assert_eq!(
+            Timestamp(10, 600_000_000).saturating_add(&d),
+            Timestamp(12, 100_000_000)
+        );
+        assert_eq!(
+            Timestamp(i64::MAX, 0).saturating_add(&d),
+            Timestamp(i64::MAX, 500_000_000)
+        );
+    }
 }
@@ -0,0 +1,107 @@
+//! Signing and checking warrants
+
+use crate::prelude::*;
+use holochain_keystore::KeystoreError;
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum WarrantError {
+    #[error(transparent)]
+    KeystoreError(#[from] KeystoreError),
+    #[error("The headers in this fork warrant do not show a fork: {0}")]
+    NotAFork(String),
+    #[error("An invalid op warrant can't be accepted without validating the header it is about")]
+    Unproven,
+}
+
+#[allow(missing_docs)]
+pub type WarrantResult<T> = Result<T, WarrantError>;
+
+/// Extension trait to keep zome types minimal
+#[async_trait::async_trait]
+pub trait SignedWarrantExt {
+    /// Sign a warrant with the key of the authority that issued it
+    #[allow(clippy::new_ret_no_self)]
+    async fn new(
+        keystore: &KeystoreSender,
+        warrant: Warrant,
+    ) -> Result<SignedWarrant, KeystoreError>;
+    /// Check the warrant is signed by its author and that the proof holds up.
+    ///
+    /// The reason given for an invalid op can't be checked without running
+    /// validation again, so invalid op warrants always return
+    /// [WarrantError::Unproven].
+    async fn verify(&self) -> WarrantResult<()>;
+    /// Check the signatures of the warrant and the headers it carries,
+    /// and that a fork proof shows a fork.
+    /// This does not check an invalid op is actually invalid.
+    async fn verify_signatures(&self) -> WarrantResult<()>;
+}
+
+#[async_trait::async_trait]
+impl SignedWarrantExt for SignedWarrant {
+    async fn new(keystore: &KeystoreSender, warrant: Warrant) -> Result<Self, KeystoreError> {
+        let signature = warrant.author.sign(keystore, &warrant).await?;
+        Ok(Self { warrant, signature })
+    }
+
+    async fn verify(&self) -> WarrantResult<()> {
+        self.verify_signatures().await?;
+        match &self.warrant().proof {
+            WarrantProof::ChainFork(_, _) => Ok(()),
+            WarrantProof::InvalidChainOp { .. } => Err(WarrantError::Unproven),
+        }
+    }
+
+    async fn verify_signatures(&self) -> WarrantResult<()> {
+        let warrant = self.warrant();
+        if !warrant
+            .author
+            .verify_signature(self.signature(), warrant)
+            .await?
+        {
+            return Err(KeystoreError::InvalidSignature(
+                self.signature().clone(),
+                format!("warrant by {}", warrant.author),
+            )
+            .into());
+        }
+        match &warrant.proof {
+            WarrantProof::ChainFork(first, second) => {
+                let (a, b) = (first.header(), second.header());
+                if a.author() != b.author() || a.header_seq() != b.header_seq() || a == b {
+                    return Err(WarrantError::NotAFork(format!(
+                        "{} at {} and {} at {}",
+                        a.author(),
+                        a.header_seq(),
+                        b.author(),
+                        b.header_seq()
+                    )));
+                }
+                verify_header_signature(first).await?;
+                verify_header_signature(second).await?;
+            }
+            WarrantProof::InvalidChainOp { header, .. } => {
+                verify_header_signature(header).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn verify_header_signature(signed_header: &SignedHeader) -> WarrantResult<()> {
+    let header = signed_header.header();
+    if !header
+        .author()
+        .verify_signature(signed_header.signature(), header)
+        .await?
+    {
+        return Err(KeystoreError::InvalidSignature(
+            signed_header.signature().clone(),
+            format!("header {:?}", HeaderHash::with_data_sync(header)),
+        )
+        .into());
+    }
+    Ok(())
+}
@@ -105,6 +105,10 @@ use util::*;
 mod websocket_config;
 pub use websocket_config::*;
 
+// checks made on incoming handshakes
+mod websocket_auth;
+pub use websocket_auth::*;
+
 // handles dispatching messages between the sender/receiver/sink/stream
 pub(crate) mod task_dispatch_incoming;
 
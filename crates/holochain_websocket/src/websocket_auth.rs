@@ -0,0 +1,230 @@
+//! defines the checks a listener makes during the websocket handshake
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::RwLock;
+use tungstenite::handshake::server::Request;
+
+/// The query parameter a bearer token can be passed in, for clients
+/// such as browsers which cannot set headers on a websocket handshake.
+pub const ACCESS_TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// The hash of a bearer token.
+/// Only hashes are kept, so tokens can't be read back out of memory or storage.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TokenHash(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl TokenHash {
+    /// Hash a bearer token.
+    pub fn new(token: &str) -> Self {
+        Self(blake2b_simd::blake2b(token.as_bytes()).as_bytes().to_vec())
+    }
+
+    /// Compare without returning early, so the time taken
+    /// doesn't leak how much of the hash matched.
+    fn constant_time_eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(other.0.iter())
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// A shared, mutable set of bearer tokens, held as [TokenHash]es.
+/// This struct is cheaply clone-able, and clones share the same tokens.
+#[derive(Clone, Debug, Default)]
+pub struct WebsocketTokens(Arc<RwLock<HashSet<TokenHash>>>);
+
+impl WebsocketTokens {
+    /// Construct a token set holding these tokens.
+    pub fn new<I: IntoIterator<Item = String>>(tokens: I) -> Self {
+        Self::from_hashes(tokens.into_iter().map(|t| TokenHash::new(&t)))
+    }
+
+    /// Construct a token set holding the tokens with these hashes.
+    pub fn from_hashes<I: IntoIterator<Item = TokenHash>>(hashes: I) -> Self {
+        Self(Arc::new(RwLock::new(hashes.into_iter().collect())))
+    }
+
+    /// Generate a new random token and add it to the set.
+    /// This is the only time the token itself is available.
+    pub fn mint(&self) -> String {
+        let token = nanoid::nanoid!(32);
+        self.insert(TokenHash::new(&token));
+        token
+    }
+
+    /// Add a token to the set by its hash.
+    pub fn insert(&self, hash: TokenHash) {
+        self.0.write().unwrap().insert(hash);
+    }
+
+    /// Remove a token from the set. Returns false if it was not in the set.
+    /// Connections already made with the token are not closed.
+    pub fn revoke(&self, token: &str) -> bool {
+        self.0.write().unwrap().remove(&TokenHash::new(token))
+    }
+
+    /// Is this token in the set?
+    /// Every hash in the set is compared, so the time taken doesn't
+    /// depend on which token matched.
+    pub fn contains(&self, token: &str) -> bool {
+        let hash = TokenHash::new(token);
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .fold(false, |found, h| h.constant_time_eq(&hash) | found)
+    }
+
+    /// The hashes of all the tokens in the set.
+    pub fn hashes(&self) -> Vec<TokenHash> {
+        self.0.read().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Checks a listener makes on each incoming handshake.
+/// The default accepts every connection.
+#[derive(Clone, Debug, Default)]
+pub struct WebsocketAuth {
+    /// If set, handshakes with an `Origin` header must have one of these origins.
+    /// Only browsers send this header, so this is what stops web pages
+    /// from connecting to a local port.
+    allowed_origins: Option<Arc<HashSet<String>>>,
+
+    /// If not empty, handshakes must present a bearer token from one of these sets.
+    token_sets: Vec<WebsocketTokens>,
+}
+
+impl WebsocketAuth {
+    /// Builder-style setter.
+    /// Only accept handshakes from browsers on these origins.
+    pub fn allowed_origins<I: IntoIterator<Item = String>>(mut self, origins: I) -> Self {
+        self.allowed_origins = Some(Arc::new(origins.into_iter().collect()));
+        self
+    }
+
+    /// Builder-style setter.
+    /// Require handshakes to present a token from this set, or from any
+    /// other set added this way. Tokens can be added to or revoked from
+    /// the set after the listener is bound.
+    pub fn require_token(mut self, tokens: WebsocketTokens) -> Self {
+        self.token_sets.push(tokens);
+        self
+    }
+
+    /// Check the handshake request, returning why it was refused if it was.
+    pub fn check(&self, request: &Request) -> std::result::Result<(), String> {
+        if let Some(allowed_origins) = &self.allowed_origins {
+            if let Some(origin) = request.headers().get("origin") {
+                let origin = origin.to_str().unwrap_or_default();
+                if !allowed_origins.contains(origin) {
+                    return Err(format!("Origin {} is not allowed", origin));
+                }
+            }
+        }
+        if !self.token_sets.is_empty() {
+            match bearer_token(request) {
+                Some(token) if self.token_sets.iter().any(|set| set.contains(&token)) => (),
+                Some(_) => return Err("Invalid bearer token".to_string()),
+                None => return Err("Missing bearer token".to_string()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the bearer token in the `Authorization` header,
+/// or failing that in the access token query parameter.
+fn bearer_token(request: &Request) -> Option<String> {
+    let header = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.starts_with("Bearer "))
+        .map(|value| value["Bearer ".len()..].trim().to_string());
+    header.or_else(|| {
+        request.uri().query().and_then(|query| {
+            query.split('&').find_map(|pair| {
+                let mut pair = pair.splitn(2, '=');
+                match (pair.next(), pair.next()) {
+                    (Some(ACCESS_TOKEN_QUERY_PARAM), Some(token)) => Some(token.to_string()),
+                    _ => None,
+                }
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(origin: Option<&str>, auth: Option<&str>, uri: &str) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(origin) = origin {
+            builder = builder.header("Origin", origin);
+        }
+        if let Some(auth) = auth {
+            builder = builder.header("Authorization", auth);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn default_accepts_everything() {
+        let auth = WebsocketAuth::default();
+        assert!(auth
+            .check(&request(Some("http://evil.com"), None, "/"))
+            .is_ok());
+    }
+
+    #[test]
+    fn checks_origin_when_present() {
+        let auth = WebsocketAuth::default().allowed_origins(vec!["http://localhost:8888".into()]);
+        assert!(auth.check(&request(None, None, "/")).is_ok());
+        assert!(auth
+            .check(&request(Some("http://localhost:8888"), None, "/"))
+            .is_ok());
+        assert!(auth
+            .check(&request(Some("http://evil.com"), None, "/"))
+            .is_err());
+    }
+
+    #[test]
+    fn checks_minted_and_revoked_tokens() {
+        let tokens = WebsocketTokens::default();
+        let auth = WebsocketAuth::default().require_token(tokens.clone());
+        let token = tokens.mint();
+
+        let header = format!("Bearer {}", token);
+        let query = format!("/?{}={}", ACCESS_TOKEN_QUERY_PARAM, token);
+        assert!(auth.check(&request(None, Some(&header), "/")).is_ok());
+        assert!(auth.check(&request(None, None, &query)).is_ok());
+        assert!(auth
+            .check(&request(None, Some("Bearer nope"), "/"))
+            .is_err());
+        assert!(auth.check(&request(None, None, "/")).is_err());
+
+        assert!(tokens.revoke(&token));
+        assert!(auth.check(&request(None, Some(&header), "/")).is_err());
+    }
+
+    #[test]
+    fn only_token_hashes_are_kept() {
+        let tokens = WebsocketTokens::new(vec!["config-token".to_string()]);
+        let minted = tokens.mint();
+        let hashes = tokens.hashes();
+        assert_eq!(hashes.len(), 2);
+        assert!(hashes.contains(&TokenHash::new("config-token")));
+        assert!(hashes.contains(&TokenHash::new(&minted)));
+
+        let restored = WebsocketTokens::from_hashes(hashes);
+        assert!(restored.contains("config-token"));
+        assert!(restored.contains(&minted));
+        assert!(!restored.contains("nope"));
+    }
+}
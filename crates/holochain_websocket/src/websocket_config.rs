@@ -1,5 +1,7 @@
 //! defines a builder-style config struct for setting up websockets
 
+use crate::WebsocketAuth;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +28,14 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Checks listeners make on incoming handshakes.
+    /// [default = accept every connection]
+    pub auth: WebsocketAuth,
+
+    /// Bearer token outgoing connections present in the handshake.
+    /// [default = None]
+    pub bearer_token: Option<String>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +48,8 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            auth: WebsocketAuth::default(),
+            bearer_token: None,
         }
     }
 }
@@ -78,6 +90,18 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn auth(mut self, auth: WebsocketAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn bearer_token(mut self, token: String) -> Self {
+        self.bearer_token = Some(token);
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -3,6 +3,9 @@
 use crate::*;
 use futures::stream::BoxStream;
 use futures::stream::StreamExt;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
 
 /// Websocket listening / server socket. This struct is an async Stream -
 /// calling `.next().await` will give you a Future that will in turn resolve
@@ -89,8 +92,18 @@ async fn connect(
                 message = "accepted incoming raw socket",
                 remote_addr = %socket.peer_addr()?,
             );
-            let socket = tokio_tungstenite::accept_async_with_config(
+            let auth = config.auth.clone();
+            let check_handshake = move |request: &Request, response: Response| {
+                auth.check(request).map(|_| response).map_err(|reason| {
+                    tracing::warn!(message = "refused handshake", %reason);
+                    let mut response = ErrorResponse::new(Some(reason));
+                    *response.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+                    response
+                })
+            };
+            let socket = tokio_tungstenite::accept_hdr_async_with_config(
                 socket,
+                check_handshake,
                 Some(tungstenite::protocol::WebSocketConfig {
                     max_send_queue: Some(config.max_send_queue),
                     max_message_size: Some(config.max_message_size),
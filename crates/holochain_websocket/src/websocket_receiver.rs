@@ -1,6 +1,7 @@
 //! defines the read/recv half of a websocket pair
 
 use crate::*;
+use tungstenite::client::IntoClientRequest;
 
 /// When a websocket is closed gracefully from the remote end,
 /// this item is included in the ConnectionReset error message.
@@ -110,13 +111,20 @@ pub async fn websocket_connect(
     socket.set_keepalive(Some(std::time::Duration::from_secs(
         config.tcp_keepalive_s as u64,
     )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    if let Some(token) = &config.bearer_token {
+        let value = format!("Bearer {}", token)
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        request.headers_mut().insert("authorization", value);
+    }
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     build_websocket_pair(config, socket)
 }
 
@@ -68,6 +68,52 @@ async fn integration_test() {
     assert_eq!("None", &format!("{:?}", recv.next().await),);
 }
 
+#[tokio::test]
+async fn handshake_requires_token() {
+    let tokens = WebsocketTokens::default();
+    let token = tokens.mint();
+    let config = WebsocketConfig::default().auth(WebsocketAuth::default().require_token(tokens));
+    let mut server = websocket_bind(url2!("ws://127.0.0.1:0"), std::sync::Arc::new(config))
+        .await
+        .unwrap();
+    let binding = server.local_addr().clone();
+
+    tokio::task::spawn(async move {
+        while let Some(maybe_con) = server.next().await {
+            // refused handshakes come through as errors
+            if let Ok((_send, mut recv)) = maybe_con {
+                tokio::task::spawn(async move {
+                    while let Some(msg) = recv.next().await {
+                        if let WebsocketMessage::Request(data, respond) = msg {
+                            let msg: TestMessage = data.try_into().unwrap();
+                            let msg = TestMessage(format!("echo: {}", msg.0));
+                            respond(msg.try_into().unwrap()).await.unwrap();
+                        }
+                    }
+                });
+            }
+        }
+    });
+
+    let refused = websocket_connect(
+        binding.clone(),
+        std::sync::Arc::new(WebsocketConfig::default()),
+    )
+    .await;
+    assert!(refused.is_err());
+
+    let config = WebsocketConfig::default().bearer_token("wrong".into());
+    let refused = websocket_connect(binding.clone(), std::sync::Arc::new(config)).await;
+    assert!(refused.is_err());
+
+    let config = WebsocketConfig::default().bearer_token(token);
+    let (mut send, _recv) = websocket_connect(binding, std::sync::Arc::new(config))
+        .await
+        .unwrap();
+    let rsp: TestMessage = send.request(TestMessage("test".to_string())).await.unwrap();
+    assert_eq!("echo: test", &rsp.0);
+}
+
 #[tokio::test]
 #[ignore = "stub"]
 async fn channels_properly_close() {
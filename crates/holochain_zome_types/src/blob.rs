@@ -0,0 +1,217 @@
+//! Blobs hold data that is too large for a single entry.
+//!
+//! A blob is committed as a sequence of content addressed [`BlobChunk`] entries
+//! followed by a [`BlobManifest`] entry that lists the chunk hashes in order.
+//! The hash of the manifest entry is the address of the blob.
+
+use crate::crdt::CrdtType;
+use crate::entry_def::EntryDef;
+use crate::entry_def::EntryDefId;
+use crate::entry_def::EntryVisibility;
+use crate::entry_def::RequiredValidations;
+use crate::validate::RequiredValidationType;
+use holo_hash::EntryHash;
+use holochain_serialized_bytes::prelude::*;
+
+/// Blobs are split into chunks of this many bytes.
+/// Every chunk except the last is exactly this size.
+pub const BLOB_CHUNK_SIZE: usize = 1_000_000;
+
+/// Entry def id reserved for blob manifests.
+pub const BLOB_MANIFEST_ENTRY_DEF_ID: &str = "hdk.blob_manifest";
+
+/// Entry def id reserved for blob chunks.
+pub const BLOB_CHUNK_ENTRY_DEF_ID: &str = "hdk.blob_chunk";
+
+/// A single chunk of blob data.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, SerializedBytes)]
+#[repr(transparent)]
+pub struct BlobChunk(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl BlobChunk {
+    /// Constructor
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// The entry def zomes must include to commit blob chunks.
+    pub fn entry_def() -> EntryDef {
+        blob_entry_def(BLOB_CHUNK_ENTRY_DEF_ID)
+    }
+
+    /// The reserved entry def id for blob chunks.
+    pub fn entry_def_id() -> EntryDefId {
+        BLOB_CHUNK_ENTRY_DEF_ID.into()
+    }
+
+    /// Number of bytes in this chunk.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// True if the chunk holds no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl AsRef<[u8]> for BlobChunk {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<BlobChunk> for Vec<u8> {
+    fn from(chunk: BlobChunk) -> Self {
+        chunk.0
+    }
+}
+
+/// Lists the chunks that make up a blob, in order.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct BlobManifest {
+    /// Total size of the blob in bytes.
+    pub size: u64,
+    /// Hashes of the [`BlobChunk`] entries.
+    pub chunks: Vec<EntryHash>,
+}
+
+/// Ways a manifest can disagree with itself or with its chunks.
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum BlobError {
+    /// The manifest lists the wrong number of chunks for its size.
+    #[error("Blob of {size} bytes should have {expected} chunks but the manifest lists {found}")]
+    ChunkCount {
+        /// Size claimed by the manifest.
+        size: u64,
+        /// Chunks needed to hold that size.
+        expected: usize,
+        /// Chunks listed.
+        found: usize,
+    },
+    /// A chunk has the wrong length for its position in the blob.
+    #[error("Blob chunk {index} should be {expected} bytes but is {found} bytes")]
+    ChunkSize {
+        /// Position of the chunk in the manifest.
+        index: usize,
+        /// Length required at that position.
+        expected: usize,
+        /// Length of the chunk.
+        found: usize,
+    },
+}
+
+impl BlobManifest {
+    /// Constructor
+    pub fn new(size: u64, chunks: Vec<EntryHash>) -> Self {
+        Self { size, chunks }
+    }
+
+    /// The entry def zomes must include to commit blob manifests.
+    pub fn entry_def() -> EntryDef {
+        blob_entry_def(BLOB_MANIFEST_ENTRY_DEF_ID)
+    }
+
+    /// The reserved entry def id for blob manifests.
+    pub fn entry_def_id() -> EntryDefId {
+        BLOB_MANIFEST_ENTRY_DEF_ID.into()
+    }
+
+    /// Split bytes into the chunks that should be committed for them.
+    pub fn chunk(bytes: &[u8]) -> Vec<BlobChunk> {
+        bytes
+            .chunks(BLOB_CHUNK_SIZE)
+            .map(|c| BlobChunk::new(c.to_vec()))
+            .collect()
+    }
+
+    /// Number of chunks a blob of this many bytes is split into.
+    pub fn chunk_count(size: u64) -> usize {
+        ((size + BLOB_CHUNK_SIZE as u64 - 1) / BLOB_CHUNK_SIZE as u64) as usize
+    }
+
+    /// Check the number of chunks listed matches the size of the blob.
+    pub fn check(&self) -> Result<(), BlobError> {
+        let expected = Self::chunk_count(self.size);
+        if self.chunks.len() == expected {
+            Ok(())
+        } else {
+            Err(BlobError::ChunkCount {
+                size: self.size,
+                expected,
+                found: self.chunks.len(),
+            })
+        }
+    }
+
+    /// Check a chunk has the right length for its position in this blob.
+    pub fn check_chunk(&self, index: usize, chunk: &BlobChunk) -> Result<(), BlobError> {
+        let offset = index as u64 * BLOB_CHUNK_SIZE as u64;
+        let expected = self.size.saturating_sub(offset).min(BLOB_CHUNK_SIZE as u64) as usize;
+        if chunk.len() == expected {
+            Ok(())
+        } else {
+            Err(BlobError::ChunkSize {
+                index,
+                expected,
+                found: chunk.len(),
+            })
+        }
+    }
+}
+
+/// Blob entries are public so any agent can fetch the chunks of a manifest.
+fn blob_entry_def(id: &str) -> EntryDef {
+    EntryDef::new(
+        id.into(),
+        EntryVisibility::Public,
+        CrdtType,
+        RequiredValidations::default(),
+        RequiredValidationType::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(n: usize) -> Vec<EntryHash> {
+        (0..n)
+            .map(|i| EntryHash::from_raw_36(vec![i as u8; 36]))
+            .collect()
+    }
+
+    #[test]
+    fn chunk_count_matches_size() {
+        let size = 2 * BLOB_CHUNK_SIZE as u64 + 1;
+        assert_eq!(BlobManifest::new(size, hashes(3)).check(), Ok(()));
+        assert_eq!(BlobManifest::new(0, hashes(0)).check(), Ok(()));
+        assert_eq!(
+            BlobManifest::new(size, hashes(2)).check(),
+            Err(BlobError::ChunkCount {
+                size,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn chunks_round_trip_through_manifest() {
+        let bytes = vec![7; BLOB_CHUNK_SIZE + 10];
+        let chunks = BlobManifest::chunk(&bytes);
+        let manifest = BlobManifest::new(bytes.len() as u64, hashes(chunks.len()));
+        manifest.check().unwrap();
+        for (i, chunk) in chunks.iter().enumerate() {
+            manifest.check_chunk(i, chunk).unwrap();
+        }
+        assert_eq!(
+            manifest.check_chunk(1, &chunks[0]),
+            Err(BlobError::ChunkSize {
+                index: 1,
+                expected: 10,
+                found: BLOB_CHUNK_SIZE
+            })
+        );
+    }
+}
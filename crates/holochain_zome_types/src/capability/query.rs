@@ -0,0 +1,59 @@
+use super::CapClaim;
+use super::CapGrant;
+use super::ZomeCallCapGrant;
+use holo_hash::*;
+use holochain_serialized_bytes::prelude::*;
+
+/// Filter for listing the capability grants or claims on the local source chain.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default, SerializedBytes)]
+pub struct CapQuery {
+    /// Only return grants or claims with exactly this tag.
+    /// `None` returns everything.
+    pub tag: Option<String>,
+}
+
+impl CapQuery {
+    /// Match every grant or claim regardless of tag.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Match only grants or claims with the given tag.
+    pub fn tagged(tag: String) -> Self {
+        Self { tag: Some(tag) }
+    }
+
+    /// Does the given tag pass this filter?
+    pub fn matches(&self, tag: &str) -> bool {
+        self.tag.as_ref().map(|t| t == tag).unwrap_or(true)
+    }
+}
+
+/// A grant committed to the local source chain that has not been updated or deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LiveCapGrant {
+    /// The header that created the grant.
+    /// This is the address to update or delete to revoke the grant.
+    pub header_hash: HeaderHash,
+    /// The grant itself.
+    pub grant: ZomeCallCapGrant,
+}
+
+/// A claim committed to the local source chain that has not been deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LiveCapClaim {
+    /// The header that created the claim.
+    pub header_hash: HeaderHash,
+    /// The claim itself.
+    pub claim: CapClaim,
+}
+
+/// The grant that authorized the currently running zome call.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, SerializedBytes)]
+pub struct CapGrantInfo {
+    /// The grant matched against the call's function, provenance and secret.
+    /// This is `CapGrant::ChainAuthor` when the chain author calls itself.
+    pub cap_grant: CapGrant,
+    /// The agent who made the call.
+    pub provenance: AgentPubKey,
+}
@@ -11,6 +11,7 @@
 
 #[allow(missing_docs)]
 pub mod agent_info;
+pub mod blob;
 pub mod bytes;
 #[allow(missing_docs)]
 pub mod call;
@@ -37,8 +38,10 @@ pub mod migrate_agent;
 #[allow(missing_docs)]
 pub mod post_commit;
 pub mod prelude;
+pub mod property;
 pub mod query;
 pub mod request;
+pub mod schedule;
 pub mod signal;
 pub mod signature;
 pub mod timestamp;
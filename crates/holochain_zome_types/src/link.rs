@@ -1,4 +1,7 @@
 use crate::element::SignedHeaderHashed;
+use crate::header::CreateLink;
+use crate::timestamp::Timestamp;
+use holo_hash::AgentPubKey;
 use holo_hash::HeaderHash;
 use holochain_serialized_bytes::prelude::*;
 
@@ -90,6 +93,164 @@ impl Links {
     }
 }
 
+/// Narrows down the links returned by `get_links`.
+///
+/// Authorities apply the filter before responding so links that don't match
+/// are never sent over the network.
+///
+/// Links are ordered by the timestamp of their [CreateLink] and then by its hash.
+/// Pass the [LinkCursor] of the last link of a page to get the next page.
+#[derive(
+    serde::Serialize, serde::Deserialize, SerializedBytes, Default, PartialEq, Clone, Debug,
+)]
+pub struct LinkFilter {
+    /// Only match links created at or after this time.
+    pub after: Option<Timestamp>,
+    /// Only match links created before this time.
+    pub before: Option<Timestamp>,
+    /// Only match links created by one of these agents.
+    pub authors: Option<Vec<AgentPubKey>>,
+    /// Only match links that come after this position in the ordering.
+    pub cursor: Option<LinkCursor>,
+    /// Return at most this many links.
+    pub limit: Option<u32>,
+}
+
+/// The position of a link in the order links are paged through.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct LinkCursor {
+    /// When the link was created.
+    pub timestamp: Timestamp,
+    /// The hash of the link's create header.
+    pub create_link_hash: HeaderHash,
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        let since_epoch = link
+            .timestamp
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            timestamp: Timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos()),
+            create_link_hash: link.create_link_hash.clone(),
+        }
+    }
+}
+
+impl LinkFilter {
+    /// Create a no-op LinkFilter which returns everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match links created at or after this time
+    pub fn after(mut self, after: Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only match links created before this time
+    pub fn before(mut self, before: Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Only match links created by these agents
+    pub fn authors(mut self, authors: Vec<AgentPubKey>) -> Self {
+        self.authors = Some(authors);
+        self
+    }
+
+    /// Only match links after this position, e.g. the last link of the previous page
+    pub fn cursor(mut self, cursor: LinkCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Return at most this many links
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Does this filter match every link?
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Perform the boolean check which this filter represents.
+    /// The limit is not part of this check, see [LinkFilter::apply].
+    pub fn check(&self, create_link: &CreateLink, create_link_hash: &HeaderHash) -> bool {
+        self.check_parts(
+            &create_link.timestamp,
+            Some(&create_link.author),
+            create_link_hash,
+        )
+    }
+
+    /// Same as [LinkFilter::check] for when only parts of the [CreateLink] are known.
+    /// If the author isn't known the link is not checked against `authors`.
+    pub fn check_parts(
+        &self,
+        timestamp: &Timestamp,
+        author: Option<&AgentPubKey>,
+        create_link_hash: &HeaderHash,
+    ) -> bool {
+        let check_after = self.after.map(|after| *timestamp >= after).unwrap_or(true);
+        let check_before = self
+            .before
+            .map(|before| *timestamp < before)
+            .unwrap_or(true);
+        let check_authors = match (&self.authors, author) {
+            (Some(authors), Some(author)) => authors.contains(author),
+            _ => true,
+        };
+        let check_cursor = self
+            .cursor
+            .as_ref()
+            .map(|cursor| {
+                (timestamp, create_link_hash) > (&cursor.timestamp, &cursor.create_link_hash)
+            })
+            .unwrap_or(true);
+        check_after && check_before && check_authors && check_cursor
+    }
+
+    /// Sort links into page order, drop the ones that fail the check
+    /// and keep at most `limit` of the rest.
+    pub fn apply<T>(
+        &self,
+        links: Vec<T>,
+        create_link: impl Fn(&T) -> (&CreateLink, &HeaderHash),
+    ) -> Vec<T> {
+        self.apply_parts(links, |link| {
+            let (link, hash) = create_link(link);
+            (&link.timestamp, Some(&link.author), hash)
+        })
+    }
+
+    /// Same as [LinkFilter::apply] for when only parts of the [CreateLink] are known.
+    pub fn apply_parts<T>(
+        &self,
+        mut links: Vec<T>,
+        parts: impl Fn(&T) -> (&Timestamp, Option<&AgentPubKey>, &HeaderHash),
+    ) -> Vec<T> {
+        links.sort_by(|a, b| {
+            let (a, _, a_hash) = parts(a);
+            let (b, _, b_hash) = parts(b);
+            (a, a_hash).cmp(&(b, b_hash))
+        });
+        links.retain(|link| {
+            let (timestamp, author, hash) = parts(link);
+            self.check_parts(timestamp, author, hash)
+        });
+        if let Some(limit) = self.limit {
+            links.truncate(limit as usize);
+        }
+        links
+    }
+}
+
 type CreateLinkWithDeleteLinks = Vec<(SignedHeaderHashed, Vec<SignedHeaderHashed>)>;
 #[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 /// CreateLinks with and DeleteLinks on them
@@ -113,3 +274,85 @@ impl LinkDetails {
         self.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
+
+    /// Create links one second apart, alternating between two authors.
+    fn links(authors: &[AgentPubKey]) -> Vec<(CreateLink, HeaderHash)> {
+        (0..6)
+            .map(|i| {
+                let mut link = fixt!(CreateLink);
+                link.timestamp = Timestamp(i, 0);
+                link.author = authors[i as usize % 2].clone();
+                (link, fixt!(HeaderHash))
+            })
+            .collect()
+    }
+
+    fn timestamps(links: Vec<(CreateLink, HeaderHash)>) -> Vec<i64> {
+        links.into_iter().map(|(l, _)| l.timestamp.0).collect()
+    }
+
+    #[test]
+    fn filter_bounds_and_authors() {
+        let authors = vec![fixt!(AgentPubKey), fixt!(AgentPubKey)];
+        let mut links = links(&authors);
+        links.reverse();
+
+        let all = LinkFilter::new().apply(links.clone(), |(l, h)| (l, h));
+        assert_eq!(timestamps(all), vec![0, 1, 2, 3, 4, 5]);
+
+        let filter = LinkFilter::new()
+            .after(Timestamp(1, 0))
+            .before(Timestamp(5, 0));
+        assert_eq!(
+            timestamps(filter.apply(links.clone(), |(l, h)| (l, h))),
+            vec![1, 2, 3, 4]
+        );
+
+        let filter = LinkFilter::new().authors(vec![authors[1].clone()]);
+        assert_eq!(
+            timestamps(filter.apply(links, |(l, h)| (l, h))),
+            vec![1, 3, 5]
+        );
+    }
+
+    #[test]
+    fn filter_pages_with_cursor() {
+        let authors = vec![fixt!(AgentPubKey), fixt!(AgentPubKey)];
+        let links = links(&authors);
+
+        let filter = LinkFilter::new().limit(4);
+        let page = filter.apply(links.clone(), |(l, h)| (l, h));
+        assert_eq!(timestamps(page.clone()), vec![0, 1, 2, 3]);
+
+        let (last, last_hash) = page.last().cloned().unwrap();
+        let filter = filter.cursor(LinkCursor {
+            timestamp: last.timestamp,
+            create_link_hash: last_hash,
+        });
+        assert_eq!(timestamps(filter.apply(links, |(l, h)| (l, h))), vec![4, 5]);
+    }
+
+    #[test]
+    fn unknown_authors_are_not_filtered() {
+        let authors = vec![fixt!(AgentPubKey), fixt!(AgentPubKey)];
+        let links = links(&authors);
+
+        let filter = LinkFilter::new().authors(vec![authors[1].clone()]);
+        assert!(!filter.is_empty());
+        let matched = filter.apply_parts(links, |(l, h)| {
+            let author = if l.timestamp.0 < 2 {
+                None
+            } else {
+                Some(&l.author)
+            };
+            (&l.timestamp, author, h)
+        });
+        assert_eq!(timestamps(matched), vec![0, 1, 3, 5]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Common types
 
 pub use crate::agent_info::*;
+pub use crate::blob::*;
 pub use crate::bytes::*;
 pub use crate::call::*;
 pub use crate::call_remote::*;
@@ -24,9 +25,11 @@ pub use crate::link::*;
 pub use crate::metadata::*;
 pub use crate::migrate_agent::*;
 pub use crate::post_commit::*;
+pub use crate::property::*;
 pub use crate::query::ChainQueryFilter as QueryFilter;
 pub use crate::query::*;
 pub use crate::request::*;
+pub use crate::schedule::*;
 pub use crate::signal::*;
 pub use crate::signature::*;
 pub use crate::timestamp::*;
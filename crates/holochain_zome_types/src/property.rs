@@ -0,0 +1,43 @@
+//! Types related to the `property` host function
+
+use holochain_serialized_bytes::prelude::*;
+
+/// Selects the DNA properties, or a value nested inside them, for the
+/// `property` host function.
+///
+/// Each segment is a key into an object or an index into an array, so
+/// `["limits", "max_posts"]` selects `10` from
+/// `{ "limits": { "max_posts": 10 } }`.
+/// An empty path selects all the properties.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
+pub struct PropertyPath(pub Vec<String>);
+
+impl PropertyPath {
+    /// Select all the properties.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Is this the path to all the properties?
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<&str> for PropertyPath {
+    /// Split a dotted path such as `"limits.max_posts"` into segments.
+    fn from(path: &str) -> Self {
+        Self(
+            path.split('.')
+                .filter(|segment| !segment.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<String>> for PropertyPath {
+    fn from(segments: Vec<String>) -> Self {
+        Self(segments)
+    }
+}
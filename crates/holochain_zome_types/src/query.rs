@@ -3,9 +3,13 @@
 use crate::header::EntryType;
 use crate::header::Header;
 use crate::header::HeaderType;
-use crate::warrant::Warrant;
+use crate::warrant::SignedWarrant;
+use holo_hash::EntryHash;
 use holo_hash::HeaderHash;
 pub use holochain_serialized_bytes::prelude::*;
+use std::collections::HashSet;
+use std::ops::Bound;
+use std::ops::RangeBounds;
 
 /// Query arguments
 #[derive(
@@ -14,15 +18,66 @@ pub use holochain_serialized_bytes::prelude::*;
 #[non_exhaustive]
 pub struct ChainQueryFilter {
     /// The range of source chain sequence numbers to match.
-    /// Inclusive start, exclusive end.
-    // TODO: can we generalize this over RangeBounds to allow unbounded ranges?
-    pub sequence_range: Option<std::ops::Range<u32>>,
-    /// Filter by EntryType
-    pub entry_type: Option<EntryType>,
-    /// Filter by HeaderType
-    pub header_type: Option<HeaderType>,
+    pub sequence_range: Option<SequenceRange>,
+    /// Filter by any of these EntryTypes
+    pub entry_type: Option<HashSet<EntryType>>,
+    /// Filter by any of these HeaderTypes
+    pub header_type: Option<HashSet<HeaderType>>,
+    /// Filter by any of these entry hashes
+    pub entry_hashes: Option<HashSet<EntryHash>>,
     /// Include the entries in the elements
     pub include_entries: bool,
+    /// Return the newest elements first
+    pub descending: bool,
+    /// Return at most this many elements
+    pub limit: Option<u32>,
+}
+
+/// A range of source chain sequence numbers.
+/// Inclusive start, exclusive end. No end means the range
+/// runs to the head of the chain.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SequenceRange {
+    /// The first sequence number in the range.
+    pub start: u32,
+    /// One past the last sequence number in the range.
+    pub end: Option<u32>,
+}
+
+impl SequenceRange {
+    /// Normalize any range of sequence numbers.
+    pub fn new(range: impl RangeBounds<u32>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(s) => *s,
+            Bound::Excluded(s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => Some(e.saturating_add(1)),
+            Bound::Excluded(e) => Some(*e),
+            Bound::Unbounded => None,
+        };
+        Self { start, end }
+    }
+
+    /// The range clamped to a chain of this length.
+    pub fn clamp(&self, len: u32) -> std::ops::Range<u32> {
+        let end = self.end.map(|e| e.min(len)).unwrap_or(len);
+        self.start.min(end)..end
+    }
+}
+
+impl RangeBounds<u32> for SequenceRange {
+    fn start_bound(&self) -> Bound<&u32> {
+        Bound::Included(&self.start)
+    }
+
+    fn end_bound(&self) -> Bound<&u32> {
+        match &self.end {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -37,9 +92,8 @@ pub struct AgentActivity {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
-    /// Warrants about this AgentActivity.
-    /// Placeholder for future.
-    pub warrants: Vec<Warrant>,
+    /// Warrants issued against this agent.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -116,21 +170,52 @@ impl ChainQueryFilter {
         }
     }
 
-    /// Filter on sequence range
-    pub fn sequence_range(mut self, sequence_range: std::ops::Range<u32>) -> Self {
-        self.sequence_range = Some(sequence_range);
+    /// Filter on sequence range.
+    /// Accepts any range, e.g. `2..5`, `2..=4`, `2..` or `..5`.
+    pub fn sequence_range(mut self, sequence_range: impl RangeBounds<u32>) -> Self {
+        self.sequence_range = Some(SequenceRange::new(sequence_range));
         self
     }
 
-    /// Filter on entry type
+    /// Filter on entry type.
+    /// Can be called more than once to match any of several types.
     pub fn entry_type(mut self, entry_type: EntryType) -> Self {
-        self.entry_type = Some(entry_type);
+        self.entry_type
+            .get_or_insert_with(HashSet::new)
+            .insert(entry_type);
         self
     }
 
-    /// Filter on header type
+    /// Filter on any of these entry types
+    pub fn entry_types(mut self, entry_types: impl IntoIterator<Item = EntryType>) -> Self {
+        self.entry_type
+            .get_or_insert_with(HashSet::new)
+            .extend(entry_types);
+        self
+    }
+
+    /// Filter on header type.
+    /// Can be called more than once to match any of several types.
     pub fn header_type(mut self, header_type: HeaderType) -> Self {
-        self.header_type = Some(header_type);
+        self.header_type
+            .get_or_insert_with(HashSet::new)
+            .insert(header_type);
+        self
+    }
+
+    /// Filter on any of these header types
+    pub fn header_types(mut self, header_types: impl IntoIterator<Item = HeaderType>) -> Self {
+        self.header_type
+            .get_or_insert_with(HashSet::new)
+            .extend(header_types);
+        self
+    }
+
+    /// Filter on headers for any of these entries
+    pub fn entry_hashes(mut self, entry_hashes: impl IntoIterator<Item = EntryHash>) -> Self {
+        self.entry_hashes
+            .get_or_insert_with(HashSet::new)
+            .extend(entry_hashes);
         self
     }
 
@@ -140,7 +225,21 @@ impl ChainQueryFilter {
         self
     }
 
-    /// Perform the boolean check which this filter represents
+    /// Return the newest elements first
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Return at most this many elements
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Perform the boolean check which this filter represents.
+    /// Ordering and the limit are not part of the check and must
+    /// be applied by whoever walks the chain.
     pub fn check(&self, header: &Header) -> bool {
         let check_range = self
             .sequence_range
@@ -150,19 +249,29 @@ impl ChainQueryFilter {
         let check_header_type = self
             .header_type
             .as_ref()
-            .map(|header_type| header.header_type() == *header_type)
+            .map(|header_types| header_types.contains(&header.header_type()))
             .unwrap_or(true);
         let check_entry_type = self
             .entry_type
             .as_ref()
-            .map(|entry_type| {
+            .map(|entry_types| {
                 header
                     .entry_type()
-                    .map(|header_entry_type| *header_entry_type == *entry_type)
+                    .map(|header_entry_type| entry_types.contains(header_entry_type))
+                    .unwrap_or(false)
+            })
+            .unwrap_or(true);
+        let check_entry_hash = self
+            .entry_hashes
+            .as_ref()
+            .map(|entry_hashes| {
+                header
+                    .entry_hash()
+                    .map(|entry_hash| entry_hashes.contains(entry_hash))
                     .unwrap_or(false)
             })
             .unwrap_or(true);
-        check_range && check_header_type && check_entry_type
+        check_range && check_header_type && check_entry_type && check_entry_hash
     }
 }
 
@@ -176,6 +285,7 @@ mod tests {
     use ::fixt::prelude::*;
 
     use super::ChainQueryFilter;
+    use super::SequenceRange;
 
     /// Create three Headers with various properties.
     /// Also return the EntryTypes used to construct the first two headers.
@@ -324,4 +434,64 @@ mod tests {
             [true, false, false, false, true, false].to_vec()
         );
     }
+
+    #[test]
+    fn filter_by_open_ranges() {
+        let headers = fixtures();
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(4..), &headers),
+            [false, false, false, false, true, true].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(..=1), &headers),
+            [true, true, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(..), &headers),
+            [true; 6].to_vec()
+        );
+    }
+
+    #[test]
+    fn sequence_range_normalizes_bounds() {
+        assert_eq!(SequenceRange::new(2..=4), SequenceRange::new(2..5));
+        assert_eq!(SequenceRange::new(..).clamp(3), 0..3);
+        assert_eq!(SequenceRange::new(1..10).clamp(3), 1..3);
+        assert_eq!(SequenceRange::new(5..).clamp(3), 3..3);
+    }
+
+    #[test]
+    fn filter_by_type_sets() {
+        let headers = fixtures();
+
+        let query = ChainQueryFilter::new()
+            .header_types(vec![headers[0].header_type(), headers[2].header_type()]);
+        assert_eq!(
+            map_query(&query, &headers),
+            [true, false, true, true, false, true].to_vec()
+        );
+
+        let query = ChainQueryFilter::new()
+            .entry_type(headers[0].entry_type().unwrap().clone())
+            .entry_type(headers[1].entry_type().unwrap().clone());
+        assert_eq!(
+            map_query(&query, &headers),
+            [true, true, false, true, true, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_entry_hash() {
+        let headers = fixtures();
+
+        let query = ChainQueryFilter::new().entry_hashes(vec![
+            headers[1].entry_hash().unwrap().clone(),
+            headers[3].entry_hash().unwrap().clone(),
+        ]);
+        assert_eq!(
+            map_query(&query, &headers),
+            [false, true, false, true, false, false].to_vec()
+        );
+    }
 }
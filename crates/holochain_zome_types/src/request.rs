@@ -15,8 +15,6 @@ pub struct MetadataRequest {
     pub all_deletes: bool,
     /// Get all the updates on an entry or header
     pub all_updates: bool,
-    /// Placeholder
-    pub follow_redirects: bool,
     /// Request the status of an entry.
     /// This is faster then getting all the headers
     /// and checking for live headers.
@@ -30,7 +28,6 @@ impl Default for MetadataRequest {
             all_invalid_headers: false,
             all_deletes: true,
             all_updates: true,
-            follow_redirects: false,
             entry_dht_status: false,
         }
     }
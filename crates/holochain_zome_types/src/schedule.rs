@@ -0,0 +1,55 @@
+//! Types related to the `schedule` host function
+
+use crate::zome::FunctionName;
+use core::time::Duration;
+use holochain_serialized_bytes::prelude::*;
+
+/// The shortest interval a function may recur at.
+/// Anything shorter would keep the host busy calling it back.
+pub const MIN_SCHEDULE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A request from a zome to have one of its own functions called back by the
+/// host at some point in the future.
+///
+/// The function is called with the author of the source chain as the
+/// provenance, so it is always authorized, and receives `payload` as its input.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
+pub struct Schedule {
+    /// The function in the calling zome to call back.
+    pub fn_name: FunctionName,
+    /// The serialized input for the function.
+    pub payload: SerializedBytes,
+    /// How long to wait before the first call.
+    pub after: Duration,
+    /// If set, keep calling the function at this interval after the first
+    /// call, until the cell is removed.
+    /// It may not be shorter than [MIN_SCHEDULE_INTERVAL].
+    pub every: Option<Duration>,
+}
+
+impl Schedule {
+    /// Call `fn_name` once, after the given delay.
+    pub fn once(fn_name: FunctionName, payload: SerializedBytes, after: Duration) -> Self {
+        Self {
+            fn_name,
+            payload,
+            after,
+            every: None,
+        }
+    }
+
+    /// Call `fn_name` after the given delay and then repeatedly at the interval.
+    pub fn recurring(
+        fn_name: FunctionName,
+        payload: SerializedBytes,
+        after: Duration,
+        every: Duration,
+    ) -> Self {
+        Self {
+            fn_name,
+            payload,
+            after,
+            every: Some(every),
+        }
+    }
+}
@@ -1,6 +1,129 @@
 //! Types for warrants
+//!
+//! A warrant is a statement, signed by an authority, that an agent has broken
+//! the rules of the network. It carries the proof so anyone receiving it can
+//! check the claim for themselves instead of trusting the authority.
+use crate::element::SignedHeader;
+use crate::entry::Entry;
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use holo_hash::AgentPubKey;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A warrant issued by an authority about another agent's chain.
+pub struct Warrant {
+    /// The evidence of bad behaviour.
+    pub proof: WarrantProof,
+    /// The authority that issued this warrant.
+    pub author: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// The evidence backing a warrant.
+/// The headers are signed by the warranted agent so they
+/// can't be fabricated by the authority.
+pub enum WarrantProof {
+    /// Two different headers by the same author at the same `header_seq`.
+    ChainFork(SignedHeader, SignedHeader),
+    /// A header that failed validation.
+    InvalidChainOp {
+        /// The header that was rejected.
+        header: SignedHeader,
+        /// The entry that was validated along with the header,
+        /// if the op carried one.
+        entry: Option<Entry>,
+        /// The op the header was rejected in.
+        op_type: DhtOpType,
+        /// Which kind of validation rejected the header.
+        validation_type: ValidationType,
+        /// Why the header was rejected.
+        reason: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+/// The kind of op a header was validated in.
+/// Each kind of op is validated by different authorities, with different rules.
+pub enum DhtOpType {
+    #[allow(missing_docs)]
+    StoreElement,
+    #[allow(missing_docs)]
+    StoreEntry,
+    #[allow(missing_docs)]
+    RegisterAgentActivity,
+    #[allow(missing_docs)]
+    RegisterUpdatedContent,
+    #[allow(missing_docs)]
+    RegisterUpdatedElement,
+    #[allow(missing_docs)]
+    RegisterDeletedBy,
+    #[allow(missing_docs)]
+    RegisterDeletedEntryHeader,
+    #[allow(missing_docs)]
+    RegisterAddLink,
+    #[allow(missing_docs)]
+    RegisterRemoveLink,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+/// The kind of validation that rejected a header.
+pub enum ValidationType {
+    /// Rejected by the integrity checks every DNA shares.
+    Sys,
+    /// Rejected by the DNA's own validation callbacks.
+    App,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A warrant along with the signature of the authority that issued it.
+pub struct SignedWarrant {
+    /// The content of the warrant.
+    pub warrant: Warrant,
+    /// The signature of the warrant's author.
+    pub signature: Signature,
+}
+
+impl Warrant {
+    /// The agent this warrant is about.
+    pub fn warranted_agent(&self) -> &AgentPubKey {
+        self.proof.warranted_agent()
+    }
+}
+
+impl WarrantProof {
+    /// The agent this proof is about.
+    pub fn warranted_agent(&self) -> &AgentPubKey {
+        self.warranted_header().header().author()
+    }
+
+    /// The header this proof is about.
+    /// For a fork this is the first of the two headers.
+    pub fn warranted_header(&self) -> &SignedHeader {
+        match self {
+            WarrantProof::ChainFork(first, _) => first,
+            WarrantProof::InvalidChainOp { header, .. } => header,
+        }
+    }
+}
+
+// The signature is over the whole warrant, so equal warrants hash the same
+impl std::hash::Hash for SignedWarrant {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.signature.hash(state)
+    }
+}
+
+impl SignedWarrant {
+    /// Accessor for the Warrant
+    pub fn warrant(&self) -> &Warrant {
+        &self.warrant
+    }
+
+    /// Accessor for the Signature
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
@@ -5,6 +5,32 @@ pub mod key_ref;
 pub mod nonce;
 pub mod x25519;
 
+/// Derive a new secretbox key from an existing one and a context.
+/// The same key and context always derive the same key.
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct XSalsa20Poly1305DeriveKey {
+    key_ref: crate::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef,
+    #[serde(with = "serde_bytes")]
+    context: Vec<u8>,
+}
+
+impl XSalsa20Poly1305DeriveKey {
+    pub fn new(
+        key_ref: crate::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef,
+        context: Vec<u8>,
+    ) -> Self {
+        Self { key_ref, context }
+    }
+
+    pub fn as_key_ref_ref(&self) -> &crate::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef {
+        &self.key_ref
+    }
+
+    pub fn as_context_ref(&self) -> &[u8] {
+        &self.context
+    }
+}
+
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct XSalsa20Poly1305Encrypt {
     key_ref: crate::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef,
@@ -1,10 +1,13 @@
 use holochain_serialized_bytes::prelude::*;
 
 /// Key refs are the same length as the keys themselves.
-/// The key ref is just a sha256 of the key. There are no benefits, only downsides, to having
-/// either a larger or smaller set of outputs (ref size) vs. the set of inputs (key size).
+/// There are no benefits, only downsides, to having either a larger or smaller set of outputs
+/// (ref size) vs. the set of inputs (key size).
 pub const KEY_REF_BYTES: usize = 32;
 
+/// An opaque reference to a secretbox key held by the keystore.
+/// The key itself never leaves the keystore, so holding a ref is only useful on the
+/// conductor whose keystore created it.
 #[derive(Clone, Copy, SerializedBytes)]
 pub struct XSalsa20Poly1305KeyRef([u8; KEY_REF_BYTES]);
 pub type SecretBoxKeyRef = XSalsa20Poly1305KeyRef;
@@ -80,17 +80,25 @@ wasm_io_types! {
     // Header hash of the DeleteLink element.
     fn call_remote (zt::call_remote::CallRemote) -> zt::ZomeCallResponse;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List all the live capability claims on the local chain.
+    fn capability_claims (zt::capability::CapQuery) -> Vec<zt::capability::LiveCapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List all the live capability grants on the local chain.
+    fn capability_grants (zt::capability::CapQuery) -> Vec<zt::capability::LiveCapGrant>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability grant that authorized the current zome call.
+    fn capability_info (()) -> zt::capability::CapGrantInfo;
 
     fn create_x25519_keypair(()) -> zt::x_salsa20_poly1305::x25519::X25519PubKey;
 
+    // Generate a secretbox key in the keystore.
+    fn create_x_salsa20_poly1305_key(()) -> zt::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef;
+
+    // Key, Context.
+    fn derive_x_salsa20_poly1305_key(
+        zt::x_salsa20_poly1305::XSalsa20Poly1305DeriveKey
+    ) -> zt::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef;
+
     fn x_salsa20_poly1305_encrypt(
         zt::x_salsa20_poly1305::XSalsa20Poly1305Encrypt
     ) -> zt::x_salsa20_poly1305::encrypted_data::XSalsa20Poly1305EncryptedData;
@@ -139,18 +147,22 @@ wasm_io_types! {
         )
     ) -> zt::query::AgentActivity;
 
+    // Get a blob by the hash of its manifest, fetching the chunks in parallel.
+    fn get_blob (holo_hash::EntryHash) -> Option<zt::bytes::Bytes>;
+
     fn get_details ((holo_hash::AnyDhtHash, zt::entry::GetOptions)) -> Option<zt::metadata::Details>;
 
     // Get links by entry hash from the cascade.
-    fn get_links ((holo_hash::EntryHash, Option<zt::link::LinkTag>)) -> zt::link::Links;
+    // The tag matches every link whose tag starts with it.
+    fn get_links ((holo_hash::EntryHash, Option<zt::link::LinkTag>, zt::link::LinkFilter)) -> zt::link::Links;
 
     fn get_link_details ((holo_hash::EntryHash, Option<zt::link::LinkTag>)) -> zt::link::LinkDetails;
 
     // Hash an entry on the host.
     fn hash_entry (zt::entry::Entry) -> holo_hash::EntryHash;
 
-    // @todo
-    fn property (()) -> ();
+    // The DNA properties, or the value at a path inside them if it exists.
+    fn property (zt::property::PropertyPath) -> Option<SerializedBytes>;
 
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> zt::element::ElementVec;
@@ -164,8 +176,8 @@ wasm_io_types! {
     // // @todo
     // fn send (()) -> ();
 
-    // @todo
-    fn schedule (core::time::Duration) -> ();
+    // Have the host call a function in this zome at some time in the future.
+    fn schedule (zt::schedule::Schedule) -> ();
 
     // @todo
     fn show_env (()) -> ();
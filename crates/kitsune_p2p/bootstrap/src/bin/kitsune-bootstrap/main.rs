@@ -0,0 +1,44 @@
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_bootstrap::*;
+use kitsune_p2p_types::dependencies::ghost_actor;
+use structopt::StructOpt;
+
+mod opt;
+use opt::*;
+
+#[tokio::main]
+async fn main() {
+    let _ = tracing::subscriber::set_global_default(
+        tracing_subscriber::FmtSubscriber::builder()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .finish(),
+    );
+
+    if let Err(e) = inner().await {
+        eprintln!("{:?}", e);
+    }
+}
+
+async fn inner() -> BootstrapResult<()> {
+    let opt = Opt::from_args();
+    let addr = opt
+        .bind_to
+        .unwrap_or_else(|| std::net::SocketAddr::from(([0, 0, 0, 0], 0)));
+
+    let limits = StoreLimits {
+        max_spaces: opt.max_spaces,
+        max_agents_per_space: opt.max_agents_per_space,
+    };
+
+    match &opt.data_dir {
+        Some(dir) => serve(addr, FileStore::open_with_limits(dir, limits)?).await,
+        None => serve(addr, MemStore::with_limits(limits)).await,
+    }
+}
+
+async fn serve<S: BootstrapStore>(addr: std::net::SocketAddr, store: S) -> BootstrapResult<()> {
+    let (bound, server) = bind(addr, store).await?;
+    tracing::info!("kitsune-bootstrap listening on http://{}", bound);
+    println!("http://{}", bound);
+    server.await
+}
@@ -0,0 +1,24 @@
+/// Option Parsing
+#[derive(structopt::StructOpt, Debug)]
+#[structopt(name = "kitsune-bootstrap")]
+pub struct Opt {
+    /// To which network interface / port should we bind?
+    /// Default: "0.0.0.0:0".
+    #[structopt(short = "b", long)]
+    pub bind_to: Option<std::net::SocketAddr>,
+
+    /// Persist agent infos in this directory so they survive restarts.
+    /// Default: None = keep agent infos in memory only.
+    #[structopt(short = "d", long)]
+    pub data_dir: Option<std::path::PathBuf>,
+
+    /// The most spaces to hold agent infos for at once.
+    /// Default: 1024.
+    #[structopt(long, default_value = "1024")]
+    pub max_spaces: usize,
+
+    /// The most agents to hold agent infos for in any one space.
+    /// Default: 1024.
+    #[structopt(long, default_value = "1024")]
+    pub max_agents_per_space: usize,
+}
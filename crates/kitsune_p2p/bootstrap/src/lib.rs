@@ -0,0 +1,191 @@
+#![deny(missing_docs)]
+//! Self-hostable bootstrap service for kitsune-p2p.
+//!
+//! Speaks the same `X-Op` POST protocol as the hosted bootstrap service:
+//!
+//! - `put`: store a messagepack encoded `AgentInfoSigned` after checking its
+//!   signature and expiry
+//! - `now`: return the service's current unix time in milliseconds
+//! - `random`: return up to `limit` random agent infos for a space
+//!
+//! Agent infos can be held in memory with [`MemStore`] or persisted to disk
+//! with [`FileStore`].
+
+use kitsune_p2p::agent_store::now_ms;
+use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::KitsuneAgent;
+use kitsune_p2p::KitsuneP2pError;
+use kitsune_p2p::KitsuneSpace;
+
+/// re-exported dependencies
+pub mod dependencies {
+    pub use ::kitsune_p2p;
+}
+
+/// Bootstrap service error type.
+#[derive(Debug, thiserror::Error)]
+pub enum BootstrapError {
+    /// The request or stored data could not be decoded.
+    #[error("Decoding Error: {0}")]
+    Decoding(Box<str>),
+
+    /// The agent info has a bad signature, is from the future or has expired.
+    #[error(transparent)]
+    AgentInfo(#[from] KitsuneP2pError),
+
+    /// The request op header is missing or unknown.
+    #[error("Unknown op: {0:?}")]
+    UnknownOp(Option<String>),
+
+    /// Std Io Error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Hyper Error.
+    #[error(transparent)]
+    Hyper(#[from] hyper::Error),
+
+    /// The request body is larger than [`MAX_BODY_BYTES`].
+    #[error("Request body is larger than {} bytes", MAX_BODY_BYTES)]
+    BodyTooLarge,
+
+    /// The agent info is valid for longer than [`MAX_EXPIRES_AFTER_MS`].
+    #[error(
+        "Agent info expires after {0}ms, the most allowed is {}ms",
+        MAX_EXPIRES_AFTER_MS
+    )]
+    ExpiresAfterTooLong(u64),
+
+    /// The store already holds as many spaces as it allows.
+    #[error("The bootstrap service is not accepting any more spaces")]
+    TooManySpaces,
+
+    /// The store already holds as many agents for the space as it allows.
+    #[error("The bootstrap service is not accepting any more agents for this space")]
+    SpaceFull,
+}
+
+/// Bootstrap service result type.
+pub type BootstrapResult<T> = Result<T, BootstrapError>;
+
+/// The largest request body the service will read.
+/// Agent infos are well under this even with many urls.
+pub const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// The longest an agent info may stay valid for after it was signed.
+/// Kitsune signs agent infos that expire after 20 minutes.
+pub const MAX_EXPIRES_AFTER_MS: u64 = 60 * 60 * 1000;
+
+/// An agent info that has passed signature and expiry checks,
+/// along with the exact bytes it was received as.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedAgentInfo {
+    space: KitsuneSpace,
+    agent: KitsuneAgent,
+    signed_at_ms: u64,
+    expires_at_ms: u64,
+    encoded: Vec<u8>,
+}
+
+impl VerifiedAgentInfo {
+    /// Decode a messagepack encoded `AgentInfoSigned` and check that:
+    ///
+    /// - the signing agent is the agent the info describes
+    /// - the ed25519 signature verifies against the agent info bytes
+    /// - it was not signed after `now_ms`
+    /// - it has not expired as of `now_ms`
+    /// - it does not stay valid for longer than [`MAX_EXPIRES_AFTER_MS`]
+    pub fn verify(encoded: Vec<u8>, now_ms: u64) -> BootstrapResult<Self> {
+        let signed: AgentInfoSigned = kitsune_p2p_types::codec::rmp_decode(&mut &*encoded)
+            .map_err(|e| BootstrapError::Decoding(e.to_string().into_boxed_str()))?;
+        let info = signed.verify(now_ms)?;
+        if info.expires_after_ms() > MAX_EXPIRES_AFTER_MS {
+            return Err(BootstrapError::ExpiresAfterTooLong(info.expires_after_ms()));
+        }
+        Ok(Self {
+            space: info.as_space_ref().clone(),
+            agent: info.as_agent_ref().clone(),
+            signed_at_ms: info.signed_at_ms(),
+            expires_at_ms: info.expires_at_ms(),
+            encoded,
+        })
+    }
+
+    /// The space this agent info is for.
+    pub fn space(&self) -> &KitsuneSpace {
+        &self.space
+    }
+
+    /// The agent this agent info is for.
+    pub fn agent(&self) -> &KitsuneAgent {
+        &self.agent
+    }
+
+    /// When the agent signed this agent info.
+    pub fn signed_at_ms(&self) -> u64 {
+        self.signed_at_ms
+    }
+
+    /// When this agent info expires.
+    pub fn expires_at_ms(&self) -> u64 {
+        self.expires_at_ms
+    }
+
+    /// The messagepack encoded `AgentInfoSigned` as it was received.
+    pub fn encoded(&self) -> &[u8] {
+        &self.encoded
+    }
+}
+
+mod store;
+pub use store::*;
+
+mod server;
+pub use server::*;
+
+#[cfg(test)]
+pub(crate) mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+    use kitsune_p2p::agent_store::MAX_CLOCK_SKEW_MS;
+
+    #[test]
+    fn verify_agent_info() {
+        let space = space();
+        let keypair = keypair();
+        let now = now_ms().unwrap();
+
+        let good = signed_agent_info(&keypair, &space, now - 1000, 60_000);
+        let info = VerifiedAgentInfo::verify(good.clone(), now).unwrap();
+        assert_eq!(&space, info.space());
+        assert_eq!(now + 59_000, info.expires_at_ms());
+
+        // Flipping a byte of the signed agent info must be rejected.
+        let mut tampered = good.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(VerifiedAgentInfo::verify(tampered, now).is_err());
+
+        assert!(matches!(
+            VerifiedAgentInfo::verify(good.clone(), now - 1001 - MAX_CLOCK_SKEW_MS),
+            Err(BootstrapError::AgentInfo(
+                KitsuneP2pError::AgentInfoFromFuture { .. }
+            ))
+        ));
+        assert!(matches!(
+            VerifiedAgentInfo::verify(good, now + 60_000),
+            Err(BootstrapError::AgentInfo(
+                KitsuneP2pError::AgentInfoExpired { .. }
+            ))
+        ));
+
+        let long_lived = signed_agent_info(&keypair, &space, now - 1000, MAX_EXPIRES_AFTER_MS + 1);
+        assert!(matches!(
+            VerifiedAgentInfo::verify(long_lived, now),
+            Err(BootstrapError::ExpiresAfterTooLong(_))
+        ));
+    }
+}
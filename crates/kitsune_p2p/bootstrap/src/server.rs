@@ -0,0 +1,165 @@
+//! The HTTP side of the bootstrap service.
+
+use crate::*;
+use hyper::body::HttpBody;
+use hyper::Body;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// The HTTP header name for setting the op on POST requests.
+const OP_HEADER: &str = "X-Op";
+/// The header op to put a signed agent info.
+const OP_PUT: &str = "put";
+/// The header op to return our opinion of 'now' in milliseconds.
+const OP_NOW: &str = "now";
+/// The header op to return a random set of agents in a specific space.
+const OP_RANDOM: &str = "random";
+
+/// Body of the `random` op, as sent by the kitsune bootstrap client.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RandomQuery {
+    space: KitsuneSpace,
+    limit: RandomLimit,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RandomLimit(u32);
+
+/// Bind a bootstrap service to `addr` backed by `store`.
+///
+/// Returns the address actually bound, which is useful when binding port 0,
+/// and a future that serves requests until it is dropped or fails.
+pub async fn bind<S: BootstrapStore>(
+    addr: SocketAddr,
+    store: S,
+) -> BootstrapResult<(SocketAddr, impl Future<Output = BootstrapResult<()>>)> {
+    let store = Arc::new(store);
+    let make_service = hyper::service::make_service_fn(move |_| {
+        let store = store.clone();
+        async move {
+            Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |req| {
+                let store = store.clone();
+                async move { Ok::<_, std::convert::Infallible>(respond(store, req).await) }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&addr)?.serve(make_service);
+    let bound = server.local_addr();
+    Ok((
+        bound,
+        async move { server.await.map_err(BootstrapError::from) },
+    ))
+}
+
+async fn respond<S: BootstrapStore>(store: Arc<S>, req: Request<Body>) -> Response<Body> {
+    if req.method() != Method::POST {
+        return Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Body::empty())
+            .expect("static response is valid");
+    }
+    match handle(store, req).await {
+        Ok(body) => Response::new(Body::from(body)),
+        Err(e) => {
+            let status = match e {
+                BootstrapError::Io(_)
+                | BootstrapError::Hyper(_)
+                | BootstrapError::AgentInfo(KitsuneP2pError::SystemTime(_))
+                | BootstrapError::AgentInfo(KitsuneP2pError::TryFromInt(_)) => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+                BootstrapError::BodyTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+                BootstrapError::TooManySpaces | BootstrapError::SpaceFull => {
+                    StatusCode::SERVICE_UNAVAILABLE
+                }
+                _ => StatusCode::BAD_REQUEST,
+            };
+            Response::builder()
+                .status(status)
+                .body(Body::from(e.to_string()))
+                .expect("status and string body are valid")
+        }
+    }
+}
+
+/// Run the op for a single request and return the messagepack encoded output.
+async fn handle<S: BootstrapStore>(store: Arc<S>, req: Request<Body>) -> BootstrapResult<Vec<u8>> {
+    let op = req
+        .headers()
+        .get(OP_HEADER)
+        .and_then(|op| op.to_str().ok())
+        .map(|op| op.to_string());
+    let body = read_body(req.into_body()).await?;
+    let now_ms = now_ms()?;
+    let mut out = Vec::new();
+    match op.as_deref() {
+        Some(OP_PUT) => {
+            store.put(VerifiedAgentInfo::verify(body, now_ms)?, now_ms)?;
+            kitsune_p2p_types::codec::rmp_encode(&mut out, ())?;
+        }
+        Some(OP_NOW) => {
+            kitsune_p2p_types::codec::rmp_encode(&mut out, now_ms)?;
+        }
+        Some(OP_RANDOM) => {
+            let query: RandomQuery = kitsune_p2p_types::codec::rmp_decode(&mut &*body)
+                .map_err(|e| BootstrapError::Decoding(e.to_string().into_boxed_str()))?;
+            let infos: Vec<serde_bytes::ByteBuf> = store
+                .random(&query.space, query.limit.0, now_ms)?
+                .into_iter()
+                .map(|info| serde_bytes::ByteBuf::from(info.encoded().to_vec()))
+                .collect();
+            kitsune_p2p_types::codec::rmp_encode(&mut out, infos)?;
+        }
+        _ => return Err(BootstrapError::UnknownOp(op)),
+    }
+    Ok(out)
+}
+
+/// Read a request body, failing as soon as it is larger than [`MAX_BODY_BYTES`].
+async fn read_body(mut body: Body) -> BootstrapResult<Vec<u8>> {
+    if body.size_hint().lower() > MAX_BODY_BYTES as u64 {
+        return Err(BootstrapError::BodyTooLarge);
+    }
+    let mut out = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if out.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(BootstrapError::BodyTooLarge);
+        }
+        out.extend_from_slice(&chunk);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn large_bodies_are_rejected() {
+        let (addr, server) = bind(([127, 0, 0, 1], 0).into(), MemStore::new())
+            .await
+            .unwrap();
+        tokio::task::spawn(server);
+
+        let post = |body: Vec<u8>| {
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}", addr))
+                .header(OP_HEADER, OP_NOW)
+                .body(Body::from(body))
+                .unwrap();
+            hyper::Client::new().request(req)
+        };
+
+        let res = post(vec![0; MAX_BODY_BYTES]).await.unwrap();
+        assert_eq!(StatusCode::OK, res.status());
+        let res = post(vec![0; MAX_BODY_BYTES + 1]).await.unwrap();
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, res.status());
+    }
+}
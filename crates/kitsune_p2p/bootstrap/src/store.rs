@@ -0,0 +1,476 @@
+//! Storage backends for verified agent infos.
+
+use crate::*;
+use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Storage for the agent infos the bootstrap service hands out.
+///
+/// Implementations keep at most one agent info per agent per space,
+/// preferring the most recently signed one, and never return expired infos.
+pub trait BootstrapStore: 'static + Send + Sync {
+    /// Store a verified agent info unless we already hold a newer one
+    /// for the same agent in the same space.
+    ///
+    /// Fails with [`BootstrapError::TooManySpaces`] or
+    /// [`BootstrapError::SpaceFull`] if the store is at its [`StoreLimits`]
+    /// even after dropping the agent infos expired as of `now_ms`.
+    fn put(&self, info: VerifiedAgentInfo, now_ms: u64) -> BootstrapResult<()>;
+
+    /// Fetch up to `limit` unexpired agent infos for `space` in random order.
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: u32,
+        now_ms: u64,
+    ) -> BootstrapResult<Vec<VerifiedAgentInfo>>;
+}
+
+/// How much a bootstrap store will hold, so a flood of puts can't exhaust
+/// the service's memory or disk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StoreLimits {
+    /// The most spaces held at once.
+    pub max_spaces: usize,
+    /// The most agents held at once for any one space.
+    pub max_agents_per_space: usize,
+}
+
+impl Default for StoreLimits {
+    fn default() -> Self {
+        Self {
+            max_spaces: 1024,
+            max_agents_per_space: 1024,
+        }
+    }
+}
+
+type Spaces = HashMap<KitsuneSpace, HashMap<KitsuneAgent, VerifiedAgentInfo>>;
+
+/// In-memory bootstrap storage, lost when the service stops.
+#[derive(Default)]
+pub struct MemStore {
+    spaces: Mutex<Spaces>,
+    limits: StoreLimits,
+}
+
+impl MemStore {
+    /// Construct an empty in-memory store with the default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct an empty in-memory store holding at most `limits`.
+    pub fn with_limits(limits: StoreLimits) -> Self {
+        Self {
+            spaces: Mutex::new(HashMap::new()),
+            limits,
+        }
+    }
+
+    /// Insert `info` if it is newer than what we hold for its agent.
+    /// Returns whether it was inserted.
+    ///
+    /// If the store is at its limits the agent infos expired as of `now_ms`
+    /// are dropped to make room and added to `pruned`.
+    fn insert(
+        &self,
+        info: VerifiedAgentInfo,
+        now_ms: u64,
+        pruned: &mut Vec<VerifiedAgentInfo>,
+    ) -> BootstrapResult<bool> {
+        let mut spaces = self.spaces.lock();
+        if !spaces.contains_key(info.space()) && spaces.len() >= self.limits.max_spaces {
+            let held: Vec<KitsuneSpace> = spaces.keys().cloned().collect();
+            for space in held.iter() {
+                pruned.extend(prune(&mut spaces, space, now_ms));
+            }
+            if spaces.len() >= self.limits.max_spaces {
+                return Err(BootstrapError::TooManySpaces);
+            }
+        }
+
+        let agents_held = |spaces: &Spaces| spaces.get(info.space()).map(HashMap::len);
+        let is_new_agent = spaces
+            .get(info.space())
+            .map(|agents| !agents.contains_key(info.agent()))
+            .unwrap_or(true);
+        if is_new_agent && agents_held(&spaces).unwrap_or(0) >= self.limits.max_agents_per_space {
+            pruned.extend(prune(&mut spaces, info.space(), now_ms));
+            if agents_held(&spaces).unwrap_or(0) >= self.limits.max_agents_per_space {
+                return Err(BootstrapError::SpaceFull);
+            }
+        }
+
+        let agents = spaces.entry(info.space().clone()).or_default();
+        match agents.get(info.agent()) {
+            Some(held) if held.signed_at_ms() >= info.signed_at_ms() => Ok(false),
+            _ => {
+                agents.insert(info.agent().clone(), info);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Drop any expired agent infos for `space` and return them.
+    fn prune(&self, space: &KitsuneSpace, now_ms: u64) -> Vec<VerifiedAgentInfo> {
+        prune(&mut self.spaces.lock(), space, now_ms)
+    }
+
+    /// Pick up to `limit` random agent infos for `space`.
+    fn sample(&self, space: &KitsuneSpace, limit: u32) -> Vec<VerifiedAgentInfo> {
+        let spaces = self.spaces.lock();
+        match spaces.get(space) {
+            Some(agents) => {
+                let infos: Vec<&VerifiedAgentInfo> = agents.values().collect();
+                infos
+                    .choose_multiple(&mut rand::thread_rng(), limit as usize)
+                    .map(|info| (*info).clone())
+                    .collect()
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Drop any expired agent infos for `space` from `spaces` and return them.
+fn prune(spaces: &mut Spaces, space: &KitsuneSpace, now_ms: u64) -> Vec<VerifiedAgentInfo> {
+    let agents = match spaces.get_mut(space) {
+        Some(agents) => agents,
+        None => return Vec::new(),
+    };
+    let expired: Vec<KitsuneAgent> = agents
+        .values()
+        .filter(|info| info.expires_at_ms() <= now_ms)
+        .map(|info| info.agent().clone())
+        .collect();
+    let pruned = expired
+        .iter()
+        .filter_map(|agent| agents.remove(agent))
+        .collect();
+    if agents.is_empty() {
+        spaces.remove(space);
+    }
+    pruned
+}
+
+impl BootstrapStore for MemStore {
+    fn put(&self, info: VerifiedAgentInfo, now_ms: u64) -> BootstrapResult<()> {
+        self.insert(info, now_ms, &mut Vec::new())?;
+        Ok(())
+    }
+
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: u32,
+        now_ms: u64,
+    ) -> BootstrapResult<Vec<VerifiedAgentInfo>> {
+        self.prune(space, now_ms);
+        Ok(self.sample(space, limit))
+    }
+}
+
+/// On-disk bootstrap storage.
+///
+/// Each agent info is written as `<dir>/<hex space>/<hex agent>` holding the
+/// encoded `AgentInfoSigned` exactly as it was put, and served from memory.
+pub struct FileStore {
+    dir: PathBuf,
+    mem: MemStore,
+    /// Held across each change to `mem` and the matching file writes and
+    /// removals, so the files always end up agreeing with `mem`.
+    write_lock: Mutex<()>,
+    /// Makes the name of each temporary file written unique.
+    next_tmp: AtomicU64,
+}
+
+impl FileStore {
+    /// Open (creating if needed) a store in `dir` with the default limits.
+    pub fn open<P: Into<PathBuf>>(dir: P) -> BootstrapResult<Self> {
+        Self::open_with_limits(dir, StoreLimits::default())
+    }
+
+    /// Open (creating if needed) a store in `dir` holding at most `limits`,
+    /// loading every agent info that still verifies and fits.
+    ///
+    /// The files of agent infos which fail to decode or verify are removed.
+    /// Those which don't fit in `limits` are kept but not served.
+    pub fn open_with_limits<P: Into<PathBuf>>(
+        dir: P,
+        limits: StoreLimits,
+    ) -> BootstrapResult<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let now_ms = now_ms()?;
+        let mem = MemStore::with_limits(limits);
+        for space_dir in std::fs::read_dir(&dir)? {
+            let space_dir = space_dir?.path();
+            if !space_dir.is_dir() {
+                continue;
+            }
+            for file in std::fs::read_dir(&space_dir)? {
+                let file = file?.path();
+                match VerifiedAgentInfo::verify(std::fs::read(&file)?, now_ms) {
+                    Ok(info) => {
+                        // Only over the limits, which the files may be
+                        // within again once they are raised.
+                        let _ = mem.insert(info, now_ms, &mut Vec::new());
+                    }
+                    Err(_) => std::fs::remove_file(&file)?,
+                }
+            }
+        }
+        Ok(Self {
+            dir,
+            mem,
+            write_lock: Mutex::new(()),
+            next_tmp: AtomicU64::new(0),
+        })
+    }
+
+    fn remove_files(&self, infos: Vec<VerifiedAgentInfo>) -> BootstrapResult<()> {
+        for info in infos {
+            match std::fs::remove_file(self.path_for(info.space(), info.agent())) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn path_for(&self, space: &KitsuneSpace, agent: &KitsuneAgent) -> PathBuf {
+        self.dir
+            .join(hex::encode(&space.0))
+            .join(hex::encode(&agent.0))
+    }
+}
+
+impl BootstrapStore for FileStore {
+    fn put(&self, info: VerifiedAgentInfo, now_ms: u64) -> BootstrapResult<()> {
+        let path = self.path_for(info.space(), info.agent());
+        let encoded = info.encoded().to_vec();
+        let _write = self.write_lock.lock();
+        let mut pruned = Vec::new();
+        let inserted = self.mem.insert(info, now_ms, &mut pruned);
+        self.remove_files(pruned)?;
+        if inserted? {
+            if let Some(space_dir) = path.parent() {
+                std::fs::create_dir_all(space_dir)?;
+            }
+            // Write then rename so a crash never leaves a truncated file.
+            let tmp = path.with_extension(format!(
+                "{}.{}.tmp",
+                std::process::id(),
+                self.next_tmp.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&tmp, encoded)?;
+            std::fs::rename(&tmp, &path)?;
+        }
+        Ok(())
+    }
+
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: u32,
+        now_ms: u64,
+    ) -> BootstrapResult<Vec<VerifiedAgentInfo>> {
+        {
+            let _write = self.write_lock.lock();
+            self.remove_files(self.mem.prune(space, now_ms))?;
+        }
+        Ok(self.mem.sample(space, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+
+    fn check_store<S: BootstrapStore>(store: &S) {
+        let space = space();
+        let now = now_ms().unwrap();
+
+        let alice = keypair();
+        let alice_old = verified(signed_agent_info(&alice, &space, now - 2000, 60_000));
+        let alice_new = verified(signed_agent_info(&alice, &space, now - 1000, 60_000));
+        let bob = verified(signed_agent_info(&keypair(), &space, now - 1000, 60_000));
+
+        store.put(alice_new.clone(), now).unwrap();
+        // An older agent info never replaces a newer one.
+        store.put(alice_old, now).unwrap();
+        store.put(bob.clone(), now).unwrap();
+
+        let mut expected = vec![alice_new.encoded().to_vec(), bob.encoded().to_vec()];
+        let mut random: Vec<Vec<u8>> = store
+            .random(&space, 16, now)
+            .unwrap()
+            .into_iter()
+            .map(|info| info.encoded().to_vec())
+            .collect();
+        expected.sort();
+        random.sort();
+        assert_eq!(expected, random);
+
+        assert_eq!(1, store.random(&space, 1, now).unwrap().len());
+
+        // Once they expire nothing is returned.
+        assert!(store.random(&space, 16, now + 60_000).unwrap().is_empty());
+        assert!(store.random(&space, 16, now).unwrap().is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn mem_store() {
+        check_store(&MemStore::new());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn file_store() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        check_store(&FileStore::open(dir.path()).unwrap());
+
+        let space = space();
+        let now = now_ms().unwrap();
+        let alice = verified(signed_agent_info(&keypair(), &space, now - 1000, 60_000));
+        let bob = verified(signed_agent_info(&keypair(), &space, now - 1000, 1500));
+        {
+            let store = FileStore::open(dir.path()).unwrap();
+            store.put(alice.clone(), now).unwrap();
+            store.put(bob, now).unwrap();
+        }
+        tokio::time::delay_for(std::time::Duration::from_millis(600)).await;
+
+        // Reopening only loads the agent infos that have not expired.
+        let store = FileStore::open(dir.path()).unwrap();
+        assert_eq!(vec![alice], store.random(&space, 16, now).unwrap());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn file_store_only_removes_bad_files() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        let space = space();
+        let now = now_ms().unwrap();
+        let infos: Vec<VerifiedAgentInfo> = (0..3)
+            .map(|_| verified(signed_agent_info(&keypair(), &space, now - 1000, 60_000)))
+            .collect();
+        let garbage = dir.path().join(hex::encode(&space.0)).join("garbage");
+        {
+            let store = FileStore::open(dir.path()).unwrap();
+            for info in infos.iter() {
+                store.put(info.clone(), now).unwrap();
+            }
+            std::fs::write(&garbage, b"not an agent info").unwrap();
+        }
+
+        // The agent infos over the limits are kept for when they are raised.
+        let limits = StoreLimits {
+            max_spaces: 1,
+            max_agents_per_space: 2,
+        };
+        let store = FileStore::open_with_limits(dir.path(), limits).unwrap();
+        assert_eq!(2, store.random(&space, 16, now).unwrap().len());
+        assert!(!garbage.exists());
+        for info in infos.iter() {
+            assert!(store.path_for(&space, info.agent()).exists());
+        }
+        let store = FileStore::open(dir.path()).unwrap();
+        assert_eq!(3, store.random(&space, 16, now).unwrap().len());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn file_store_concurrent_puts_keep_the_newest() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        let store = std::sync::Arc::new(FileStore::open(dir.path()).unwrap());
+        let space = space();
+        let alice = keypair();
+        let now = now_ms().unwrap();
+        let infos: Vec<VerifiedAgentInfo> = (0..16)
+            .map(|i| verified(signed_agent_info(&alice, &space, now - 1000 + i, 60_000)))
+            .collect();
+        let newest = infos.last().unwrap().clone();
+        let threads: Vec<_> = infos
+            .into_iter()
+            .map(|info| {
+                let store = store.clone();
+                std::thread::spawn(move || store.put(info, now).unwrap())
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let path = store.path_for(&space, newest.agent());
+        assert_eq!(newest.encoded(), &std::fs::read(&path).unwrap()[..]);
+        let files = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(1, files);
+    }
+
+    fn check_limits<S: BootstrapStore>(store: &S) {
+        let now = now_ms().unwrap();
+        let agent_info = |space: &KitsuneSpace, expires_after_ms| {
+            verified(signed_agent_info(
+                &keypair(),
+                space,
+                now - 1000,
+                expires_after_ms,
+            ))
+        };
+
+        let full = space();
+        let short_lived = agent_info(&full, 30_000);
+        store.put(short_lived.clone(), now).unwrap();
+        store.put(agent_info(&full, 60_000), now).unwrap();
+        assert!(matches!(
+            store.put(agent_info(&full, 60_000), now),
+            Err(BootstrapError::SpaceFull)
+        ));
+        // Agents already held can still update their agent info.
+        let alice = keypair();
+        let empty = space();
+        store
+            .put(
+                verified(signed_agent_info(&alice, &empty, now - 2000, 60_000)),
+                now,
+            )
+            .unwrap();
+        store
+            .put(
+                verified(signed_agent_info(&alice, &empty, now - 1000, 60_000)),
+                now,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            store.put(agent_info(&space(), 60_000), now),
+            Err(BootstrapError::TooManySpaces)
+        ));
+
+        // Expired agent infos make room for new ones.
+        let later = short_lived.expires_at_ms();
+        store.put(agent_info(&full, 60_000), later).unwrap();
+        assert_eq!(2, store.random(&full, 16, later).unwrap().len());
+    }
+
+    fn limits() -> StoreLimits {
+        StoreLimits {
+            max_spaces: 2,
+            max_agents_per_space: 2,
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn mem_store_limits() {
+        check_limits(&MemStore::with_limits(limits()));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn file_store_limits() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        check_limits(&FileStore::open_with_limits(dir.path(), limits()).unwrap());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::*;
+use kitsune_p2p::agent_store::AgentInfo;
+use kitsune_p2p::KitsuneBinType;
+use kitsune_p2p::KitsuneSignature;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
+
+pub fn space() -> KitsuneSpace {
+    KitsuneSpace::new(rand::random::<[u8; 32]>().to_vec())
+}
+
+pub fn keypair() -> Ed25519KeyPair {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+}
+
+/// Encoded `AgentInfoSigned` for `keypair` in `space`.
+pub fn signed_agent_info(
+    keypair: &Ed25519KeyPair,
+    space: &KitsuneSpace,
+    signed_at_ms: u64,
+    expires_after_ms: u64,
+) -> Vec<u8> {
+    let agent = KitsuneAgent::new(keypair.public_key().as_ref().to_vec());
+    let agent_info = AgentInfo::new(
+        space.clone(),
+        agent.clone(),
+        Vec::new(),
+        signed_at_ms,
+        expires_after_ms,
+        kitsune_p2p_types::dht_arc::DhtArc::new(0, u32::MAX),
+    );
+    let mut data = Vec::new();
+    kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+    let signature = KitsuneSignature(keypair.sign(&data).as_ref().to_vec());
+    let agent_info_signed = AgentInfoSigned::try_new(agent, signature, data).unwrap();
+    let mut encoded = Vec::new();
+    kitsune_p2p_types::codec::rmp_encode(&mut encoded, &agent_info_signed).unwrap();
+    encoded
+}
+
+pub fn verified(encoded: Vec<u8>) -> VerifiedAgentInfo {
+    VerifiedAgentInfo::verify(encoded, now_ms().unwrap()).unwrap()
+}
@@ -4,6 +4,12 @@ use url2::Url2;
 pub const BOOTSTRAP_SERVICE_DEFAULT: &str = "https://bootstrap.holo.host";
 /// The default development bootstrap service url.
 pub const BOOTSTRAP_SERVICE_DEV: &str = "https://bootstrap-dev.holohost.workers.dev";
+/// The default number of peers each agent sizes its dht arc to overlap with.
+pub const REDUNDANCY_TARGET_DEFAULT: u32 = 5;
+
+fn default_redundancy_target() -> u32 {
+    REDUNDANCY_TARGET_DEFAULT
+}
 
 /// Configure the kitsune actor
 #[non_exhaustive]
@@ -13,6 +19,16 @@ pub struct KitsuneP2pConfig {
     pub transport_pool: Vec<TransportConfig>,
     /// The service used for peers to discover each before they are peers.
     pub bootstrap_service: Option<Url2>,
+    /// How many of the nearest known peers each agent's dht arc
+    /// should reach. Agents hold the whole dht until they know
+    /// of at least this many peers.
+    #[serde(default = "default_redundancy_target")]
+    pub redundancy_target: u32,
+    /// Announce our agents to, and discover peers from, other nodes
+    /// on the local network, e.g. where there is no bootstrap service.
+    /// Default: None = disabled.
+    #[serde(default)]
+    pub lan_discovery: Option<LanDiscoveryConfig>,
 }
 
 impl Default for KitsuneP2pConfig {
@@ -20,6 +36,51 @@ impl Default for KitsuneP2pConfig {
         Self {
             transport_pool: Vec::new(),
             bootstrap_service: None,
+            redundancy_target: REDUNDANCY_TARGET_DEFAULT,
+            lan_discovery: None,
+        }
+    }
+}
+
+/// Configure LAN discovery, where each space announces the signed agent
+/// infos of its joined agents by UDP multicast, and stores the ones
+/// announced by other nodes.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct LanDiscoveryConfig {
+    /// The multicast group and port to announce to and listen on.
+    /// Default: "239.255.75.80:45111".
+    #[serde(default = "default_lan_multicast_addr")]
+    pub multicast_addr: std::net::SocketAddrV4,
+
+    /// The ip of the local network interface to announce and listen on.
+    /// Default: "0.0.0.0" = let the OS choose.
+    #[serde(default = "default_lan_interface")]
+    pub interface: std::net::Ipv4Addr,
+
+    /// How often to announce our agents, in milliseconds.
+    /// Default: 5000.
+    #[serde(default = "default_lan_announce_interval_ms")]
+    pub announce_interval_ms: u64,
+}
+
+fn default_lan_multicast_addr() -> std::net::SocketAddrV4 {
+    std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(239, 255, 75, 80), 45111)
+}
+
+fn default_lan_interface() -> std::net::Ipv4Addr {
+    std::net::Ipv4Addr::UNSPECIFIED
+}
+
+fn default_lan_announce_interval_ms() -> u64 {
+    5000
+}
+
+impl Default for LanDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            multicast_addr: default_lan_multicast_addr(),
+            interface: default_lan_interface(),
+            announce_interval_ms: default_lan_announce_interval_ms(),
         }
     }
 }
@@ -48,6 +109,24 @@ pub enum TransportConfig {
         /// Default: None = use NIC port.
         override_port: Option<u16>,
     },
+    /// A transport that uses TLS over TCP,
+    /// for networks that block the UDP the QUIC transport needs
+    Tcp {
+        /// To which network interface / port should we bind?
+        /// Default: "kitsune-tcp://0.0.0.0:0".
+        bind_to: Option<Url2>,
+
+        /// If you have port-forwarding set up,
+        /// or wish to apply a vanity domain name,
+        /// you may need to override the local NIC ip.
+        /// Default: None = use NIC ip.
+        override_host: Option<String>,
+
+        /// If you have port-forwarding set up,
+        /// you may need to override the local NIC port.
+        /// Default: None = use NIC port.
+        override_port: Option<u16>,
+    },
     /// A transport that tls tunnels through a sub-transport (ALPN kitsune-proxy/0)
     Proxy {
         /// The 'Proxy' transport is a wrapper around a sub-transport
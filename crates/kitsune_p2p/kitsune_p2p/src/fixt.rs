@@ -1,14 +1,19 @@
 //! Fixturator definitions for kitsune_p2p.
 
+use crate::agent_store::now_ms;
 use crate::agent_store::AgentInfo;
 use crate::agent_store::AgentInfoSigned;
 use crate::agent_store::Urls;
 use crate::dependencies::url2;
+use crate::dht_arc::DhtArc;
+use crate::spawn::AGENT_INFO_EXPIRES_AFTER_MS;
 use crate::KitsuneAgent;
 use crate::KitsuneBinType;
 use crate::KitsuneSignature;
 use crate::KitsuneSpace;
 use ::fixt::prelude::*;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
 use url2::url2;
 
 fixturator!(
@@ -51,38 +56,88 @@ fixturator!(
     from SixtyFourBytes;
 );
 
+fixturator!(
+    DhtArc;
+    constructor fn new(U32, U32);
+);
+
 fixturator!(
     AgentInfo;
-    constructor fn new(KitsuneSpace, KitsuneAgent, Urls, U64, U64);
+    constructor fn new(KitsuneSpace, KitsuneAgent, Urls, U64, U64, DhtArc);
 );
 
 fixturator!(
     AgentInfoSigned;
-    curve Empty {
-        let mut data = Vec::new();
-        kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo, Empty)).unwrap();
-        AgentInfoSigned::try_new(
-            fixt!(KitsuneAgent, Empty),
-            fixt!(KitsuneSignature, Empty),
-            data,
-        ).unwrap()
-    };
-    curve Unpredictable {
-        let mut data = Vec::new();
-        kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo)).unwrap();
-        AgentInfoSigned::try_new(
-            fixt!(KitsuneAgent),
-            fixt!(KitsuneSignature),
-            data,
-        ).unwrap()
-    };
-    curve Predictable {
-        let mut data = Vec::new();
-        kitsune_p2p_types::codec::rmp_encode(&mut data, &fixt!(AgentInfo, Predictable)).unwrap();
-        AgentInfoSigned::try_new(
-            fixt!(KitsuneAgent, Predictable),
-            fixt!(KitsuneSignature, Predictable),
-            data,
-        ).unwrap()
-    };
+    curve Empty agent_info_signed_with_seed(
+        [0; 32],
+        fixt!(KitsuneSpace, Empty),
+        fixt!(Urls, Empty),
+        now_ms().unwrap(),
+        AGENT_INFO_EXPIRES_AFTER_MS,
+        fixt!(DhtArc, Empty),
+    );
+    curve Unpredictable agent_info_signed_with_seed(
+        rand::random(),
+        fixt!(KitsuneSpace),
+        fixt!(Urls),
+        now_ms().unwrap(),
+        AGENT_INFO_EXPIRES_AFTER_MS,
+        fixt!(DhtArc),
+    );
+    curve Predictable agent_info_signed_with_seed(
+        [0; 32],
+        fixt!(KitsuneSpace, Predictable),
+        fixt!(Urls, Predictable),
+        now_ms().unwrap(),
+        AGENT_INFO_EXPIRES_AFTER_MS,
+        fixt!(DhtArc, Predictable),
+    );
 );
+
+/// Sign an agent info in `space` as the agent whose ed25519 keypair is
+/// derived from `seed`. The same seed always gives the same agent.
+pub fn agent_info_signed_with_seed(
+    seed: [u8; 32],
+    space: KitsuneSpace,
+    urls: Urls,
+    signed_at_ms: u64,
+    expires_after_ms: u64,
+    dht_arc: DhtArc,
+) -> AgentInfoSigned {
+    let keypair = Ed25519KeyPair::from_seed_unchecked(&seed).unwrap();
+    let agent = KitsuneAgent::new(keypair.public_key().as_ref().to_vec());
+    let agent_info = AgentInfo::new(
+        space,
+        agent.clone(),
+        urls,
+        signed_at_ms,
+        expires_after_ms,
+        dht_arc,
+    );
+    let mut data = Vec::new();
+    kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+    let signature = KitsuneSignature(keypair.sign(&data).as_ref().to_vec());
+    AgentInfoSigned::try_new(agent, signature, data).unwrap()
+}
+
+/// An agent info as a malicious peer could send it over the wire,
+/// with a signature that does not verify.
+/// `AgentInfoSigned::try_new` refuses to build these, but deserializing does not.
+pub fn forged_agent_info_signed() -> AgentInfoSigned {
+    #[derive(serde::Serialize)]
+    struct Forged {
+        agent: KitsuneAgent,
+        signature: KitsuneSignature,
+        #[serde(with = "serde_bytes")]
+        agent_info: Vec<u8>,
+    }
+    let agent_info_signed = fixt!(AgentInfoSigned);
+    let forged = Forged {
+        agent: agent_info_signed.as_agent_ref().clone(),
+        signature: fixt!(KitsuneSignature),
+        agent_info: agent_info_signed.as_agent_info_ref().to_vec(),
+    };
+    let mut data = Vec::new();
+    kitsune_p2p_types::codec::rmp_encode(&mut data, &forged).unwrap();
+    kitsune_p2p_types::codec::rmp_decode(&mut &*data).unwrap()
+}
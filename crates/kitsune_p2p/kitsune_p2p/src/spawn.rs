@@ -2,6 +2,7 @@ use crate::actor::*;
 use crate::event::*;
 
 mod actor;
+pub(crate) use actor::AGENT_INFO_EXPIRES_AFTER_MS;
 use actor::*;
 
 /// Spawn a new KitsuneP2p actor.
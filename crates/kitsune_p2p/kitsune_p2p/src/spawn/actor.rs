@@ -19,9 +19,12 @@ use std::sync::Arc;
 mod bootstrap;
 mod discover;
 mod gossip;
+mod lan;
+mod metrics;
 mod space;
 use ghost_actor::dependencies::must_future;
 use ghost_actor::dependencies::tracing;
+pub(crate) use space::AGENT_INFO_EXPIRES_AFTER_MS;
 use space::*;
 
 ghost_actor::ghost_chan! {
@@ -66,6 +69,22 @@ fn build_transport(
                     .set_override_port(override_port);
                 Ok(kitsune_p2p_transport_quic::spawn_transport_listener_quic(sub_conf).await?)
             }
+            TransportConfig::Tcp {
+                bind_to,
+                override_host,
+                override_port,
+            } => {
+                // present the same cert as the proxy layer
+                let sub_conf = kitsune_p2p_transport_tcp::ConfigListenerTcp::default()
+                    .set_bind_to(bind_to)
+                    .set_override_host(override_host)
+                    .set_override_port(override_port)
+                    .set_tls(Some((
+                        tls_config.cert.clone(),
+                        tls_config.cert_priv_key.clone(),
+                    )));
+                Ok(kitsune_p2p_transport_tcp::spawn_transport_listener_tcp(sub_conf).await?)
+            }
             TransportConfig::Proxy {
                 sub_transport,
                 proxy_config,
@@ -335,13 +354,17 @@ async fn agent_info_query(
         } else {
             Ok(vec![])
         }
-    } else if let Some(_by_basis_arc) = by_basis_arc {
+    } else if let Some((_, dht_arc)) = by_basis_arc {
+        // only the peers located within the requested arc
         Ok(evt_sender
             .query_agent_info_signed(QueryAgentInfoSignedEvt {
                 space,
                 agent: to_agent,
             })
-            .await?)
+            .await?
+            .into_iter()
+            .filter(|ai| dht_arc.contains(ai.as_agent_ref().get_loc()))
+            .collect())
     } else {
         Err("must specify by_agent or by_basis_arc".into())
     }
@@ -228,6 +228,7 @@ mod tests {
             urls,
             (millis - 100).try_into().unwrap(),
             AGENT_INFO_EXPIRES_AFTER_MS,
+            fixt!(DhtArc),
         );
         let mut data = Vec::new();
         kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
@@ -250,12 +251,122 @@ mod tests {
         // We should get back an error if we don't have a good signature.
         assert!(super::put(
             Some(url2::url2!("{}", crate::config::BOOTSTRAP_SERVICE_DEV)),
-            fixt!(AgentInfoSigned)
+            forged_agent_info_signed()
         )
         .await
         .is_err());
     }
 
+    /// Serve `store` from a bootstrap service on a free localhost port.
+    async fn local_bootstrap<S: kitsune_p2p_bootstrap::BootstrapStore>(store: S) -> Url2 {
+        let (addr, server) = kitsune_p2p_bootstrap::bind(([127, 0, 0, 1], 0).into(), store)
+            .await
+            .unwrap();
+        tokio::task::spawn(server);
+        url2::url2!("http://{}", addr)
+    }
+
+    /// An agent info for a fresh agent in `space`, signed by that agent.
+    async fn signed_agent_info(
+        space: &KitsuneSpace,
+        signed_at_ms: u64,
+        expires_after_ms: u64,
+    ) -> AgentInfoSigned {
+        let keypair = sign_ed25519_keypair_new_from_entropy().await.unwrap();
+        let agent = KitsuneAgent::new((*keypair.pub_key.0).clone());
+        let agent_info = AgentInfo::new(
+            space.clone(),
+            agent.clone(),
+            fixt!(Urls),
+            signed_at_ms,
+            expires_after_ms,
+            fixt!(DhtArc),
+        );
+        let mut data = Vec::new();
+        kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+        let signature = keypair.sign(Arc::new(data.clone())).await.unwrap();
+        AgentInfoSigned::try_new(agent, KitsuneSignature((*signature.0).clone()), data).unwrap()
+    }
+
+    /// Run the client against a local bootstrap service.
+    async fn check_local_bootstrap(url: Url2) {
+        let space = fixt!(KitsuneSpace, Unpredictable);
+        let local_now = local_now().unwrap();
+
+        let remote_now = super::now(Some(url.clone())).await.unwrap();
+        assert!(remote_now >= local_now && remote_now - local_now < 5000);
+
+        let mut expected = Vec::new();
+        for _ in 0..2 {
+            let agent_info_signed =
+                signed_agent_info(&space, remote_now - 100, AGENT_INFO_EXPIRES_AFTER_MS).await;
+            super::put(Some(url.clone()), agent_info_signed.clone())
+                .await
+                .unwrap();
+            expected.push(agent_info_signed);
+        }
+
+        // We should get back an error if we don't have a good signature.
+        assert!(super::put(Some(url.clone()), forged_agent_info_signed())
+            .await
+            .is_err());
+
+        // Expired and future agent infos are rejected.
+        let expired = signed_agent_info(&space, remote_now - 2000, 1000).await;
+        assert!(super::put(Some(url.clone()), expired).await.is_err());
+        let future = signed_agent_info(&space, remote_now + MAX_CLOCK_SKEW_MS + 60_000, 1000).await;
+        assert!(super::put(Some(url.clone()), future).await.is_err());
+
+        let mut random = super::random(
+            Some(url.clone()),
+            super::RandomQuery {
+                space: Arc::new(space.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        expected.sort();
+        random.sort();
+        assert_eq!(expected, random);
+
+        let random_single = super::random(
+            Some(url.clone()),
+            super::RandomQuery {
+                space: Arc::new(space.clone()),
+                limit: 1.into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, random_single.len());
+        assert!(expected.contains(&random_single[0]));
+
+        // Other spaces are empty.
+        let random_other = super::random(
+            Some(url),
+            super::RandomQuery {
+                space: Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(random_other.is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_local_bootstrap_mem() {
+        check_local_bootstrap(local_bootstrap(kitsune_p2p_bootstrap::MemStore::new()).await).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_local_bootstrap_file() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        let store = kitsune_p2p_bootstrap::FileStore::open(dir.path()).unwrap();
+        check_local_bootstrap(local_bootstrap(store).await).await;
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn test_now() {
         let local_now = std::time::SystemTime::now();
@@ -313,6 +424,7 @@ mod tests {
                 fixt!(Urls),
                 now,
                 AGENT_INFO_EXPIRES_AFTER_MS,
+                fixt!(DhtArc),
             );
             let mut data = Vec::new();
             kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
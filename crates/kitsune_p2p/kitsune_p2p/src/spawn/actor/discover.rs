@@ -191,8 +191,7 @@ pub(crate) fn message_neighborhood<T, F>(
     target_node_count: u8,
     stage_1_timeout_if_any_ms: u64,
     stage_2_timeout_even_if_none_ms: u64,
-    // ignored while full-sync
-    _basis: Arc<KitsuneBasis>,
+    basis: Arc<KitsuneBasis>,
     payload: wire::Wire,
     accept_result_cb: F,
 ) -> MustBoxFuture<'static, Vec<T>>
@@ -240,7 +239,7 @@ where
             if let Ok(nodes) = get_5_or_less_non_local_agents_near_basis(
                 space.clone(),
                 from_agent.clone(),
-                _basis.clone(),
+                basis.clone(),
                 i_s.clone(),
                 evt_sender.clone(),
                 bootstrap_service.clone(),
@@ -260,25 +259,34 @@ where
                         let accept_result_cb = accept_result_cb.clone();
                         let out = out.clone();
                         tokio::task::spawn(async move {
-                            let (_, mut write, read) = fut.await?;
-                            match &mut payload {
+                            let kind = match &mut payload {
                                 wire::Wire::Notify(n) => {
                                     n.to_agent = to_agent.clone();
+                                    "notify"
                                 }
                                 wire::Wire::Call(c) => {
                                     c.to_agent = to_agent.clone();
+                                    "call"
                                 }
                                 _ => panic!("cannot message {:?}", payload),
+                            };
+                            let res = async move {
+                                let (_, mut write, read) = fut.await?;
+                                let payload = payload.encode_vec()?;
+                                write.write_and_close(payload).await?;
+                                let res = read.read_to_end().await;
+                                let (_, res) = wire::Wire::decode_ref(&res)?;
+                                match accept_result_cb(to_agent, res) {
+                                    Ok(res) => {
+                                        out.lock().await.push(res);
+                                        KitsuneP2pResult::Ok(())
+                                    }
+                                    Err(()) => Err(format!("unexpected {} response", kind).into()),
+                                }
                             }
-                            let payload = payload.encode_vec()?;
-                            write.write_and_close(payload).await?;
-                            let res = read.read_to_end().await;
-                            let (_, res) = wire::Wire::decode_ref(&res)?;
-                            if let Ok(res) = accept_result_cb(to_agent, res) {
-                                out.lock().await.push(res);
-                            }
-
-                            KitsuneP2pResult::Ok(())
+                            .await;
+                            super::metrics::record_rpc(kind, &res);
+                            res
                         });
                     }
                 }
@@ -309,8 +317,7 @@ where
 pub(crate) fn get_5_or_less_non_local_agents_near_basis(
     space: Arc<KitsuneSpace>,
     from_agent: Arc<KitsuneAgent>,
-    // ignored while full-sync
-    _basis: Arc<KitsuneBasis>,
+    basis: Arc<KitsuneBasis>,
     i_s: ghost_actor::GhostSender<SpaceInternal>,
     evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     bootstrap_service: Option<url2::Url2>,
@@ -318,24 +325,28 @@ pub(crate) fn get_5_or_less_non_local_agents_near_basis(
     async move {
         let mut out = HashSet::new();
 
-        if let Ok(mut list) = evt_sender
+        if let Ok(list) = evt_sender
             .query_agent_info_signed(QueryAgentInfoSignedEvt {
                 space: space.clone(),
                 agent: from_agent.clone(),
             })
             .await
         {
+            // prefer the peers that claim to hold the basis
+            let (mut near, mut far): (Vec<_>, Vec<_>) = list
+                .iter()
+                .filter_map(|item| AgentInfo::try_from(item).ok())
+                .partition(|info| info.dht_arc().contains(basis.get_loc()));
             // randomize the results
-            rand::seq::SliceRandom::shuffle(&mut list[..], &mut rand::thread_rng());
-            for item in list {
-                if let Ok(info) = AgentInfo::try_from(&item) {
-                    if let Ok(is_local) = i_s
-                        .is_agent_local(Arc::new(info.as_agent_ref().clone()))
-                        .await
-                    {
-                        if !is_local {
-                            out.insert(info);
-                        }
+            rand::seq::SliceRandom::shuffle(&mut near[..], &mut rand::thread_rng());
+            rand::seq::SliceRandom::shuffle(&mut far[..], &mut rand::thread_rng());
+            for info in near.into_iter().chain(far) {
+                if let Ok(is_local) = i_s
+                    .is_agent_local(Arc::new(info.as_agent_ref().clone()))
+                    .await
+                {
+                    if !is_local {
+                        out.insert(info);
                     }
                 }
                 if out.len() >= 5 {
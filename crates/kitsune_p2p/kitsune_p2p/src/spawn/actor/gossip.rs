@@ -1,5 +1,8 @@
-//! This is a temporary quick-hack gossip module for use with the
-//! in-memory / full-sync / non-sharded networking module
+//! Sharded gossip module.
+//!
+//! Each agent holds the ops in its own [DhtArc].
+//! Agents only gossip with agents whose arcs overlap with theirs
+//! and only send each other the ops that fall in the receiver's arc.
 
 use crate::types::actor::KitsuneP2pResult;
 use crate::types::gossip::*;
@@ -71,9 +74,13 @@ async fn gossip_loop(
 
 struct GossipData {
     evt_send: futures::channel::mpsc::Sender<GossipEvent>,
-    pending_gossip_list: Vec<(Arc<KitsuneAgent>, Arc<KitsuneAgent>)>,
+    pending_gossip_list: Vec<(AgentArc, AgentArc)>,
 }
 
+/// The op hashes and agent info an agent holds
+type OpHashSet = HashSet<Arc<KitsuneOpHash>>;
+type AgentHashSet = HashSet<(Arc<KitsuneAgent>, u64)>;
+
 impl GossipData {
     pub fn new(evt_send: futures::channel::mpsc::Sender<GossipEvent>) -> Self {
         Self {
@@ -93,63 +100,78 @@ impl GossipData {
 
     async fn fetch_pending_gossip_list(&mut self) -> KitsuneP2pResult<()> {
         let (local_agents, remote_agents) = self.evt_send.list_neighbor_agents().await?;
-        // super naive gossip just processes all combinations
-        // also causes duplication because it runs pairs from both sides
-        for (i, a1) in local_agents.iter().enumerate() {
-            for a2 in local_agents.iter().skip(i) {
-                // at the very least, avoid gossiping with ourselves
-                if a1 != a2 {
-                    self.pending_gossip_list.push((a1.clone(), a2.clone()));
-                }
-            }
-            for a2 in remote_agents.iter() {
-                self.pending_gossip_list.push((a1.clone(), a2.clone()));
-            }
-        }
+        self.pending_gossip_list = gossip_pairs(&local_agents, &remote_agents);
         Ok(())
     }
 
+    /// Fetch the op hashes `to_agent` holds in `dht_arc`
+    async fn req_op_hashes_in_arc(
+        &mut self,
+        from_agent: Arc<KitsuneAgent>,
+        to_agent: Arc<KitsuneAgent>,
+        dht_arc: DhtArc,
+    ) -> KitsuneP2pResult<(OpHashSet, AgentHashSet)> {
+        let (op_hashes, agent_info) = self
+            .evt_send
+            .req_op_hashes(ReqOpHashesEvt::new(
+                from_agent,
+                to_agent,
+                dht_arc,
+                i64::MIN,
+                i64::MAX,
+            ))
+            .await?;
+        Ok((
+            HashSet::from_iter(op_hashes),
+            HashSet::from_iter(agent_info),
+        ))
+    }
+
     #[tracing::instrument(skip(self))]
     async fn process_next_gossip(&mut self) -> KitsuneP2pResult<()> {
         // !is_empty() checked above in take_action
-        let (from_agent, to_agent) = self.pending_gossip_list.remove(0);
+        let ((from_agent, from_arc), (to_agent, to_arc)) = self.pending_gossip_list.remove(0);
         let span = tracing::debug_span!("next_gossip", ?from_agent, ?to_agent);
 
-        // required so from_iters below know the build_hasher type
-        type S = HashSet<Arc<KitsuneOpHash>>;
-        type A = HashSet<(Arc<KitsuneAgent>, u64)>;
-
-        // we'll just fetch all with no constraints for now
+        // what each agent holds in to_agent's arc
+        // (from not to because we're initiating)
         let (op_hashes_from, agent_info_from) = self
-            .evt_send
-            .req_op_hashes(ReqOpHashesEvt::new(
-                from_agent.clone(), // from not to because we're initiating
-                from_agent.clone(),
-                DhtArc::new(0, u32::MAX),
-                i64::MIN,
-                i64::MAX,
-            ))
+            .req_op_hashes_in_arc(from_agent.clone(), from_agent.clone(), to_arc)
+            .await?;
+        let (op_hashes_to, agent_info_to) = self
+            .req_op_hashes_in_arc(from_agent.clone(), to_agent.clone(), to_arc)
             .await?;
-        let op_hashes_from: S = HashSet::from_iter(op_hashes_from);
-        let agent_info_from: A = HashSet::from_iter(agent_info_from);
         span.in_scope(|| {
+            tracing::debug!(?to_arc);
             tracing::debug!(from_has_len = ?op_hashes_from.len());
+            tracing::debug!(to_has_len = ?op_hashes_to.len());
         });
 
-        // we'll just fetch all with no constraints for now
-        let (op_hashes_to, agent_info_to) = self
-            .evt_send
-            .req_op_hashes(ReqOpHashesEvt::new(
-                from_agent.clone(),
-                to_agent.clone(),
-                DhtArc::new(0, u32::MAX),
-                i64::MIN,
-                i64::MAX,
-            ))
+        // values that from_agent has, and to_agent needs
+        let to_needs = op_hashes_from
+            .difference(&op_hashes_to)
+            .cloned()
+            .collect::<Vec<_>>();
+        let to_needs_agents = agent_info_from
+            .difference(&agent_info_to)
+            .cloned()
+            .map(|(ai, _)| ai)
+            .collect::<Vec<_>>();
+        span.in_scope(|| {
+            tracing::debug!(?to_needs_agents);
+            tracing::debug!(to_needs_len = ?to_needs.len());
+        });
+
+        // what each agent holds in from_agent's arc
+        let (op_hashes_from, _) = self
+            .req_op_hashes_in_arc(from_agent.clone(), from_agent.clone(), from_arc)
+            .await?;
+        let (op_hashes_to, _) = self
+            .req_op_hashes_in_arc(from_agent.clone(), to_agent.clone(), from_arc)
             .await?;
-        let op_hashes_to: S = HashSet::from_iter(op_hashes_to);
-        let agent_info_to: A = HashSet::from_iter(agent_info_to);
         span.in_scope(|| {
+            tracing::debug!(?from_arc);
+            tracing::debug!(from_has_len = ?op_hashes_from.len());
             tracing::debug!(to_has_len = ?op_hashes_to.len());
         });
 
@@ -168,21 +190,6 @@ impl GossipData {
             tracing::debug!(from_needs_len = ?from_needs.len());
         });
 
-        // values that from_agent has, and to_agent needs
-        let to_needs = op_hashes_from
-            .difference(&op_hashes_to)
-            .cloned()
-            .collect::<Vec<_>>();
-        let to_needs_agents = agent_info_from
-            .difference(&agent_info_to)
-            .cloned()
-            .map(|(ai, _)| ai)
-            .collect::<Vec<_>>();
-        span.in_scope(|| {
-            tracing::debug!(?to_needs_agents);
-            tracing::debug!(to_needs_len = ?to_needs.len());
-        });
-
         // fetch values that to_agent needs from from_agent
         if !to_needs.is_empty() || !to_needs_agents.is_empty() {
             if let Ok((r_ops, r_peers)) = self
@@ -248,3 +255,57 @@ impl GossipData {
         Ok(())
     }
 }
+
+/// Pair up every local agent with every other agent whose arc
+/// overlaps with its own. Agents with nothing in common have
+/// no ops to exchange.
+fn gossip_pairs(
+    local_agents: &[AgentArc],
+    remote_agents: &[AgentArc],
+) -> Vec<(AgentArc, AgentArc)> {
+    let mut out = Vec::new();
+    for (i, a1) in local_agents.iter().enumerate() {
+        // skip(i + 1) so local pairs are only run from one side
+        // and we never gossip with ourselves
+        for a2 in local_agents.iter().skip(i + 1).chain(remote_agents.iter()) {
+            if a1.1.overlaps(&a2.1) {
+                out.push((a1.clone(), a2.clone()));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestVal;
+
+    #[test]
+    fn only_overlapping_agents_gossip() {
+        let agent = |center, half_length| -> AgentArc {
+            (TestVal::test_val(), DhtArc::new(center, half_length))
+        };
+        let local = vec![agent(0, 100), agent(150, 100)];
+        let remote = vec![
+            agent(u32::MAX - 50, 100),
+            agent(1000, 100),
+            agent(u32::MAX / 2, u32::MAX),
+        ];
+
+        let pairs = gossip_pairs(&local, &remote)
+            .into_iter()
+            .map(|(a1, a2)| (a1.0, a2.0))
+            .collect::<HashSet<_>>();
+        let expected = vec![
+            (&local[0], &local[1]),
+            (&local[0], &remote[0]),
+            (&local[0], &remote[2]),
+            (&local[1], &remote[2]),
+        ]
+        .into_iter()
+        .map(|(a1, a2)| (a1.0.clone(), a2.0.clone()))
+        .collect::<HashSet<_>>();
+        assert_eq!(pairs, expected);
+    }
+}
@@ -0,0 +1,137 @@
+//! Announce and discover agents on the local network by UDP multicast.
+
+use super::*;
+use crate::agent_store::AgentInfoSigned;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+
+/// Every announcement starts with this,
+/// so we can ignore any other traffic on the multicast group.
+const LAN_MAGIC: &[u8] = b"kitsune-lan/0";
+
+/// Large enough for any udp datagram.
+const RECV_BUF_LEN: usize = 64 * 1024;
+
+/// Handle to the LAN discovery tasks of a space.
+/// Dropping it stops announcing and listening.
+pub(crate) struct LanDiscovery {
+    /// the latest encoded announcement for each locally joined agent
+    announcements: Arc<std::sync::Mutex<HashMap<Arc<KitsuneAgent>, Vec<u8>>>>,
+    abort: futures::future::AbortHandle,
+}
+
+impl Drop for LanDiscovery {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+impl LanDiscovery {
+    /// Join the multicast group, announcing our agents every
+    /// `announce_interval_ms` and passing the agent infos other
+    /// nodes announce on to the space.
+    pub(crate) fn spawn(
+        config: &LanDiscoveryConfig,
+        i_s: ghost_actor::GhostSender<SpaceInternal>,
+    ) -> KitsuneP2pResult<Self> {
+        let socket = bind(config)?;
+        let (mut recv, mut send) = socket.split();
+        let group = SocketAddr::from(config.multicast_addr);
+        let interval = std::time::Duration::from_millis(config.announce_interval_ms);
+        let announcements: Arc<std::sync::Mutex<HashMap<Arc<KitsuneAgent>, Vec<u8>>>> =
+            Arc::new(std::sync::Mutex::new(HashMap::new()));
+
+        let announcements2 = announcements.clone();
+        let announce = async move {
+            loop {
+                let all = announcements2
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for datagram in all {
+                    if let Err(e) = send.send_to(&datagram, &group).await {
+                        tracing::warn!(msg = "Failed to announce agent on LAN", ?e);
+                    }
+                }
+                tokio::time::delay_for(interval).await;
+            }
+        };
+
+        let listen = async move {
+            let mut buf = vec![0; RECV_BUF_LEN];
+            loop {
+                match recv.recv_from(&mut buf).await {
+                    Ok((len, _)) => {
+                        if let Some(info) = decode(&buf[..len]) {
+                            match i_s.put_lan_agent_info(info).await {
+                                Err(KitsuneP2pError::GhostError(
+                                    ghost_actor::GhostError::Disconnected,
+                                )) => {
+                                    // the space has shut down
+                                    break;
+                                }
+                                Err(e) => {
+                                    tracing::warn!(msg = "Failed to add LAN agent info", ?e)
+                                }
+                                Ok(()) => (),
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!(msg = "Failed to receive LAN announcement", ?e),
+                }
+            }
+        };
+
+        let (task, abort) = futures::future::abortable(futures::future::join(announce, listen));
+        tokio::task::spawn(task);
+
+        Ok(Self {
+            announcements,
+            abort,
+        })
+    }
+
+    /// Announce `info` for `agent` from now on, replacing any earlier one.
+    pub(crate) fn announce(
+        &self,
+        agent: Arc<KitsuneAgent>,
+        info: &AgentInfoSigned,
+    ) -> KitsuneP2pResult<()> {
+        let mut datagram = LAN_MAGIC.to_vec();
+        kitsune_p2p_types::codec::rmp_encode(&mut datagram, info)?;
+        self.announcements.lock().unwrap().insert(agent, datagram);
+        Ok(())
+    }
+
+    /// Stop announcing `agent`.
+    pub(crate) fn forget(&self, agent: &KitsuneAgent) {
+        self.announcements.lock().unwrap().remove(agent);
+    }
+}
+
+/// Bind a udp socket to the multicast port, sharing it with any other
+/// nodes on this host, and join the multicast group.
+fn bind(config: &LanDiscoveryConfig) -> std::io::Result<tokio::net::UdpSocket> {
+    use socket2::*;
+    let socket = Socket::new(Domain::ipv4(), Type::dgram(), Some(Protocol::udp()))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    let bind_to = SocketAddr::from((Ipv4Addr::UNSPECIFIED, config.multicast_addr.port()));
+    socket.bind(&SockAddr::from(bind_to))?;
+    socket.join_multicast_v4(config.multicast_addr.ip(), &config.interface)?;
+    socket.set_multicast_if_v4(&config.interface)?;
+    // other nodes on this host should hear us too
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_nonblocking(true)?;
+    tokio::net::UdpSocket::from_std(socket.into_udp_socket())
+}
+
+fn decode(datagram: &[u8]) -> Option<AgentInfoSigned> {
+    if !datagram.starts_with(LAN_MAGIC) {
+        return None;
+    }
+    kitsune_p2p_types::codec::rmp_decode(&mut &datagram[LAN_MAGIC.len()..]).ok()
+}
@@ -0,0 +1,31 @@
+//! Metrics for the requests this node sends to remote agents.
+
+use kitsune_p2p_types::metrics::CounterVec;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// Requests sent to remote agents, by wire message kind.
+static RPC_REQUESTS: Lazy<Arc<CounterVec>> = Lazy::new(|| {
+    CounterVec::register(
+        "kitsune_rpc_requests_total",
+        "Requests sent to remote agents",
+        &["kind"],
+    )
+});
+
+/// Requests to remote agents which errored or got an unexpected response.
+static RPC_FAILURES: Lazy<Arc<CounterVec>> = Lazy::new(|| {
+    CounterVec::register(
+        "kitsune_rpc_failures_total",
+        "Requests to remote agents which failed",
+        &["kind"],
+    )
+});
+
+/// Count a request to a remote agent, and count it as failed if it was.
+pub(crate) fn record_rpc<T, E>(kind: &str, result: &Result<T, E>) {
+    RPC_REQUESTS.inc(&[kind]);
+    if result.is_err() {
+        RPC_FAILURES.inc(&[kind]);
+    }
+}
@@ -2,6 +2,9 @@ use super::*;
 use ghost_actor::dependencies::tracing;
 use ghost_actor::dependencies::tracing_futures::Instrument;
 use kitsune_p2p_types::codec::Codec;
+use kitsune_p2p_types::dht_arc::DhtArc;
+use kitsune_p2p_types::dht_arc::MAX_HALF_LENGTH;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 
@@ -37,6 +40,9 @@ ghost_actor::ghost_chan! {
 
         /// see if an agent is locally joined
         fn is_agent_local(agent: Arc<KitsuneAgent>) -> bool;
+
+        /// Store an agent info announced by another node on the local network
+        fn put_lan_agent_info(info: crate::types::agent_store::AgentInfoSigned) -> ();
     }
 }
 
@@ -80,12 +86,7 @@ impl gossip::GossipEventHandler for Space {
     fn handle_list_neighbor_agents(
         &mut self,
     ) -> gossip::GossipEventHandlerResult<ListNeighborAgents> {
-        // while full-sync this is just a clone of list_by_basis
-        let local_agents = self
-            .local_joined_agents
-            .iter()
-            .cloned()
-            .collect::<HashSet<_>>();
+        let local_agents = self.local_joined_agents.clone();
         let agent = self.local_joined_agents.iter().next().cloned();
         let fut = match agent {
             Some(agent) => self
@@ -97,13 +98,23 @@ impl gossip::GossipEventHandler for Space {
             None => async { Ok(Vec::new()) }.boxed().into(),
         };
         Ok(async move {
-            let remote_agents = fut
+            let mut arcs = fut
                 .await?
+                .iter()
+                .filter_map(|ai| types::agent_store::AgentInfo::try_from(ai).ok())
+                .map(|ai| (Arc::new(ai.as_agent_ref().clone()), ai.dht_arc()))
+                .collect::<HashMap<_, _>>();
+            // local agents that haven't signed their info yet hold everything
+            let local_agents = local_agents
                 .into_iter()
-                .map(|ai| Arc::new(ai.into_agent()))
-                .filter(|a| !local_agents.contains(a))
+                .map(|a| {
+                    let arc = arcs
+                        .remove(&a)
+                        .unwrap_or_else(|| DhtArc::new(a.get_loc(), MAX_HALF_LENGTH));
+                    (a, arc)
+                })
                 .collect::<Vec<_>>();
-            let local_agents = local_agents.into_iter().collect::<Vec<_>>();
+            let remote_agents = arcs.into_iter().collect::<Vec<_>>();
             Ok((local_agents, remote_agents))
         }
         .boxed()
@@ -411,9 +422,7 @@ impl SpaceInternalHandler for Space {
         &mut self,
         _space: Arc<KitsuneSpace>,
         from_agent: Arc<KitsuneAgent>,
-        // during short-circuit / full-sync mode,
-        // we're ignoring the basis_hash and just returning everyone.
-        _basis: Arc<KitsuneBasis>,
+        basis: Arc<KitsuneBasis>,
     ) -> SpaceInternalHandlerResult<HashSet<Arc<KitsuneAgent>>> {
         let mut res: HashSet<Arc<KitsuneAgent>> =
             self.local_joined_agents.iter().cloned().collect();
@@ -425,7 +434,12 @@ impl SpaceInternalHandler for Space {
             });
         Ok(async move {
             for peer in all_peers_fut.await? {
-                res.insert(Arc::new(peer.as_agent_ref().clone()));
+                // only peers that claim to hold the basis
+                if let Ok(info) = types::agent_store::AgentInfo::try_from(&peer) {
+                    if info.dht_arc().contains(basis.get_loc()) {
+                        res.insert(Arc::new(peer.as_agent_ref().clone()));
+                    }
+                }
             }
             Ok(res)
         }
@@ -439,6 +453,8 @@ impl SpaceInternalHandler for Space {
         let bound_url = self.transport.bound_url();
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
+        let redundancy_target = self.config.redundancy_target;
+        let lan = self.lan.clone();
         Ok(async move {
             let bound_url = bound_url.await?;
             let urls = bound_url
@@ -446,12 +462,26 @@ impl SpaceInternalHandler for Space {
                 .map(|(_, sub_url)| url2::url2!("{}", sub_url))
                 .collect::<Vec<_>>();
             for agent in agent_list {
+                // size our arc from the peers we know about
+                let peer_locs = evt_sender
+                    .query_agent_info_signed(QueryAgentInfoSignedEvt {
+                        space: space.clone(),
+                        agent: agent.clone(),
+                    })
+                    .await?
+                    .into_iter()
+                    .filter(|ai| ai.as_agent_ref() != &*agent)
+                    .map(|ai| ai.as_agent_ref().get_loc())
+                    .collect::<Vec<_>>();
+                let dht_arc =
+                    DhtArc::for_peer_density(agent.get_loc(), peer_locs, redundancy_target);
                 let agent_info = crate::types::agent_store::AgentInfo::new(
                     (*space).clone(),
                     (*agent).clone(),
                     urls.clone(),
                     crate::spawn::actor::bootstrap::now_once(None).await?,
                     AGENT_INFO_EXPIRES_AFTER_MS,
+                    dht_arc,
                 );
                 let mut data = Vec::new();
                 kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info)?;
@@ -475,6 +505,10 @@ impl SpaceInternalHandler for Space {
                     })
                     .await?;
 
+                if let Some(lan) = &lan {
+                    lan.announce(agent.clone(), &agent_info_signed)?;
+                }
+
                 // Push to the bootstrap as well.
                 crate::spawn::actor::bootstrap::put(bootstrap_service.clone(), agent_info_signed)
                     .await?;
@@ -492,6 +526,50 @@ impl SpaceInternalHandler for Space {
         let res = self.local_joined_agents.contains(&agent);
         Ok(async move { Ok(res) }.boxed().into())
     }
+
+    fn handle_put_lan_agent_info(
+        &mut self,
+        info: crate::types::agent_store::AgentInfoSigned,
+    ) -> SpaceInternalHandlerResult<()> {
+        // our own announcements come back to us too,
+        // and announcements for other spaces aren't worth verifying
+        let in_space = match crate::types::agent_store::AgentInfo::try_from(&info) {
+            Ok(agent_info) => agent_info.as_space_ref() == &*self.space,
+            Err(e) => {
+                tracing::debug!(msg = "Ignoring undecodable LAN announcement", ?e);
+                false
+            }
+        };
+        let to_agent = if !in_space || self.local_joined_agents.contains(info.as_agent_ref()) {
+            None
+        } else {
+            self.local_joined_agents.iter().next().cloned()
+        };
+        let space = self.space.clone();
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let to_agent = match to_agent {
+                Some(to_agent) => to_agent,
+                None => return Ok(()),
+            };
+            let now = crate::spawn::actor::bootstrap::now_once(None).await?;
+            // anyone on the network can send us a datagram, drop bad ones
+            if let Err(e) = info.verify(now) {
+                tracing::debug!(msg = "Ignoring invalid LAN announcement", ?e);
+                return Ok(());
+            }
+            evt_sender
+                .put_agent_info_signed(PutAgentInfoSignedEvt {
+                    space,
+                    agent: to_agent,
+                    agent_info_signed: info,
+                })
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
 
 impl ghost_actor::GhostControlHandler for Space {}
@@ -550,6 +628,9 @@ impl KitsuneP2pHandler for Space {
         agent: Arc<KitsuneAgent>,
     ) -> KitsuneP2pHandlerResult<()> {
         self.local_joined_agents.remove(&agent);
+        if let Some(lan) = &self.lan {
+            lan.forget(&agent);
+        }
         Ok(async move { Ok(()) }.boxed().into())
     }
 
@@ -580,23 +661,32 @@ impl KitsuneP2pHandler for Space {
                 discover::PeerDiscoverResult::OkRemote {
                     mut write, read, ..
                 } => {
-                    let payload = wire::Wire::call(
-                        space.clone(),
-                        from_agent.clone(),
-                        to_agent.clone(),
-                        payload.into(),
-                    )
-                    .encode_vec()?;
-                    write.write_and_close(payload).await?;
-                    let res = read.read_to_end().await;
-                    let (_, res) = wire::Wire::decode_ref(&res)?;
-                    match res {
-                        wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
-                        wire::Wire::CallResp(wire::CallResp { data }) => Ok(data.into()),
-                        r => Err(format!("invalid response: {:?}", r).into()),
+                    let res: KitsuneP2pResult<Vec<u8>> = async move {
+                        let payload = wire::Wire::call(
+                            space.clone(),
+                            from_agent.clone(),
+                            to_agent.clone(),
+                            payload.into(),
+                        )
+                        .encode_vec()?;
+                        write.write_and_close(payload).await?;
+                        let res = read.read_to_end().await;
+                        let (_, res) = wire::Wire::decode_ref(&res)?;
+                        match res {
+                            wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
+                            wire::Wire::CallResp(wire::CallResp { data }) => Ok(data.into()),
+                            r => Err(format!("invalid response: {:?}", r).into()),
+                        }
                     }
+                    .await;
+                    super::metrics::record_rpc("call", &res);
+                    res
+                }
+                discover::PeerDiscoverResult::Err(e) => {
+                    let res = Err(e);
+                    super::metrics::record_rpc::<Vec<u8>, _>("call", &res);
+                    res
                 }
-                discover::PeerDiscoverResult::Err(e) => Err(e),
             }
         }
         .boxed()
@@ -687,6 +777,7 @@ pub(crate) struct Space {
     pub(crate) transport: ghost_actor::GhostSender<TransportListener>,
     pub(crate) local_joined_agents: HashSet<Arc<KitsuneAgent>>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
+    pub(crate) lan: Option<Arc<lan::LanDiscovery>>,
 }
 
 impl Space {
@@ -708,6 +799,17 @@ impl Space {
             }
         });
 
+        let lan =
+            config.lan_discovery.as_ref().and_then(|lan_config| {
+                match lan::LanDiscovery::spawn(lan_config, i_s.clone()) {
+                    Ok(lan) => Some(Arc::new(lan)),
+                    Err(e) => {
+                        tracing::error!(msg = "Failed to start LAN discovery", ?e);
+                        None
+                    }
+                }
+            });
+
         Self {
             space,
             i_s,
@@ -715,6 +817,7 @@ impl Space {
             transport,
             local_joined_agents: HashSet::new(),
             config,
+            lan,
         }
     }
 
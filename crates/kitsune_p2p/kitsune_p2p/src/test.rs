@@ -167,6 +167,105 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn test_tcp_transport_binding() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+
+        let (harness, _evt) = spawn_test_harness_tcp().await?;
+
+        harness.add_space().await?;
+        let (_, p2p) = harness.add_direct_agent("DIRECT".into()).await?;
+
+        // The proxy layer is tunneled over the tcp transport.
+        let bindings = p2p.list_transport_bindings().await?;
+        assert_eq!(1, bindings.len());
+        let binding = &bindings[0];
+        assert_eq!("kitsune-proxy", binding.scheme());
+        assert_eq!(
+            "kitsune-tcp",
+            binding.path_segments().unwrap().next().unwrap()
+        );
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_tcp_transport_coms() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+        let (harness, _evt) = spawn_test_harness_tcp().await?;
+
+        let space = harness.add_space().await?;
+        let (a1, p2p1) = harness.add_direct_agent("one".into()).await?;
+        let (a2, p2p2) = harness.add_direct_agent("two".into()).await?;
+
+        harness.magic_peer_info_exchange().await?;
+
+        let r1 = p2p1
+            .rpc_single(space.clone(), a2.clone(), a1.clone(), b"m1".to_vec(), None)
+            .await?;
+        let r2 = p2p2
+            .rpc_single(space.clone(), a1, a2, b"m2".to_vec(), None)
+            .await?;
+        assert_eq!(b"echo: m1".to_vec(), r1);
+        assert_eq!(b"echo: m2".to_vec(), r2);
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_lan_discovery() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+
+        // announce over the loopback interface, on a random port
+        // so concurrent test runs don't hear each other
+        let lan = LanDiscoveryConfig {
+            multicast_addr: std::net::SocketAddrV4::new(
+                std::net::Ipv4Addr::new(239, 255, 75, 80),
+                40_000 + rand::random::<u16>() % 20_000,
+            ),
+            interface: std::net::Ipv4Addr::LOCALHOST,
+            announce_interval_ms: 100,
+        };
+        let (harness, _evt) = spawn_test_harness_with_lan_discovery(
+            TransportConfig::Tcp {
+                bind_to: Some(url2::url2!("kitsune-tcp://127.0.0.1:0")),
+                override_host: None,
+                override_port: None,
+            },
+            lan,
+        )
+        .await?;
+
+        let space = harness.add_space().await?;
+        let (a1, p2p1) = harness.add_direct_agent("one".into()).await?;
+        let (a2, p2p2) = harness.add_direct_agent("two".into()).await?;
+
+        // no magic peer info exchange, the nodes must find each other
+        let mut found = false;
+        for _ in 0..50 {
+            let peers1 = harness.dump_local_peer_data(a1.clone()).await?;
+            let peers2 = harness.dump_local_peer_data(a2.clone()).await?;
+            if peers1.contains_key(&a2) && peers2.contains_key(&a1) {
+                found = true;
+                break;
+            }
+            tokio::time::delay_for(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(found, "agents did not discover each other on the LAN");
+
+        let r1 = p2p1
+            .rpc_single(space.clone(), a2.clone(), a1.clone(), b"m1".to_vec(), None)
+            .await?;
+        let r2 = p2p2
+            .rpc_single(space.clone(), a1, a2, b"m2".to_vec(), None)
+            .await?;
+        assert_eq!(b"echo: m1".to_vec(), r1);
+        assert_eq!(b"echo: m2".to_vec(), r2);
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn test_request_workflow() -> Result<(), KitsuneP2pError> {
         observability::test_run().ok();
@@ -449,4 +548,96 @@ mod tests {
         harness.ghost_actor_shutdown().await?;
         Ok(())
     }
+
+    /// Test that agents only hold and gossip the ops in their own arc.
+    #[tokio::test(threaded_scheduler)]
+    async fn test_gossip_sharded() -> Result<(), KitsuneP2pError> {
+        use crate::dht_arc::DhtArc;
+        use std::collections::HashMap;
+        use std::collections::HashSet;
+        use std::convert::TryFrom;
+
+        observability::test_run().ok();
+        // each agent only needs to reach its nearest peer
+        let (harness, _evt) =
+            spawn_test_harness_with_redundancy(TransportConfig::Mem {}, 1).await?;
+
+        let space = harness.add_space().await?;
+        let mut agents = Vec::new();
+        for i in 0..4 {
+            agents.push(harness.add_direct_agent(format!("agent-{}", i)).await?);
+        }
+
+        // - Let everyone know about each other
+        // then rejoin so the arcs are sized from the peers we know
+        harness.magic_peer_info_exchange().await?;
+        for (agent, p2p) in agents.iter() {
+            p2p.join(space.clone(), agent.clone()).await?;
+        }
+        harness.magic_peer_info_exchange().await?;
+
+        // - Let any gossip rounds planned with the old arcs finish
+        tokio::time::delay_for(std::time::Duration::from_millis(200)).await;
+
+        let mut arcs: HashMap<Arc<KitsuneAgent>, DhtArc> = HashMap::new();
+        for (agent, _) in agents.iter() {
+            let peers = harness.dump_local_peer_data(agent.clone()).await?;
+            let info = agent_store::AgentInfo::try_from(&*peers[agent])?;
+            arcs.insert(agent.clone(), info.dht_arc());
+        }
+
+        // - Arcs reach the nearest peer but don't cover the whole dht
+        for (agent, arc) in arcs.iter() {
+            assert!(arc.half_length < dht_arc::MAX_HALF_LENGTH);
+            let nearest = arcs
+                .keys()
+                .filter(|a| *a != agent)
+                .min_by_key(|a| {
+                    let d = a.get_loc().wrapping_sub(agent.get_loc());
+                    std::cmp::min(d, d.wrapping_neg())
+                })
+                .unwrap();
+            assert!(arc.contains(nearest.get_loc()));
+        }
+
+        // - Insert some data for every agent
+        let mut authored: HashMap<Arc<KitsuneAgent>, HashSet<Arc<KitsuneOpHash>>> = HashMap::new();
+        for (agent, _) in agents.iter() {
+            for i in 0..10 {
+                let op = harness
+                    .inject_gossip_data(agent.clone(), format!("{:?}-{}", agent, i))
+                    .await?;
+                authored.entry(agent.clone()).or_default().insert(op);
+            }
+        }
+
+        tokio::time::delay_for(std::time::Duration::from_millis(500)).await;
+
+        for (agent, arc) in arcs.iter() {
+            let held = harness
+                .dump_local_gossip_data(agent.clone())
+                .await?
+                .into_iter()
+                .map(|(op, _)| op)
+                .collect::<HashSet<_>>();
+
+            // - Nothing outside our arc was gossiped to us
+            for op in held.iter() {
+                assert!(authored[agent].contains(op) || arc.contains(op.get_loc()));
+            }
+
+            // - Everything in our arc from agents we overlap with was
+            for (author, ops) in authored.iter() {
+                if author == agent || !arc.overlaps(&arcs[author]) {
+                    continue;
+                }
+                for op in ops.iter().filter(|op| arc.contains(op.get_loc())) {
+                    assert!(held.contains(op));
+                }
+            }
+        }
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
 }
@@ -68,6 +68,22 @@ pub async fn spawn_test_harness_quic() -> Result<
     .await
 }
 
+/// construct a test suite around a tcp transport
+pub async fn spawn_test_harness_tcp() -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
+> {
+    spawn_test_harness(TransportConfig::Tcp {
+        bind_to: Some(url2::url2!("kitsune-tcp://0.0.0.0:0")),
+        override_host: None,
+        override_port: None,
+    })
+    .await
+}
+
 /// construct a test suite around a sub transport config concept
 pub async fn spawn_test_harness(
     sub_config: TransportConfig,
@@ -77,6 +93,55 @@ pub async fn spawn_test_harness(
         HarnessEventChannel,
     ),
     KitsuneP2pError,
+> {
+    spawn_test_harness_with_config(sub_config, KitsuneP2pConfig::default()).await
+}
+
+/// construct a test suite where agents size their arcs
+/// to reach `redundancy_target` peers
+pub async fn spawn_test_harness_with_redundancy(
+    sub_config: TransportConfig,
+    redundancy_target: u32,
+) -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
+> {
+    let mut config = KitsuneP2pConfig::default();
+    config.redundancy_target = redundancy_target;
+    spawn_test_harness_with_config(sub_config, config).await
+}
+
+/// construct a test suite where agents discover each other
+/// on the local network
+pub async fn spawn_test_harness_with_lan_discovery(
+    sub_config: TransportConfig,
+    lan_discovery: LanDiscoveryConfig,
+) -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
+> {
+    let mut config = KitsuneP2pConfig::default();
+    config.lan_discovery = Some(lan_discovery);
+    spawn_test_harness_with_config(sub_config, config).await
+}
+
+/// construct a test suite where every agent's config starts from `config`,
+/// with a proxy over `sub_config` added to its transport pool
+pub async fn spawn_test_harness_with_config(
+    sub_config: TransportConfig,
+    config: KitsuneP2pConfig,
+) -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
 > {
     let harness_chan = HarnessEventChannel::new("");
 
@@ -92,7 +157,12 @@ pub async fn spawn_test_harness(
         .create_channel::<HarnessInner>()
         .await?;
 
-    tokio::task::spawn(builder.spawn(HarnessActor::new(i_s, harness_chan.clone(), sub_config)));
+    tokio::task::spawn(builder.spawn(HarnessActor::new(
+        i_s,
+        harness_chan.clone(),
+        sub_config,
+        config,
+    )));
 
     Ok((controller, harness_chan))
 }
@@ -112,6 +182,7 @@ struct HarnessActor {
     i_s: ghost_actor::GhostSender<HarnessInner>,
     harness_chan: HarnessEventChannel,
     sub_config: TransportConfig,
+    config: KitsuneP2pConfig,
     space_list: Vec<Arc<KitsuneSpace>>,
     agents: HashMap<
         Arc<KitsuneAgent>,
@@ -127,11 +198,13 @@ impl HarnessActor {
         i_s: ghost_actor::GhostSender<HarnessInner>,
         harness_chan: HarnessEventChannel,
         sub_config: TransportConfig,
+        config: KitsuneP2pConfig,
     ) -> Self {
         Self {
             i_s,
             harness_chan,
             sub_config,
+            config,
             space_list: Vec::new(),
             agents: HashMap::new(),
         }
@@ -207,7 +280,7 @@ impl HarnessControlApiHandler for HarnessActor {
         nick: String,
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
-        let mut proxy_agent_config = KitsuneP2pConfig::default();
+        let mut proxy_agent_config = self.config.clone();
         proxy_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -235,7 +308,7 @@ impl HarnessControlApiHandler for HarnessActor {
         nick: String,
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
-        let mut direct_agent_config = KitsuneP2pConfig::default();
+        let mut direct_agent_config = self.config.clone();
         direct_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -264,7 +337,7 @@ impl HarnessControlApiHandler for HarnessActor {
         proxy_url: url2::Url2,
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
-        let mut nat_agent_config = KitsuneP2pConfig::default();
+        let mut nat_agent_config = self.config.clone();
         nat_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -297,7 +370,13 @@ impl HarnessControlApiHandler for HarnessActor {
             let infos = futures::future::try_join_all(infos).await?;
             let infos = infos.into_iter().fold(HashMap::new(), |acc, x| {
                 x.into_iter().fold(acc, |mut acc, x| {
-                    acc.insert(Arc::new(x.as_agent_ref().clone()), x);
+                    let agent = Arc::new(x.as_agent_ref().clone());
+                    match acc.get(&agent) {
+                        Some(existing) if !is_newer_agent_info(&x, existing) => (),
+                        _ => {
+                            acc.insert(agent, x);
+                        }
+                    }
                     acc
                 })
             });
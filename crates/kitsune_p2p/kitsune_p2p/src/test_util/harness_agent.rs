@@ -56,6 +56,7 @@ pub(crate) async fn spawn_test_agent(
 
 use lair_keystore_api::entry::EntrySignEd25519;
 use lair_keystore_api::internal::sign_ed25519::*;
+use std::convert::TryFrom;
 
 struct AgentHarness {
     agent: Arc<KitsuneAgent>,
@@ -70,7 +71,11 @@ impl AgentHarness {
         let EntrySignEd25519 { priv_key, pub_key } = sign_ed25519_keypair_new_from_entropy()
             .await
             .map_err(KitsuneP2pError::other)?;
-        let pub_key = (**pub_key).clone();
+        let mut pub_key = (**pub_key).clone();
+        // spread the test agents around the dht
+        // the key is random so its first bytes will do for a location
+        let loc = pub_key[..4].to_vec();
+        pub_key.extend(loc);
         let agent: Arc<KitsuneAgent> = Arc::new(KitsuneAgent::new(pub_key));
         Ok(Self {
             agent,
@@ -82,6 +87,25 @@ impl AgentHarness {
     }
 }
 
+impl AgentHarness {
+    /// gossip can deliver stale info so keep the newest we have seen
+    fn store_agent_info(&mut self, agent: Arc<KitsuneAgent>, info: Arc<AgentInfoSigned>) {
+        let is_newer = match self.agent_store.get(&agent) {
+            Some(existing) => is_newer_agent_info(&info, existing),
+            None => true,
+        };
+        if is_newer {
+            self.agent_store.insert(agent, info);
+        }
+    }
+}
+
+/// Is `info` signed at least as recently as `other`
+pub(crate) fn is_newer_agent_info(info: &AgentInfoSigned, other: &AgentInfoSigned) -> bool {
+    let signed_at_ms = |i: &AgentInfoSigned| AgentInfo::try_from(i).map(|i| i.signed_at_ms()).ok();
+    signed_at_ms(info) >= signed_at_ms(other)
+}
+
 impl ghost_actor::GhostControlHandler for AgentHarness {}
 
 impl ghost_actor::GhostHandler<HarnessAgentControl> for AgentHarness {}
@@ -98,7 +122,9 @@ impl HarnessAgentControlHandler for AgentHarness {
         &mut self,
         info: HashMap<Arc<KitsuneAgent>, Arc<AgentInfoSigned>>,
     ) -> HarnessAgentControlHandlerResult<()> {
-        self.agent_store.extend(info);
+        for (agent, info) in info {
+            self.store_agent_info(agent, info);
+        }
         Ok(async move { Ok(()) }.boxed().into())
     }
 
@@ -134,7 +160,7 @@ impl KitsuneP2pEventHandler for AgentHarness {
         input: PutAgentInfoSignedEvt,
     ) -> KitsuneP2pEventHandlerResult<()> {
         let info = Arc::new(input.agent_info_signed);
-        self.agent_store.insert(input.agent.clone(), info.clone());
+        self.store_agent_info(Arc::new(info.as_agent_ref().clone()), info.clone());
         self.harness_chan.publish(HarnessEventType::StoreAgentInfo {
             agent: (&input.agent).into(),
             agent_info: info,
@@ -213,9 +239,14 @@ impl KitsuneP2pEventHandler for AgentHarness {
 
     fn handle_fetch_op_hashes_for_constraints(
         &mut self,
-        _input: FetchOpHashesForConstraintsEvt,
+        input: FetchOpHashesForConstraintsEvt,
     ) -> KitsuneP2pEventHandlerResult<Vec<Arc<super::KitsuneOpHash>>> {
-        let hashes: Vec<Arc<super::KitsuneOpHash>> = self.gossip_store.keys().cloned().collect();
+        let hashes: Vec<Arc<super::KitsuneOpHash>> = self
+            .gossip_store
+            .keys()
+            .filter(|h| input.dht_arc.contains(h.get_loc()))
+            .cloned()
+            .collect();
         let slug_hashes: Vec<Slug> = hashes.iter().map(|h| h.into()).collect();
         tracing::trace!(?slug_hashes, "FETCH_OP_HASHES");
         Ok(async move { Ok(hashes) }.boxed().into())
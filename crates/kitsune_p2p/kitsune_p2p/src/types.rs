@@ -36,6 +36,28 @@ pub enum KitsuneP2pError {
     #[error("Bootstrap Error: {0}")]
     Bootstrap(Box<str>),
 
+    /// The signature on an agent info does not verify against its agent.
+    #[error("Invalid agent info signature for {0:?}")]
+    InvalidAgentInfoSignature(Arc<KitsuneAgent>),
+
+    /// An agent info claims to be signed later than now.
+    #[error("Agent info signed at {signed_at_ms} is in the future of {now_ms}")]
+    AgentInfoFromFuture {
+        /// When the agent info claims to be signed.
+        signed_at_ms: u64,
+        /// Our 'now' at the time of checking.
+        now_ms: u64,
+    },
+
+    /// An agent info has expired.
+    #[error("Agent info expired at {expires_at_ms}, now is {now_ms}")]
+    AgentInfoExpired {
+        /// When the agent info expired.
+        expires_at_ms: u64,
+        /// Our 'now' at the time of checking.
+        now_ms: u64,
+    },
+
     /// SystemTime call failed.
     #[error(transparent)]
     SystemTime(#[from] std::time::SystemTimeError),
@@ -222,3 +244,4 @@ pub mod gossip;
 pub(crate) mod wire;
 
 pub use kitsune_p2p_types::dht_arc;
+pub use kitsune_p2p_types::metrics;
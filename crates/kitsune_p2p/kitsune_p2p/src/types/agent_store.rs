@@ -1,14 +1,33 @@
 //! Data structures to be stored in the agent/peer database.
 
 use crate::types::KitsuneAgent;
+use crate::types::KitsuneBinType;
 use crate::types::KitsuneP2pError;
 use crate::types::KitsuneSignature;
 use crate::types::KitsuneSpace;
+use kitsune_p2p_types::dht_arc::DhtArc;
+use kitsune_p2p_types::dht_arc::MAX_HALF_LENGTH;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+use std::sync::Arc;
 use url2::Url2;
 
 /// A list of Urls.
 pub type Urls = Vec<Url2>;
 
+/// The current unix time in milliseconds, as agent info signing and expiry
+/// times are measured.
+pub fn now_ms() -> Result<u64, KitsuneP2pError> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_millis()
+        .try_into()?)
+}
+
+/// How far in the future of our clock an agent info may claim to be signed,
+/// to allow for the agent's clock running ahead of ours.
+pub const MAX_CLOCK_SKEW_MS: u64 = 5 * 60 * 1000;
+
 /// Value in the peer database that tracks an Agent's representation as signed by that agent.
 #[derive(
     serde::Serialize,
@@ -33,18 +52,60 @@ pub struct AgentInfoSigned {
 
 impl AgentInfoSigned {
     /// Build a new AgentInfoSigned struct given a valid signature of the AgentInfo.
-    // @todo fail this if the signature does not verify against the agent info.
-    // It should not be possible to express a signed agent info type  with no valid signature.
+    /// Fails if the signature does not verify against the agent info.
     pub fn try_new(
         agent: KitsuneAgent,
         signature: KitsuneSignature,
         agent_info: Vec<u8>,
     ) -> Result<Self, KitsuneP2pError> {
-        Ok(Self {
+        let agent_info_signed = Self {
             agent,
             signature,
             agent_info,
-        })
+        };
+        agent_info_signed.verify_signature()?;
+        Ok(agent_info_signed)
+    }
+
+    /// Decode the AgentInfo, checking that it describes the signing agent
+    /// and that the signature verifies against the agent info bytes.
+    ///
+    /// Deserializing an AgentInfoSigned does not check the signature,
+    /// so anything received over the network must be verified before use.
+    pub fn verify_signature(&self) -> Result<AgentInfo, KitsuneP2pError> {
+        let invalid = || KitsuneP2pError::InvalidAgentInfoSignature(Arc::new(self.agent.clone()));
+        // `get_bytes` expects the 32 key bytes plus 4 location bytes.
+        if self.agent.len() != 36 {
+            return Err(invalid());
+        }
+        let agent_info = AgentInfo::try_from(self)?;
+        if agent_info.as_agent_ref() != &self.agent {
+            return Err(invalid());
+        }
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, self.agent.get_bytes())
+            .verify(&self.agent_info, &self.signature.0)
+            .map_err(|_| invalid())?;
+        Ok(agent_info)
+    }
+
+    /// As [`verify_signature`](Self::verify_signature), additionally
+    /// rejecting agent infos signed more than [`MAX_CLOCK_SKEW_MS`] after
+    /// `now_ms` or expired as of `now_ms`.
+    pub fn verify(&self, now_ms: u64) -> Result<AgentInfo, KitsuneP2pError> {
+        let agent_info = self.verify_signature()?;
+        if agent_info.signed_at_ms() > now_ms.saturating_add(MAX_CLOCK_SKEW_MS) {
+            return Err(KitsuneP2pError::AgentInfoFromFuture {
+                signed_at_ms: agent_info.signed_at_ms(),
+                now_ms,
+            });
+        }
+        if agent_info.is_expired(now_ms) {
+            return Err(KitsuneP2pError::AgentInfoExpired {
+                expires_at_ms: agent_info.expires_at_ms(),
+                now_ms,
+            });
+        }
+        Ok(agent_info)
     }
 
     /// Thin wrapper around AsRef for KitsuneSignature.
@@ -85,6 +146,15 @@ pub struct AgentInfo {
     // The expiry ttl for the agent info relative to the signing time.
     #[as_ref(ignore)]
     expires_after_ms: u64,
+    // The arc of the dht this agent claims to hold and gossip.
+    // Agent infos signed before arcs were added claim the full arc.
+    #[as_ref(ignore)]
+    #[serde(default = "full_dht_arc")]
+    dht_arc: DhtArc,
+}
+
+fn full_dht_arc() -> DhtArc {
+    DhtArc::new(0, MAX_HALF_LENGTH)
 }
 
 impl std::convert::TryFrom<&AgentInfoSigned> for AgentInfo {
@@ -104,6 +174,7 @@ impl AgentInfo {
         urls: Urls,
         signed_at_ms: u64,
         expires_after_ms: u64,
+        dht_arc: DhtArc,
     ) -> Self {
         Self {
             space,
@@ -111,6 +182,7 @@ impl AgentInfo {
             urls,
             signed_at_ms,
             expires_after_ms,
+            dht_arc,
         }
     }
 }
@@ -146,6 +218,25 @@ impl AgentInfo {
     pub fn expires_after_ms(&self) -> u64 {
         self.expires_after_ms
     }
+
+    /// The unix ms timestamp this agent info expires at.
+    pub fn expires_at_ms(&self) -> u64 {
+        self.signed_at_ms.saturating_add(self.expires_after_ms)
+    }
+
+    /// Whether this agent info has expired as of `now_ms`.
+    /// An expiry that overflows a u64 counts as expired.
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        self.signed_at_ms
+            .checked_add(self.expires_after_ms)
+            .map(|expires_at_ms| expires_at_ms <= now_ms)
+            .unwrap_or(true)
+    }
+
+    /// Accessor for dht_arc.
+    pub fn dht_arc(&self) -> DhtArc {
+        self.dht_arc
+    }
 }
 
 impl From<AgentInfoSigned> for KitsuneAgent {
@@ -159,3 +250,64 @@ impl From<AgentInfo> for KitsuneAgent {
         ai.agent
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct AgentInfoWithoutArc {
+        space: KitsuneSpace,
+        agent: KitsuneAgent,
+        urls: Urls,
+        signed_at_ms: u64,
+        expires_after_ms: u64,
+    }
+
+    #[test]
+    fn agent_info_without_arc_decodes_with_full_arc() {
+        let old = AgentInfoWithoutArc {
+            space: KitsuneSpace::new(vec![0; 36]),
+            agent: KitsuneAgent::new(vec![1; 36]),
+            urls: vec![],
+            signed_at_ms: 1,
+            expires_after_ms: 2,
+        };
+        let mut bytes = Vec::new();
+        kitsune_p2p_types::codec::rmp_encode(&mut bytes, &old).unwrap();
+        let info: AgentInfo = kitsune_p2p_types::codec::rmp_decode(&mut &*bytes).unwrap();
+        assert_eq!(info.dht_arc(), full_dht_arc());
+        assert_eq!(info.expires_after_ms(), 2);
+    }
+
+    fn signed_at(signed_at_ms: u64) -> AgentInfoSigned {
+        use ring::signature::KeyPair;
+        let pkcs8 =
+            ring::signature::Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+                .unwrap();
+        let keypair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let agent = KitsuneAgent::new(keypair.public_key().as_ref().to_vec());
+        let agent_info = AgentInfo::new(
+            KitsuneSpace::new(vec![0; 36]),
+            agent.clone(),
+            vec![],
+            signed_at_ms,
+            60_000,
+            full_dht_arc(),
+        );
+        let mut data = Vec::new();
+        kitsune_p2p_types::codec::rmp_encode(&mut data, &agent_info).unwrap();
+        let signature = KitsuneSignature(keypair.sign(&data).as_ref().to_vec());
+        AgentInfoSigned::try_new(agent, signature, data).unwrap()
+    }
+
+    #[test]
+    fn agent_info_from_the_near_future_is_allowed() {
+        let now = 1_000_000_000;
+        assert!(signed_at(now + MAX_CLOCK_SKEW_MS).verify(now).is_ok());
+        assert!(matches!(
+            signed_at(now + MAX_CLOCK_SKEW_MS + 1).verify(now),
+            Err(KitsuneP2pError::AgentInfoFromFuture { .. })
+        ));
+    }
+}
@@ -53,5 +53,7 @@ pub struct GossipEvt {
 pub type OpHashesAgentHashes = (Vec<Arc<KitsuneOpHash>>, Vec<(Arc<KitsuneAgent>, u64)>);
 /// The Dht op data and agent store information
 pub type OpDataAgentInfo = (Vec<(Arc<KitsuneOpHash>, Vec<u8>)>, Vec<AgentInfoSigned>);
+/// An agent along with the arc of the dht it holds.
+pub type AgentArc = (Arc<KitsuneAgent>, DhtArc);
 /// Local and remote neighbors.
-pub type ListNeighborAgents = (Vec<Arc<KitsuneAgent>>, Vec<Arc<KitsuneAgent>>);
+pub type ListNeighborAgents = (Vec<AgentArc>, Vec<AgentArc>);
@@ -0,0 +1,28 @@
+#![deny(missing_docs)]
+//! TCP+TLS transport module for kitsune-p2p
+//!
+//! A fallback for networks that drop UDP, and with it QUIC.
+//! Each connection is a single TLS stream, on which any number of
+//! kitsune channels are multiplexed.
+
+/// Re-exported dependencies.
+pub mod dependencies {
+    pub use ::kitsune_p2p_types;
+    pub use ::tokio_rustls;
+}
+
+use kitsune_p2p_types::dependencies::url2::*;
+
+const SCHEME: &str = "kitsune-tcp";
+
+mod config;
+pub use config::*;
+
+mod tls;
+
+mod mux;
+
+mod listener;
+pub use listener::*;
+
+mod test;
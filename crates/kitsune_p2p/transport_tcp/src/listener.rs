@@ -0,0 +1,330 @@
+use crate::mux::*;
+use crate::*;
+use futures::future::FutureExt;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::dependencies::ghost_actor;
+use kitsune_p2p_types::dependencies::serde_json;
+use kitsune_p2p_types::dependencies::url2;
+use kitsune_p2p_types::transport::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// How long a remote that connected to us has to complete the TLS handshake,
+/// so stalled handshakes can't pile up.
+const TLS_ACCEPT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// An outgoing connection being established.
+/// Shared so every request to the url while dialing can wait on it.
+type PendingDial = futures::future::Shared<
+    futures::future::BoxFuture<'static, Result<Arc<MuxConnection>, String>>,
+>;
+
+/// TCP+TLS implementation of kitsune TransportListener actor.
+struct TransportListenerTcp {
+    /// internal api logic
+    internal_sender: ghost_actor::GhostSender<ListenerInner>,
+    /// incoming channel send to our owner
+    incoming_channel_sender: TransportEventSender,
+    /// the url to return on 'bound_url' calls - what we bound to
+    bound_url: Url2,
+    /// tls config for establishing outgoing connections
+    tls_client_config: Arc<rustls::ClientConfig>,
+    /// stops accepting incoming connections
+    accept_abort: futures::future::AbortHandle,
+    /// pool of active connections
+    connections: HashMap<Url2, Arc<MuxConnection>>,
+    /// outgoing connections still being established
+    pending_dials: HashMap<Url2, PendingDial>,
+}
+
+impl ghost_actor::GhostControlHandler for TransportListenerTcp {
+    fn handle_ghost_actor_shutdown(
+        mut self,
+    ) -> ghost_actor::dependencies::must_future::MustBoxFuture<'static, ()> {
+        async move {
+            let _ = self.incoming_channel_sender.close_channel();
+            self.accept_abort.abort();
+            for (_, con) in self.connections.into_iter() {
+                con.close();
+            }
+        }
+        .boxed()
+        .into()
+    }
+}
+
+ghost_actor::ghost_chan! {
+    /// Internal Sender
+    chan ListenerInner<TransportError> {
+        /// Places a connection in our hash map
+        /// for use establishing outgoing channels.
+        fn set_connection(
+            url: Url2,
+            con: Arc<MuxConnection>,
+        ) -> ();
+
+        /// Once a connection has closed, remove it from our pool,
+        /// unless it has already been replaced by a newer one.
+        fn drop_connection(
+            url: Url2,
+            con: Arc<MuxConnection>,
+        ) -> ();
+
+        /// Once an outgoing connection is established (or failed),
+        /// stop handing out its pending dial.
+        fn dial_finished(url: Url2) -> ();
+    }
+}
+
+impl ghost_actor::GhostHandler<ListenerInner> for TransportListenerTcp {}
+
+impl ListenerInnerHandler for TransportListenerTcp {
+    fn handle_set_connection(
+        &mut self,
+        url: Url2,
+        con: Arc<MuxConnection>,
+    ) -> ListenerInnerHandlerResult<()> {
+        // Any channels still open on a connection we replace keep running,
+        // it is closed by the remote or on shutdown.
+        self.connections.insert(url, con);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_drop_connection(
+        &mut self,
+        url: Url2,
+        con: Arc<MuxConnection>,
+    ) -> ListenerInnerHandlerResult<()> {
+        if let Some(cur) = self.connections.get(&url) {
+            if Arc::ptr_eq(cur, &con) {
+                self.connections.remove(&url);
+            }
+        }
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_dial_finished(&mut self, url: Url2) -> ListenerInnerHandlerResult<()> {
+        self.pending_dials.remove(&url);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+}
+
+/// Shared code for both incoming and outgoing connections.
+/// Multiplex channels over the TLS stream and track the connection
+/// in our pool until it closes.
+async fn take_stream<S>(
+    i_s: ghost_actor::GhostSender<ListenerInner>,
+    stream: S,
+    role: Role,
+    url: Url2,
+    incoming_channel_sender: TransportEventSender,
+) -> TransportResult<Arc<MuxConnection>>
+where
+    S: 'static + tokio::io::AsyncRead + tokio::io::AsyncWrite + Send,
+{
+    tracing::debug!("TCP handle connection: {}", url);
+    let (con, closed) = MuxConnection::spawn(stream, role, url.clone(), incoming_channel_sender);
+    i_s.set_connection(url.clone(), con.clone()).await?;
+    let con_clone = con.clone();
+    tokio::task::spawn(async move {
+        let _ = closed.await;
+        i_s.drop_connection(url, con_clone).await?;
+        TransportResult::Ok(())
+    });
+    Ok(con)
+}
+
+/// Establish an outgoing connection to `url` and add it to our pool.
+async fn dial(
+    i_s: ghost_actor::GhostSender<ListenerInner>,
+    incoming_channel_sender: TransportEventSender,
+    connector: tokio_rustls::TlsConnector,
+    url: Url2,
+) -> Result<Arc<MuxConnection>, String> {
+    let con = async {
+        let addr = url_to_addr(&url, crate::SCHEME).await?;
+        tracing::debug!("attempt raw connect: {:?}", addr);
+        let stream = tokio::net::TcpStream::connect(addr)
+            .await
+            .map_err(TransportError::other)?;
+        stream.set_nodelay(true).map_err(TransportError::other)?;
+        let dns_name = webpki::DNSNameRef::try_from_ascii_str("stub.stub").unwrap();
+        let stream = connector
+            .connect(dns_name, stream)
+            .await
+            .map_err(TransportError::other)?;
+        take_stream(
+            i_s.clone(),
+            stream,
+            Role::Dialer,
+            url.clone(),
+            incoming_channel_sender,
+        )
+        .await
+    }
+    .await;
+    let _ = i_s.dial_finished(url).await;
+    con.map_err(|err| err.to_string())
+}
+
+impl ghost_actor::GhostHandler<TransportListener> for TransportListenerTcp {}
+
+impl TransportListenerHandler for TransportListenerTcp {
+    fn handle_debug(&mut self) -> TransportListenerHandlerResult<serde_json::Value> {
+        let url = self.bound_url.clone();
+        let connections = self.connections.keys().cloned().collect::<Vec<_>>();
+        Ok(async move {
+            Ok(serde_json::json! {{
+                "url": url,
+                "connections": connections,
+            }})
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_bound_url(&mut self) -> TransportListenerHandlerResult<Url2> {
+        let out = self.bound_url.clone();
+        Ok(async move { Ok(out) }.boxed().into())
+    }
+
+    fn handle_create_channel(
+        &mut self,
+        url: Url2,
+    ) -> TransportListenerHandlerResult<(Url2, TransportChannelWrite, TransportChannelRead)> {
+        // if we already have an open connection to the remote end,
+        // just open a new channel on it.
+        if let Some(con) = self.connections.get(&url) {
+            if let Ok((write, read)) = con.create_channel() {
+                return Ok(async move { Ok((url, write, read)) }.boxed().into());
+            }
+        }
+
+        // otherwise wait on the dial to the remote end, starting one
+        // if no other request already has.
+        let pending = match self.pending_dials.get(&url) {
+            Some(pending) => pending.clone(),
+            None => {
+                let connector = tokio_rustls::TlsConnector::from(self.tls_client_config.clone());
+                // spawned so the dial finishes even if every request waiting on it is dropped
+                let pending = tokio::task::spawn(dial(
+                    self.internal_sender.clone(),
+                    self.incoming_channel_sender.clone(),
+                    connector,
+                    url.clone(),
+                ))
+                .map(|res| res.map_err(|err| err.to_string()).and_then(|con| con))
+                .boxed()
+                .shared();
+                self.pending_dials.insert(url.clone(), pending.clone());
+                pending
+            }
+        };
+        Ok(async move {
+            let con = pending.await.map_err(TransportError::from)?;
+            let (write, read) = con.create_channel()?;
+
+            Ok((url, write, read))
+        }
+        .boxed()
+        .into())
+    }
+}
+
+/// Spawn a new TCP+TLS TransportListenerSender.
+pub async fn spawn_transport_listener_tcp(
+    config: ConfigListenerTcp,
+) -> TransportListenerResult<(
+    ghost_actor::GhostSender<TransportListener>,
+    TransportEventReceiver,
+)> {
+    let bind_to = config
+        .bind_to
+        .unwrap_or_else(|| url2::url2!("kitsune-tcp://0.0.0.0:0"));
+    let (tls_server_config, tls_client_config) = crate::tls::gen_tls_configs(config.tls).await?;
+    let mut tcp_listener =
+        tokio::net::TcpListener::bind(url_to_addr(&bind_to, crate::SCHEME).await?)
+            .await
+            .map_err(TransportError::other)?;
+    let local_addr = tcp_listener.local_addr().map_err(TransportError::other)?;
+
+    let (incoming_channel_sender, receiver) = futures::channel::mpsc::channel(10);
+
+    let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
+
+    let internal_sender = builder.channel_factory().create_channel().await?;
+
+    let sender = builder.channel_factory().create_channel().await?;
+
+    let i_s = internal_sender.clone();
+    let i_c_s = incoming_channel_sender.clone();
+    let acceptor = tokio_rustls::TlsAcceptor::from(tls_server_config);
+    let (accept, accept_abort) = futures::future::abortable(async move {
+        loop {
+            let (stream, addr) = match tcp_listener.accept().await {
+                Ok(r) => r,
+                Err(err) => {
+                    tracing::error!(?err);
+                    continue;
+                }
+            };
+            let i_s = i_s.clone();
+            let i_c_s = i_c_s.clone();
+            let acceptor = acceptor.clone();
+            tokio::task::spawn(async move {
+                let res: TransportResult<()> = async {
+                    stream.set_nodelay(true).map_err(TransportError::other)?;
+                    let stream = tokio::time::timeout(TLS_ACCEPT_TIMEOUT, acceptor.accept(stream))
+                        .await
+                        .map_err(TransportError::other)?
+                        .map_err(TransportError::other)?;
+                    let url = url2!("{}://{}", crate::SCHEME, addr);
+                    take_stream(i_s, stream, Role::Acceptor, url, i_c_s).await?;
+                    Ok(())
+                }
+                .await;
+                if let Err(err) = res {
+                    tracing::error!(?err);
+                }
+            });
+        }
+    });
+    tokio::task::spawn(accept);
+
+    let mut bound_url = url2!("{}://{}", crate::SCHEME, local_addr);
+    if let Some(override_host) = &config.override_host {
+        bound_url.set_host(Some(override_host)).unwrap();
+    } else if let Some(host) = bound_url.host_str() {
+        if host == "0.0.0.0" {
+            for iface in if_addrs::get_if_addrs().map_err(TransportError::other)? {
+                // super naive - just picking the first v4 that is not 127.0.0.1
+                let addr = iface.addr.ip();
+                if let std::net::IpAddr::V4(addr) = addr {
+                    if addr != std::net::Ipv4Addr::from([127, 0, 0, 1]) {
+                        bound_url
+                            .set_host(Some(&iface.addr.ip().to_string()))
+                            .unwrap();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    if let Some(override_port) = config.override_port {
+        bound_url.set_port(Some(override_port)).unwrap();
+    }
+
+    let actor = TransportListenerTcp {
+        internal_sender,
+        incoming_channel_sender,
+        bound_url,
+        tls_client_config,
+        accept_abort,
+        connections: HashMap::new(),
+        pending_dials: HashMap::new(),
+    };
+
+    tokio::task::spawn(builder.spawn(actor));
+
+    Ok((sender, receiver))
+}
@@ -0,0 +1,476 @@
+//! Multiplex kitsune channels over a single TLS stream.
+//!
+//! Every frame is a 13 byte header - the channel id (u64 BE),
+//! the frame kind (u8) and the payload length (u32 BE) - then the payload.
+//! The side that dialed the connection numbers its channels evenly,
+//! the side that accepted it oddly, so either side can open channels.
+//! The first frame for an unknown channel in the remote's numbering
+//! opens a new incoming channel. A `CLOSE` frame ends the sender's
+//! half of a channel.
+//!
+//! Each side may only have [`WINDOW_FRAMES`] data frames per channel that
+//! the remote has not yet read. An `ACK` frame, carrying a u32 BE count,
+//! hands back credit for frames once they are read. A slow reader only
+//! stalls the writer of its own channel, never the other channels.
+//! Beyond [`MAX_CHANNELS`] open channels the remote opens are refused by
+//! closing them straight away, and beyond [`MAX_REFUSED`] refused channels
+//! still awaiting the remote's close the connection is closed.
+//!
+//! Acks for a channel are coalesced until they can be written, so the
+//! frames waiting to be written are bounded by the number of channels.
+
+use crate::*;
+use futures::channel::mpsc;
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::dependencies::ghost_actor;
+use kitsune_p2p_types::transport::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+
+const HEADER_LEN: usize = 13;
+const KIND_DATA: u8 = 0;
+const KIND_CLOSE: u8 = 1;
+const KIND_ACK: u8 = 2;
+
+/// Writes are split into frames of at most this many bytes,
+/// larger frames from the remote end the connection.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// How many data frames may be sent on a channel before the remote
+/// acknowledges reading them.
+const WINDOW_FRAMES: usize = 16;
+
+/// How many channels each side may have open on a connection.
+pub(crate) const MAX_CHANNELS: usize = 1024;
+
+/// How many refused channels the remote may leave open before we give up
+/// on the connection.
+pub(crate) const MAX_REFUSED: usize = 16;
+
+enum Frame {
+    Data(u64, Vec<u8>),
+    Close(u64),
+    Ack(u64, u32),
+}
+
+/// Ack and refusal frames waiting to be written.
+#[derive(Default)]
+struct Control {
+    /// credit to hand back, by channel id
+    acks: HashMap<u64, u32>,
+    /// channels to refuse
+    closes: Vec<u64>,
+}
+
+impl Control {
+    fn ack(&mut self, id: u64, count: u32) {
+        let credit = self.acks.entry(id).or_insert(0);
+        *credit = credit.saturating_add(count);
+    }
+
+    fn pop(&mut self) -> Option<Frame> {
+        if let Some(id) = self.closes.pop() {
+            return Some(Frame::Close(id));
+        }
+        let id = *self.acks.keys().next()?;
+        self.acks.remove(&id).map(|count| Frame::Ack(id, count))
+    }
+}
+
+/// Which end of the connection we are.
+#[derive(Clone, Copy)]
+pub(crate) enum Role {
+    Dialer,
+    Acceptor,
+}
+
+impl Role {
+    fn first_id(self) -> u64 {
+        match self {
+            Role::Dialer => 0,
+            Role::Acceptor => 1,
+        }
+    }
+
+    fn is_remote_id(self, id: u64) -> bool {
+        id % 2 != self.first_id()
+    }
+}
+
+/// State shared between a connection handle and its reader task.
+struct Shared {
+    role: Role,
+    url: Url2,
+    /// the read halves of the open channels, by channel id
+    channels: Mutex<HashMap<u64, mpsc::Sender<Vec<u8>>>>,
+    /// credit for the write halves of the open channels, by channel id
+    credits: Mutex<HashMap<u64, mpsc::UnboundedSender<u32>>>,
+    /// channels the remote opened that we refused, until the remote closes them
+    refused: Mutex<HashSet<u64>>,
+    /// data and close frames to be written to the stream
+    frame_send: mpsc::Sender<Frame>,
+    /// ack and refusal frames, which must never wait on data frames
+    control: Mutex<Control>,
+    /// wakes the writer when there are control frames to write
+    control_notify: tokio::sync::Notify,
+}
+
+impl Shared {
+    /// Register channel `id` and return its kitsune write / read halves.
+    fn new_channel(self: &Arc<Self>, id: u64) -> (TransportChannelWrite, TransportChannelRead) {
+        let (read_send, read_recv) = mpsc::channel::<Vec<u8>>(WINDOW_FRAMES);
+        self.channels.lock().unwrap().insert(id, read_send);
+        let (credit_send, mut credit_recv) = mpsc::unbounded::<u32>();
+        self.credits.lock().unwrap().insert(id, credit_send);
+
+        let (write_send, mut write_recv) = mpsc::channel::<Vec<u8>>(10);
+        let shared = self.clone();
+        tokio::task::spawn(async move {
+            let mut frame_send = shared.frame_send.clone();
+            let mut credit = WINDOW_FRAMES;
+            'write: while let Some(data) = write_recv.next().await {
+                for chunk in data.chunks(MAX_FRAME_LEN) {
+                    while credit == 0 {
+                        match credit_recv.next().await {
+                            Some(more) => credit += more as usize,
+                            // the connection has closed
+                            None => break 'write,
+                        }
+                    }
+                    credit -= 1;
+                    if frame_send
+                        .send(Frame::Data(id, chunk.to_vec()))
+                        .await
+                        .is_err()
+                    {
+                        break 'write;
+                    }
+                }
+            }
+            let _ = frame_send.send(Frame::Close(id)).await;
+            shared.credits.lock().unwrap().remove(&id);
+        });
+
+        let write_send: TransportChannelWrite =
+            Box::new(write_send.sink_map_err(TransportError::other));
+        let read_recv: TransportChannelRead = Box::new(ChannelRead {
+            id,
+            recv: read_recv,
+            shared: self.clone(),
+        });
+        (write_send, read_recv)
+    }
+
+    /// How many open channels were opened by the remote, or by us.
+    fn open_channels(&self, remote: bool) -> usize {
+        let channels = self.channels.lock().unwrap();
+        let credits = self.credits.lock().unwrap();
+        channels
+            .keys()
+            .chain(credits.keys().filter(|id| !channels.contains_key(id)))
+            .filter(|id| self.role.is_remote_id(**id) == remote)
+            .count()
+    }
+
+    /// Hand `count` frames of credit on channel `id` back to the remote.
+    fn ack(&self, id: u64, count: u32) {
+        self.control.lock().unwrap().ack(id, count);
+        self.control_notify.notify();
+    }
+
+    /// Refuse channel `id` the remote opened.
+    /// Fails if the remote has too many refused channels left open.
+    fn refuse(&self, id: u64, is_data: bool) -> TransportResult<()> {
+        let mut control = self.control.lock().unwrap();
+        let mut refused = self.refused.lock().unwrap();
+        if refused.len() >= MAX_REFUSED || control.closes.len() >= MAX_REFUSED {
+            return Err(format!("remote left over {} refused channels open", MAX_REFUSED).into());
+        }
+        control.closes.push(id);
+        if is_data {
+            refused.insert(id);
+            // keep the remote's writer going until it closes
+            control.ack(id, 1);
+        }
+        self.control_notify.notify();
+        Ok(())
+    }
+
+    /// The control frames to write, ending once the connection closes.
+    fn control_frames(self: Arc<Self>) -> impl futures::stream::Stream<Item = Frame> {
+        futures::stream::unfold(self, |shared| async move {
+            loop {
+                if shared.frame_send.is_closed() {
+                    return None;
+                }
+                let frame = shared.control.lock().unwrap().pop();
+                if let Some(frame) = frame {
+                    return Some((frame, shared));
+                }
+                shared.control_notify.notified().await;
+            }
+        })
+    }
+
+    /// Drop all channels and stop writing frames.
+    fn close(&self) {
+        self.channels.lock().unwrap().clear();
+        self.credits.lock().unwrap().clear();
+        self.frame_send.clone().close_channel();
+        self.control_notify.notify();
+    }
+}
+
+/// The read half of a channel, handing back credit for frames as they are read.
+struct ChannelRead {
+    id: u64,
+    recv: mpsc::Receiver<Vec<u8>>,
+    shared: Arc<Shared>,
+}
+
+impl futures::stream::Stream for ChannelRead {
+    type Item = Vec<u8>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Vec<u8>>> {
+        let data = futures::ready!(self.recv.poll_next_unpin(cx));
+        if data.is_some() {
+            self.shared.ack(self.id, 1);
+        }
+        std::task::Poll::Ready(data)
+    }
+}
+
+impl Drop for ChannelRead {
+    fn drop(&mut self) {
+        // nothing more will be read, don't leave the remote's writer waiting
+        // on credit for frames still buffered here
+        self.shared.ack(self.id, WINDOW_FRAMES as u32);
+    }
+}
+
+/// A single TLS stream carrying any number of kitsune channels.
+pub(crate) struct MuxConnection {
+    shared: Arc<Shared>,
+    next_id: AtomicU64,
+    reader: futures::future::AbortHandle,
+}
+
+impl MuxConnection {
+    /// Take over `stream`, forwarding channels the remote opens
+    /// to `incoming`. The returned receiver resolves (or errors)
+    /// once the connection has closed.
+    pub(crate) fn spawn<S>(
+        stream: S,
+        role: Role,
+        url: Url2,
+        incoming: TransportEventSender,
+    ) -> (Arc<Self>, futures::channel::oneshot::Receiver<()>)
+    where
+        S: 'static + AsyncRead + AsyncWrite + Send,
+    {
+        let (read, write) = tokio::io::split(stream);
+        let (frame_send, frame_recv) = mpsc::channel(10);
+        let shared = Arc::new(Shared {
+            role,
+            url,
+            channels: Mutex::new(HashMap::new()),
+            credits: Mutex::new(HashMap::new()),
+            refused: Mutex::new(HashSet::new()),
+            frame_send,
+            control: Mutex::new(Control::default()),
+            control_notify: tokio::sync::Notify::new(),
+        });
+
+        let control_frames = Box::pin(shared.clone().control_frames());
+        tokio::task::spawn(async move {
+            let frames = futures::stream::select(control_frames, frame_recv);
+            if let Err(err) = write_frames(write, frames).await {
+                tracing::debug!(?err, "TCP connection write failed");
+            }
+        });
+
+        let (closed_send, closed_recv) = futures::channel::oneshot::channel();
+        let (reader, abort) =
+            futures::future::abortable(read_frames(read, shared.clone(), incoming));
+        let shared_clone = shared.clone();
+        tokio::task::spawn(async move {
+            if let Ok(Err(err)) = reader.await {
+                tracing::debug!(?err, "TCP connection read failed");
+            }
+            shared_clone.close();
+            let _ = closed_send.send(());
+        });
+
+        let con = Arc::new(Self {
+            shared,
+            next_id: AtomicU64::new(role.first_id()),
+            reader: abort,
+        });
+        (con, closed_recv)
+    }
+
+    /// Open a new outgoing channel on this connection.
+    pub(crate) fn create_channel(
+        &self,
+    ) -> TransportResult<(TransportChannelWrite, TransportChannelRead)> {
+        if self.shared.frame_send.is_closed() {
+            return Err(format!("connection to {} is closed", self.shared.url).into());
+        }
+        if self.shared.open_channels(false) >= MAX_CHANNELS {
+            return Err(format!(
+                "connection to {} already has {} open channels",
+                self.shared.url, MAX_CHANNELS
+            )
+            .into());
+        }
+        let id = self.next_id.fetch_add(2, Ordering::Relaxed);
+        Ok(self.shared.new_channel(id))
+    }
+
+    /// Close the connection, ending all of its channels.
+    pub(crate) fn close(&self) {
+        self.reader.abort();
+        self.shared.close();
+    }
+}
+
+async fn write_frames<W, F>(mut write: W, mut frames: F) -> TransportResult<()>
+where
+    W: AsyncWrite + Unpin,
+    F: futures::stream::Stream<Item = Frame> + Unpin,
+{
+    while let Some(frame) = frames.next().await {
+        let (id, kind, data) = match frame {
+            Frame::Data(id, data) => (id, KIND_DATA, data),
+            Frame::Close(id) => (id, KIND_CLOSE, Vec::new()),
+            Frame::Ack(id, count) => (id, KIND_ACK, count.to_be_bytes().to_vec()),
+        };
+        let mut buf = Vec::with_capacity(HEADER_LEN + data.len());
+        buf.extend_from_slice(&id.to_be_bytes());
+        buf.push(kind);
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&data);
+        write.write_all(&buf).await.map_err(TransportError::other)?;
+        write.flush().await.map_err(TransportError::other)?;
+    }
+    write.shutdown().await.map_err(TransportError::other)?;
+    Ok(())
+}
+
+async fn read_frames<R>(
+    mut read: R,
+    shared: Arc<Shared>,
+    mut incoming: TransportEventSender,
+) -> TransportResult<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0_u8; HEADER_LEN];
+    loop {
+        match read.read_exact(&mut header).await {
+            Ok(_) => (),
+            // the remote closed the connection between frames
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(TransportError::other(e)),
+        }
+        let id = u64::from_be_bytes(header[0..8].try_into().unwrap());
+        let kind = header[8];
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(format!("frame of {} bytes exceeds {}", len, MAX_FRAME_LEN).into());
+        }
+        let mut data = vec![0; len];
+        read.read_exact(&mut data)
+            .await
+            .map_err(TransportError::other)?;
+
+        if kind == KIND_ACK {
+            let count = data
+                .as_slice()
+                .try_into()
+                .map(u32::from_be_bytes)
+                .map_err(|_| TransportError::from(format!("bad ack of {} bytes", len)))?;
+            let credit_send = shared.credits.lock().unwrap().get(&id).cloned();
+            if let Some(credit_send) = credit_send {
+                // the writer may have finished, that's fine
+                let _ = credit_send.unbounded_send(count);
+            }
+            continue;
+        }
+
+        if shared.refused.lock().unwrap().contains(&id) {
+            match kind {
+                // keep the remote's writer going until it closes
+                KIND_DATA => shared.ack(id, 1),
+                _ => {
+                    shared.refused.lock().unwrap().remove(&id);
+                }
+            }
+            continue;
+        }
+
+        let known = shared.channels.lock().unwrap().contains_key(&id);
+        if !known {
+            if !shared.role.is_remote_id(id) {
+                // a late frame for one of our own channels that has ended
+                continue;
+            }
+            if shared.open_channels(true) >= MAX_CHANNELS {
+                tracing::debug!(id, "TCP connection refusing channel, too many open");
+                shared.refuse(id, kind == KIND_DATA)?;
+                continue;
+            }
+            let (write, read) = shared.new_channel(id);
+            if incoming
+                .send(TransportEvent::IncomingChannel(
+                    shared.url.clone(),
+                    write,
+                    read,
+                ))
+                .await
+                .is_err()
+            {
+                // no-one is listening for incoming channels any more
+                return Ok(());
+            }
+        }
+
+        match kind {
+            KIND_DATA => {
+                let read_send = shared.channels.lock().unwrap().get(&id).cloned();
+                if let Some(mut read_send) = read_send {
+                    match read_send.try_send(data) {
+                        Ok(()) => (),
+                        // the reader has been dropped, keep the remote's writer going
+                        Err(e) if e.is_disconnected() => shared.ack(id, 1),
+                        Err(_) => {
+                            return Err(format!(
+                                "channel {} sent more than {} unread frames",
+                                id, WINDOW_FRAMES
+                            )
+                            .into());
+                        }
+                    }
+                }
+            }
+            KIND_CLOSE => {
+                shared.channels.lock().unwrap().remove(&id);
+            }
+            _ => return Err(format!("unknown frame kind {}", kind).into()),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use futures::stream::StreamExt;
+    use kitsune_p2p_types::transport::*;
+
+    fn echo_receiver(mut events: TransportEventReceiver) {
+        tokio::task::spawn(async move {
+            while let Some(evt) = events.next().await {
+                match evt {
+                    TransportEvent::IncomingChannel(_url, mut write, read) => {
+                        let data = read.read_to_end().await;
+                        let data = format!("echo: {}", String::from_utf8_lossy(&data)).into_bytes();
+                        write.write_and_close(data).await?;
+                    }
+                }
+            }
+            TransportResult::Ok(())
+        });
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_message() {
+        let (listener1, _events1) = spawn_transport_listener_tcp(
+            ConfigListenerTcp::default().set_override_host(Some("127.0.0.1")),
+        )
+        .await
+        .unwrap();
+
+        let bound1 = listener1.bound_url().await.unwrap();
+        assert_eq!("127.0.0.1", bound1.host_str().unwrap());
+        assert_eq!("kitsune-tcp", bound1.scheme());
+        println!("listener1 bound to: {}", bound1);
+
+        let (listener2, events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events2);
+
+        let bound2 = listener2.bound_url().await.unwrap();
+        println!("listener2 bound to: {}", bound2);
+
+        let resp = listener1.request(bound2, b"hello".to_vec()).await.unwrap();
+
+        println!("got resp: {}", String::from_utf8_lossy(&resp));
+
+        assert_eq!("echo: hello", &String::from_utf8_lossy(&resp));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_large_message() {
+        let (listener1, _events1) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+
+        let (listener2, events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events2);
+
+        let bound2 = listener2.bound_url().await.unwrap();
+
+        // larger than a single frame
+        let large_msg = std::iter::repeat(b"a"[0]).take(200_000).collect::<Vec<_>>();
+        let resp = listener1.request(bound2, large_msg.clone()).await.unwrap();
+
+        assert_eq!(
+            format!("echo: {}", String::from_utf8_lossy(&large_msg)),
+            String::from_utf8_lossy(&resp)
+        );
+        assert_eq!(resp.len(), 200_006);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_concurrent_channels_share_connection() {
+        let (listener1, events1) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events1);
+
+        let (listener2, events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events2);
+        let bound2 = listener2.bound_url().await.unwrap();
+
+        // establish the connection before racing channels over it
+        listener1
+            .request(bound2.clone(), b"hi".to_vec())
+            .await
+            .unwrap();
+
+        let all = (0..20_u32)
+            .map(|i| {
+                let listener1 = listener1.clone();
+                let bound2 = bound2.clone();
+                async move {
+                    let msg = format!("{}", i).into_bytes();
+                    let resp = listener1.request(bound2, msg).await.unwrap();
+                    assert_eq!(format!("echo: {}", i), String::from_utf8_lossy(&resp));
+                }
+            })
+            .collect::<Vec<_>>();
+        futures::future::join_all(all).await;
+
+        let debug = listener1.debug().await.unwrap();
+        assert_eq!(1, debug["connections"].as_array().unwrap().len());
+
+        // the accepting end can open channels back over the same connection
+        let debug = listener2.debug().await.unwrap();
+        let back_url = Url2::parse(debug["connections"][0].as_str().unwrap());
+        let resp = listener2.request(back_url, b"back".to_vec()).await.unwrap();
+        assert_eq!("echo: back", &String::from_utf8_lossy(&resp));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_concurrent_first_requests_share_one_dial() {
+        let (listener1, _events1) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+
+        let (listener2, events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events2);
+        let bound2 = listener2.bound_url().await.unwrap();
+
+        // no connection yet, every request races to establish one
+        let all = (0..20_u32)
+            .map(|i| {
+                let listener1 = listener1.clone();
+                let bound2 = bound2.clone();
+                async move {
+                    let msg = format!("{}", i).into_bytes();
+                    let resp = listener1.request(bound2, msg).await.unwrap();
+                    assert_eq!(format!("echo: {}", i), String::from_utf8_lossy(&resp));
+                }
+            })
+            .collect::<Vec<_>>();
+        futures::future::join_all(all).await;
+
+        let debug = listener1.debug().await.unwrap();
+        assert_eq!(1, debug["connections"].as_array().unwrap().len());
+        let debug = listener2.debug().await.unwrap();
+        assert_eq!(1, debug["connections"].as_array().unwrap().len());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_stalled_reader_does_not_block_other_channels() {
+        let (listener1, _events1) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+
+        let (listener2, mut events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        let bound2 = listener2.bound_url().await.unwrap();
+
+        // hold the first incoming channel without reading it, echo the rest
+        let (stalled_send, stalled_recv) = futures::channel::oneshot::channel();
+        tokio::task::spawn(async move {
+            if let Some(TransportEvent::IncomingChannel(_url, _write, read)) = events2.next().await
+            {
+                let _ = stalled_send.send(read);
+            }
+            echo_receiver(events2);
+        });
+
+        // far more than the remote will buffer for an unread channel
+        let large_msg = std::iter::repeat(b"a"[0])
+            .take(4 * 1024 * 1024)
+            .collect::<Vec<_>>();
+        let (_url, mut write, _read) = listener1.create_channel(bound2.clone()).await.unwrap();
+        let writer = tokio::task::spawn(async move { write.write_and_close(large_msg).await });
+        let stalled = stalled_recv.await.unwrap();
+
+        let resp = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            listener1.request(bound2, b"hello".to_vec()),
+        )
+        .await
+        .expect("request blocked behind the stalled channel")
+        .unwrap();
+        assert_eq!("echo: hello", &String::from_utf8_lossy(&resp));
+
+        // once read, the stalled channel completes
+        assert_eq!(4 * 1024 * 1024, stalled.read_to_end().await.len());
+        writer.await.unwrap().unwrap();
+    }
+
+    /// Write a raw mux frame.
+    async fn write_frame(stream: &mut tokio::net::TcpStream, id: u64, kind: u8, data: &[u8]) {
+        use tokio::io::AsyncWriteExt;
+        let mut buf = id.to_be_bytes().to_vec();
+        buf.push(kind);
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(data);
+        stream.write_all(&buf).await.unwrap();
+    }
+
+    /// Read raw mux frames until a close for `id`, or the end of the stream.
+    /// Returns whether the close was read.
+    async fn read_close(stream: &mut tokio::net::TcpStream, id: u64) -> bool {
+        use tokio::io::AsyncReadExt;
+        let mut header = [0_u8; 13];
+        while stream.read_exact(&mut header).await.is_ok() {
+            let len = u32::from_be_bytes([header[9], header[10], header[11], header[12]]);
+            let mut data = vec![0; len as usize];
+            stream.read_exact(&mut data).await.unwrap();
+            // kind 1 is close
+            if header[8] == 1 && header[0..8] == id.to_be_bytes() {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_too_many_channels_are_refused() {
+        use crate::mux::*;
+        const DATA: u8 = 0;
+        const CLOSE: u8 = 1;
+
+        let mut tcp_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = tcp_listener.local_addr().unwrap();
+        let mut remote = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (stream, _) = tcp_listener.accept().await.unwrap();
+        let (incoming, mut events) = futures::channel::mpsc::channel(10);
+        let (_con, _closed) = MuxConnection::spawn(
+            stream,
+            Role::Acceptor,
+            Url2::parse(format!("{}://{}", crate::SCHEME, addr)),
+            incoming,
+        );
+        // hold every incoming channel open, without reading it
+        tokio::task::spawn(async move {
+            let mut held = Vec::new();
+            while let Some(evt) = events.next().await {
+                held.push(evt);
+            }
+        });
+
+        // as the dialer, our channel ids are even
+        let mut ids = (0..).map(|i: u64| i * 2);
+        for id in ids.by_ref().take(MAX_CHANNELS) {
+            write_frame(&mut remote, id, DATA, b"x").await;
+        }
+        let refused = ids.next().unwrap();
+        write_frame(&mut remote, refused, DATA, b"x").await;
+        assert!(read_close(&mut remote, refused).await);
+
+        // closing a refused channel makes room for another refusal
+        write_frame(&mut remote, refused, CLOSE, &[]).await;
+        for id in ids.by_ref().take(MAX_REFUSED) {
+            write_frame(&mut remote, id, DATA, b"x").await;
+            assert!(read_close(&mut remote, id).await);
+        }
+
+        // beyond that, the connection is closed
+        let id = ids.next().unwrap();
+        write_frame(&mut remote, id, DATA, b"x").await;
+        let refused = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            read_close(&mut remote, id),
+        )
+        .await
+        .expect("connection left open");
+        assert!(!refused);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_pooled_with_mem() {
+        use kitsune_p2p_types::transport_mem::*;
+        use kitsune_p2p_types::transport_pool::*;
+
+        let (pool1, listener1, events1) = spawn_transport_pool().await.unwrap();
+        let (sub, sub_events) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        pool1.push_sub_transport(sub, sub_events).await.unwrap();
+        let (sub, sub_events) = spawn_bind_transport_mem().await.unwrap();
+        pool1.push_sub_transport(sub, sub_events).await.unwrap();
+        echo_receiver(events1);
+
+        let (listener2, events2) = spawn_transport_listener_tcp(ConfigListenerTcp::default())
+            .await
+            .unwrap();
+        echo_receiver(events2);
+        let bound2 = listener2.bound_url().await.unwrap();
+
+        let resp = listener1.request(bound2, b"pooled".to_vec()).await.unwrap();
+        assert_eq!("echo: pooled", &String::from_utf8_lossy(&resp));
+    }
+}
@@ -0,0 +1,74 @@
+use kitsune_p2p_types::transport::*;
+use std::sync::Arc;
+
+/// Tls ALPN identifier for the kitsune tcp transport
+const ALPN_KITSUNE_TCP_0: &[u8] = b"kitsune-tcp/0";
+
+/// Allow only these cipher suites for kitsune tcp Tls.
+static CIPHER_SUITES: &[&rustls::SupportedCipherSuite] = &[
+    &rustls::ciphersuite::TLS13_CHACHA20_POLY1305_SHA256,
+    &rustls::ciphersuite::TLS13_AES_256_GCM_SHA384,
+];
+
+/// Generate the rustls configs for accepting and establishing connections,
+/// using `tls` as our certificate, or an ephemeral one if `None`.
+pub(crate) async fn gen_tls_configs(
+    tls: Option<(
+        lair_keystore_api::actor::Cert,
+        lair_keystore_api::actor::CertPrivKey,
+    )>,
+) -> TransportResult<(Arc<rustls::ServerConfig>, Arc<rustls::ClientConfig>)> {
+    let (cert, cert_priv_key) = match tls {
+        Some(r) => r,
+        None => {
+            let mut options = lair_keystore_api::actor::TlsCertOptions::default();
+            options.alg = lair_keystore_api::actor::TlsCertAlg::PkcsEcdsaP256Sha256;
+            let cert =
+                lair_keystore_api::internal::tls::tls_cert_self_signed_new_from_entropy(options)
+                    .await
+                    .map_err(TransportError::other)?;
+            (cert.cert_der, cert.priv_key_der)
+        }
+    };
+    let cert = rustls::Certificate(cert.0.to_vec());
+    let cert_priv_key = rustls::PrivateKey(cert_priv_key.0.to_vec());
+
+    let mut tls_server_config =
+        rustls::ServerConfig::with_ciphersuites(rustls::NoClientAuth::new(), CIPHER_SUITES);
+    tls_server_config
+        .set_single_cert(vec![cert], cert_priv_key)
+        .map_err(TransportError::other)?;
+    tls_server_config.set_protocols(&[ALPN_KITSUNE_TCP_0.to_vec()]);
+    let tls_server_config = Arc::new(tls_server_config);
+
+    let mut tls_client_config = rustls::ClientConfig::with_ciphersuites(CIPHER_SUITES);
+    tls_client_config
+        .dangerous()
+        .set_certificate_verifier(TlsServerVerifier::new());
+    tls_client_config.set_protocols(&[ALPN_KITSUNE_TCP_0.to_vec()]);
+    let tls_client_config = Arc::new(tls_client_config);
+
+    Ok((tls_server_config, tls_client_config))
+}
+
+/// Like the QUIC transport, we accept any server certificate here.
+/// Remote identity is checked by the proxy layer tunneled over us.
+struct TlsServerVerifier;
+
+impl TlsServerVerifier {
+    fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+}
+
+impl rustls::ServerCertVerifier for TlsServerVerifier {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls::RootCertStore,
+        _presented_certs: &[rustls::Certificate],
+        _dns_name: webpki::DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<rustls::ServerCertVerified, rustls::TLSError> {
+        Ok(rustls::ServerCertVerified::assertion())
+    }
+}
@@ -8,7 +8,9 @@ use std::ops::RangeBounds;
 #[cfg(test)]
 use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, From, Into)]
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash, From, Into,
+)]
 /// Type for representing a location that can wrap around
 /// a u32 dht arc
 pub struct DhtLocation(pub Wrapping<u32>);
@@ -20,7 +22,7 @@ pub struct DhtLocation(pub Wrapping<u32>);
 /// 1 more is added to represent the middle point of an odd length array
 pub const MAX_HALF_LENGTH: u32 = (u32::MAX / 2) + 1 + 1;
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
 /// Represents how much of a dht arc is held
 /// center_loc is where the hash is.
 /// The center_loc is the center of the arc
@@ -69,6 +71,55 @@ impl DhtArc {
         do_hold_something && (only_hold_self || within_range)
     }
 
+    /// Create an Arc that is just wide enough to reach the
+    /// `redundancy_target` nearest of the given peer locations.
+    /// If there are not enough peers to meet the target the whole
+    /// dht arc is held.
+    pub fn for_peer_density<I: Into<DhtLocation>>(
+        center_loc: I,
+        peer_locs: impl IntoIterator<Item = u32>,
+        redundancy_target: u32,
+    ) -> Self {
+        let center_loc = center_loc.into();
+        if redundancy_target == 0 {
+            return Self::new(center_loc, MAX_HALF_LENGTH);
+        }
+        let mut dists = peer_locs
+            .into_iter()
+            .map(|loc| shortest_arc_distance(center_loc, loc))
+            .collect::<Vec<_>>();
+        if dists.len() < redundancy_target as usize {
+            return Self::new(center_loc, MAX_HALF_LENGTH);
+        }
+        dists.sort_unstable();
+        // Add one to convert to "array length" from math distance
+        let half_length = dists[redundancy_target as usize - 1].saturating_add(1);
+        // A half length of one only holds the center
+        Self::new(center_loc, std::cmp::max(half_length, 2))
+    }
+
+    /// Check if this arc and another arc hold any location in common
+    pub fn overlaps(&self, other: &DhtArc) -> bool {
+        if self.half_length == 0 || other.half_length == 0 {
+            return false;
+        }
+        // Two arcs on a circle overlap iff one of them
+        // contains an end of the other.
+        let ends = |arc: &DhtArc| match arc.range() {
+            ArcRange {
+                start: Bound::Included(start),
+                end: Bound::Included(end),
+            } => (start, end),
+            _ => unreachable!("Non empty arcs have included bounds"),
+        };
+        let (a_start, a_end) = ends(self);
+        let (b_start, b_end) = ends(other);
+        self.contains(b_start)
+            || self.contains(b_end)
+            || other.contains(a_start)
+            || other.contains(a_end)
+    }
+
     /// Get the range of the arc
     pub fn range(&self) -> ArcRange {
         if self.half_length == 0 {
@@ -226,6 +277,50 @@ mod tests {
         assert!(DhtArc::new(0, MAX_HALF_LENGTH).contains(MAX_HALF_LENGTH));
     }
 
+    #[test]
+    fn test_arc_for_peer_density() {
+        // Not enough peers to meet the target means holding everything
+        assert_eq!(
+            DhtArc::for_peer_density(0, vec![10, 20], 3).half_length,
+            MAX_HALF_LENGTH
+        );
+        assert_eq!(
+            DhtArc::for_peer_density(0, vec![], 0).half_length,
+            MAX_HALF_LENGTH
+        );
+
+        // Reach out far enough to hold the nearest peers
+        let arc = DhtArc::for_peer_density(0, vec![10, u32::MAX - 4, 100], 2);
+        assert_eq!(arc.half_length, 11);
+        assert!(arc.contains(10));
+        assert!(arc.contains(u32::MAX - 4));
+        assert!(!arc.contains(11));
+        assert!(!arc.contains(100));
+
+        // Peers on top of us still leave room for more than the center
+        let arc = DhtArc::for_peer_density(5, vec![5], 1);
+        assert_eq!(arc.half_length, 2);
+    }
+
+    #[test]
+    fn test_arc_overlaps() {
+        let quarter = (u32::MAX as f64 / 4.0).round() as u32;
+        let half = (u32::MAX as f64 / 2.0).round() as u32;
+
+        assert!(DhtArc::new(0, 10).overlaps(&DhtArc::new(15, 10)));
+        assert!(DhtArc::new(15, 10).overlaps(&DhtArc::new(0, 10)));
+        assert!(!DhtArc::new(0, 10).overlaps(&DhtArc::new(20, 10)));
+        assert!(!DhtArc::new(0, 0).overlaps(&DhtArc::new(0, 10)));
+
+        // Wrapping around zero
+        assert!(DhtArc::new(u32::MAX - 5, 10).overlaps(&DhtArc::new(5, 10)));
+        assert!(!DhtArc::new(half, quarter).overlaps(&DhtArc::new(0, quarter)));
+
+        // One arc inside the other
+        assert!(DhtArc::new(half, 2).overlaps(&DhtArc::new(half, quarter)));
+        assert!(DhtArc::new(0, MAX_HALF_LENGTH).overlaps(&DhtArc::new(half, 1)));
+    }
+
     #[test]
     fn test_arc_start_end() {
         use std::ops::Bound::*;
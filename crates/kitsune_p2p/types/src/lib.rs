@@ -18,6 +18,7 @@ mod auto_stream_select;
 pub use auto_stream_select::*;
 pub mod codec;
 pub mod dht_arc;
+pub mod metrics;
 pub mod transport;
 pub mod transport_mem;
 pub mod transport_pool;
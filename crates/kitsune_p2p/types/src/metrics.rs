@@ -0,0 +1,323 @@
+//! A process wide metrics registry, rendered in the
+//! Prometheus text exposition format.
+//!
+//! Metric families are created with `register` and live for the rest
+//! of the process. Declare them once, e.g. in a `once_cell` `Lazy`,
+//! and record into them from anywhere.
+
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Buckets suited to timing things in seconds.
+pub const SECONDS_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Buckets suited to counting items processed in one go.
+pub const COUNT_BUCKETS: &[f64] = &[0.0, 1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+
+static REGISTRY: Lazy<Mutex<Vec<Arc<dyn Metric>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// A metric family which can render itself in the text exposition format.
+pub trait Metric: 'static + Send + Sync {
+    /// Append the HELP, TYPE and sample lines of this family to `out`.
+    fn render(&self, out: &mut String);
+}
+
+/// Add a metric family to the registry so it is included in [render].
+pub fn register(metric: Arc<dyn Metric>) {
+    REGISTRY.lock().unwrap().push(metric);
+}
+
+/// Render every registered metric family.
+pub fn render() -> String {
+    let mut out = String::new();
+    for metric in REGISTRY.lock().unwrap().iter() {
+        metric.render(&mut out);
+    }
+    out
+}
+
+/// The name, help text and label names of a metric family.
+struct Desc {
+    name: &'static str,
+    help: &'static str,
+    labels: &'static [&'static str],
+}
+
+impl Desc {
+    fn header(&self, kind: &str, out: &mut String) {
+        writeln!(out, "# HELP {} {}", self.name, self.help).unwrap();
+        writeln!(out, "# TYPE {} {}", self.name, kind).unwrap();
+    }
+
+    fn key(&self, values: &[&str]) -> Vec<String> {
+        assert_eq!(
+            values.len(),
+            self.labels.len(),
+            "metric {} takes the labels {:?}",
+            self.name,
+            self.labels
+        );
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    /// Format a label set, with an optional extra label such as `le`.
+    fn labels(&self, values: &[String], extra: Option<(&str, &str)>) -> String {
+        let pairs = self
+            .labels
+            .iter()
+            .copied()
+            .zip(values.iter().map(|v| v.as_str()))
+            .chain(extra)
+            .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+            .collect::<Vec<_>>();
+        if pairs.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", pairs.join(","))
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// A family of counters which only go up, one per set of label values.
+pub struct CounterVec {
+    desc: Desc,
+    values: Mutex<BTreeMap<Vec<String>, u64>>,
+}
+
+impl CounterVec {
+    /// Create a counter family and add it to the registry.
+    pub fn register(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Arc<Self> {
+        let this = Arc::new(Self {
+            desc: Desc { name, help, labels },
+            values: Mutex::new(BTreeMap::new()),
+        });
+        register(this.clone());
+        this
+    }
+
+    /// Add one to the counter for these label values.
+    pub fn inc(&self, labels: &[&str]) {
+        self.inc_by(labels, 1);
+    }
+
+    /// Add `n` to the counter for these label values.
+    pub fn inc_by(&self, labels: &[&str], n: u64) {
+        let key = self.desc.key(labels);
+        *self.values.lock().unwrap().entry(key).or_insert(0) += n;
+    }
+
+    /// The counter for these label values.
+    pub fn get(&self, labels: &[&str]) -> u64 {
+        let key = self.desc.key(labels);
+        self.values.lock().unwrap().get(&key).copied().unwrap_or(0)
+    }
+}
+
+impl Metric for CounterVec {
+    fn render(&self, out: &mut String) {
+        self.desc.header("counter", out);
+        for (key, value) in self.values.lock().unwrap().iter() {
+            writeln!(
+                out,
+                "{}{} {}",
+                self.desc.name,
+                self.desc.labels(key, None),
+                value
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// A family of gauges which can be set to any value, one per set of label values.
+pub struct GaugeVec {
+    desc: Desc,
+    values: Mutex<BTreeMap<Vec<String>, i64>>,
+}
+
+impl GaugeVec {
+    /// Create a gauge family and add it to the registry.
+    pub fn register(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Arc<Self> {
+        let this = Arc::new(Self {
+            desc: Desc { name, help, labels },
+            values: Mutex::new(BTreeMap::new()),
+        });
+        register(this.clone());
+        this
+    }
+
+    /// Set the gauge for these label values.
+    pub fn set(&self, labels: &[&str], value: i64) {
+        let key = self.desc.key(labels);
+        self.values.lock().unwrap().insert(key, value);
+    }
+
+    /// The gauge for these label values.
+    pub fn get(&self, labels: &[&str]) -> i64 {
+        let key = self.desc.key(labels);
+        self.values.lock().unwrap().get(&key).copied().unwrap_or(0)
+    }
+}
+
+impl Metric for GaugeVec {
+    fn render(&self, out: &mut String) {
+        self.desc.header("gauge", out);
+        for (key, value) in self.values.lock().unwrap().iter() {
+            writeln!(
+                out,
+                "{}{} {}",
+                self.desc.name,
+                self.desc.labels(key, None),
+                value
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[derive(Default)]
+struct HistogramData {
+    /// Observations in each bucket, not cumulative.
+    /// The last slot holds observations above the highest bucket.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// A family of histograms with fixed buckets, one per set of label values.
+pub struct HistogramVec {
+    desc: Desc,
+    buckets: &'static [f64],
+    values: Mutex<BTreeMap<Vec<String>, HistogramData>>,
+}
+
+impl HistogramVec {
+    /// Create a histogram family and add it to the registry.
+    /// The buckets are upper bounds and must be in increasing order.
+    pub fn register(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+        buckets: &'static [f64],
+    ) -> Arc<Self> {
+        let this = Arc::new(Self {
+            desc: Desc { name, help, labels },
+            buckets,
+            values: Mutex::new(BTreeMap::new()),
+        });
+        register(this.clone());
+        this
+    }
+
+    /// Record an observation for these label values.
+    pub fn observe(&self, labels: &[&str], value: f64) {
+        let key = self.desc.key(labels);
+        let bucket = self
+            .buckets
+            .iter()
+            .position(|upper| value <= *upper)
+            .unwrap_or_else(|| self.buckets.len());
+        let mut values = self.values.lock().unwrap();
+        let data = values.entry(key).or_default();
+        if data.counts.is_empty() {
+            data.counts = vec![0; self.buckets.len() + 1];
+        }
+        data.counts[bucket] += 1;
+        data.sum += value;
+        data.count += 1;
+    }
+
+    /// Record the time elapsed since `start` in seconds.
+    pub fn observe_since(&self, labels: &[&str], start: std::time::Instant) {
+        self.observe(labels, start.elapsed().as_secs_f64());
+    }
+
+    /// The number of observations for these label values.
+    pub fn count(&self, labels: &[&str]) -> u64 {
+        let key = self.desc.key(labels);
+        self.values
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map(|data| data.count)
+            .unwrap_or(0)
+    }
+}
+
+impl Metric for HistogramVec {
+    fn render(&self, out: &mut String) {
+        let name = self.desc.name;
+        self.desc.header("histogram", out);
+        for (key, data) in self.values.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (upper, count) in self.buckets.iter().zip(data.counts.iter()) {
+                cumulative += count;
+                let le = upper.to_string();
+                let labels = self.desc.labels(key, Some(("le", &le)));
+                writeln!(out, "{}_bucket{} {}", name, labels, cumulative).unwrap();
+            }
+            let labels = self.desc.labels(key, Some(("le", "+Inf")));
+            writeln!(out, "{}_bucket{} {}", name, labels, data.count).unwrap();
+            let labels = self.desc.labels(key, None);
+            writeln!(out, "{}_sum{} {}", name, labels, data.sum).unwrap();
+            writeln!(out, "{}_count{} {}", name, labels, data.count).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_gauges() {
+        let counter = CounterVec::register("test_requests_total", "Requests", &["kind"]);
+        counter.inc(&["call"]);
+        counter.inc_by(&["call"], 2);
+        counter.inc(&["say \"hi\""]);
+        let gauge = GaugeVec::register("test_depth", "Depth", &[]);
+        gauge.set(&[], -4);
+
+        let out = render();
+        assert!(out.contains("# TYPE test_requests_total counter\n"));
+        assert!(out.contains("test_requests_total{kind=\"call\"} 3\n"));
+        assert!(out.contains("test_requests_total{kind=\"say \\\"hi\\\"\"} 1\n"));
+        assert!(out.contains("# TYPE test_depth gauge\ntest_depth -4\n"));
+    }
+
+    #[test]
+    fn renders_cumulative_buckets() {
+        let histogram = HistogramVec::register("test_latency", "Latency", &["fn"], &[0.1, 1.0]);
+        histogram.observe(&["a"], 0.05);
+        histogram.observe(&["a"], 0.5);
+        histogram.observe(&["a"], 5.0);
+        assert_eq!(histogram.count(&["a"]), 3);
+
+        let out = render();
+        assert!(out.contains("test_latency_bucket{fn=\"a\",le=\"0.1\"} 1\n"));
+        assert!(out.contains("test_latency_bucket{fn=\"a\",le=\"1\"} 2\n"));
+        assert!(out.contains("test_latency_bucket{fn=\"a\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_latency_sum{fn=\"a\"} 5.55\n"));
+        assert!(out.contains("test_latency_count{fn=\"a\"} 3\n"));
+    }
+}
@@ -196,3 +196,39 @@ impl<T: TransportListenerSender> TransportListenerSenderExt for T {
         .into()
     }
 }
+
+/// Resolve a `scheme://host:port` url into a socket address
+/// for binding or connecting, preferring ipv4 addresses.
+pub async fn url_to_addr(url: &url2::Url2, scheme: &str) -> TransportResult<std::net::SocketAddr> {
+    if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
+        return Err(format!(
+            "invalid input. got: '{}', expected: '{}://host:port'",
+            url, scheme
+        )
+        .into());
+    }
+
+    let rendered = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+
+    if let Ok(mut iter) = tokio::net::lookup_host(rendered.clone()).await {
+        let mut tmp = iter.next();
+        let mut fallback = None;
+        loop {
+            if tmp.is_none() {
+                break;
+            }
+
+            if tmp.as_ref().unwrap().is_ipv4() {
+                return Ok(tmp.unwrap());
+            }
+
+            fallback = tmp;
+            tmp = iter.next();
+        }
+        if let Some(addr) = fallback {
+            return Ok(addr);
+        }
+    }
+
+    Err(format!("could not parse '{}', as 'host:port'", rendered).into())
+}
@@ -56,6 +56,21 @@ fn get_entry(header_hash: HeaderHash) -> ExternResult<GetOutput> {
     Ok(GetOutput::new(get(header_hash, GetOptions::latest())?))
 }
 
+#[hdk_extern]
+fn list_cap_grants(query: CapQuery) -> ExternResult<CapabilityGrantsOutput> {
+    Ok(CapabilityGrantsOutput::new(capability_grants(query)?))
+}
+
+#[hdk_extern]
+fn list_cap_claims(query: CapQuery) -> ExternResult<CapabilityClaimsOutput> {
+    Ok(CapabilityClaimsOutput::new(capability_claims(query)?))
+}
+
+#[hdk_extern]
+fn cap_info(_: ()) -> ExternResult<CapGrantInfo> {
+    Ok(capability_info()?)
+}
+
 /// accepts a cap claim and commits it to the local chain
 /// intended to
 #[hdk_extern]
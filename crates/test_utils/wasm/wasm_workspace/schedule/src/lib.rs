@@ -0,0 +1,48 @@
+use hdk3::prelude::*;
+use holochain_test_wasm_common::TestInt;
+
+#[hdk_entry(id = "tick")]
+struct Tick;
+
+entry_defs![Tick::entry_def()];
+
+#[hdk_extern]
+fn schedule_tick(after_ms: TestInt) -> ExternResult<()> {
+    schedule(
+        "tick",
+        &(),
+        core::time::Duration::from_millis(after_ms.0 as u64),
+        None,
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+fn schedule_tick_every(every_ms: TestInt) -> ExternResult<()> {
+    schedule(
+        "tick",
+        &(),
+        core::time::Duration::from_millis(0),
+        Some(core::time::Duration::from_millis(every_ms.0 as u64)),
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+fn tick(_: ()) -> ExternResult<HeaderHash> {
+    Ok(create_entry(&Tick)?)
+}
+
+#[hdk_extern]
+fn tick_count(_: ()) -> ExternResult<TestInt> {
+    let ticks = query(
+        QueryFilter::new()
+            .header_type(HeaderType::Create)
+            .include_entries(true),
+    )?
+    .0
+    .into_iter()
+    .filter(|element| matches!(element.entry().as_option(), Some(Entry::App(_))))
+    .count();
+    Ok(TestInt(ticks as u32))
+}
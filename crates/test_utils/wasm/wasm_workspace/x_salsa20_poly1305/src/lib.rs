@@ -12,6 +12,17 @@ fn x_salsa20_poly1305_decrypt(input: XSalsa20Poly1305DecryptInput) -> ExternResu
     Ok(XSalsa20Poly1305DecryptOutput::new(hdk3::prelude::x_salsa20_poly1305_decrypt(decrypt.as_key_ref_ref().to_owned(), decrypt.as_encrypted_data_ref().to_owned())?))
 }
 
+#[hdk_extern]
+fn create_x_salsa20_poly1305_key(_: ()) -> ExternResult<XSalsa20Poly1305KeyRef> {
+    Ok(hdk3::prelude::create_x_salsa20_poly1305_key()?)
+}
+
+#[hdk_extern]
+fn derive_x_salsa20_poly1305_key(input: DeriveXSalsa20Poly1305KeyInput) -> ExternResult<XSalsa20Poly1305KeyRef> {
+    let derive = input.into_inner();
+    Ok(hdk3::prelude::derive_x_salsa20_poly1305_key(derive.as_key_ref_ref().to_owned(), derive.as_context_ref().to_owned())?)
+}
+
 #[hdk_extern]
 fn create_x25519_keypair(_: ()) -> ExternResult<X25519PubKey> {
     Ok(hdk3::prelude::create_x25519_keypair()?)